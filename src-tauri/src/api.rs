@@ -1,7 +1,10 @@
 // src-tauri/src/api.rs
 use serde::{Deserialize, Serialize};
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE, AUTHORIZATION};
+use std::collections::BTreeMap;
+use crate::hand_history::Position;
+use crate::stats_filter::StatsFilter;
 
 // Estructura para las estadísticas del jugador
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -31,6 +34,26 @@ pub struct PlayerStats {
     pub total_manos: String,
     pub bb_100: String,
     pub win_usd: String,
+    // IDs de las manos contadas, si el servidor los envía (para no contarlas dos veces al combinar)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hand_ids: Option<Vec<String>>,
+    // Stats preflop por posición, si el servidor (o el cálculo local) las tiene
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub by_position: Option<BTreeMap<Position, PositionalStats>>,
+}
+
+// Stats preflop de una posición. El robo solo aplica a CO/BTN/SB y
+// fold to steal a las ciegas; en el resto de posiciones valen "-".
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PositionalStats {
+    pub total_manos: String,
+    pub vpip: String,
+    pub pfr: String,
+    pub rfi: String,
+    pub three_bet: String,
+    pub fold_to_3bet_pct: String,
+    pub steal_pct: String,
+    pub fold_to_steal_pct: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -38,8 +61,8 @@ struct ApiError {
     error: String,
 }
 
-// Función para obtener estadísticas del jugador
-pub async fn get_player_stats(nick: String, sala: String, token: String, server_url: String) -> Result<PlayerStats, String> {
+// Función para obtener estadísticas del jugador (el filtro va como parámetros de la consulta)
+pub async fn get_player_stats(nick: String, sala: String, token: String, server_url: String, filter: &StatsFilter) -> Result<PlayerStats, String> {
     let client = Client::new();
     
     let mut headers = HeaderMap::new();
@@ -57,6 +80,7 @@ pub async fn get_player_stats(nick: String, sala: String, token: String, server_
     
     match client.get(&url)
         .headers(headers)
+        .query(filter)
         .send()
         .await {
             Ok(response) => {
@@ -68,6 +92,9 @@ pub async fn get_player_stats(nick: String, sala: String, token: String, server_
                         Ok(stats) => Ok(stats),
                         Err(e) => Err(format!("Error al decodificar respuesta: {}", e)),
                     }
+                } else if status == StatusCode::UNAUTHORIZED {
+                    // Token inválido o expirado: el llamador debe cerrar la sesión
+                    Err(crate::auth::SESSION_EXPIRED_ERROR.to_string())
                 } else {
                     // Intentar obtener mensaje de error de la API
                     match serde_json::from_str::<ApiError>(&text) {
@@ -81,9 +108,7 @@ pub async fn get_player_stats(nick: String, sala: String, token: String, server_
 }
 
 // Función para analizar estadísticas usando GPT
-pub async fn analyze_stats(data: PlayerStats, api_key: String) -> Result<String, String> {
-    let client = Client::new();
-    
+pub async fn analyze_stats(data: PlayerStats, api_key: String, template: Option<String>) -> Result<String, String> {
     // Calcular gap VPIP-PFR
     let vpip = data.vpip.parse::<f64>().unwrap_or(0.0);
     let pfr = data.pfr.parse::<f64>().unwrap_or(0.0);
@@ -103,13 +128,55 @@ pub async fn analyze_stats(data: PlayerStats, api_key: String) -> Result<String,
     // Nombre del jugador para el informe
     let nombre_jugador = &data.player_name;
     
-    // Crear prompt para GPT
-    let prompt = create_analysis_prompt(nombre_jugador, gap_label, &data);
+    // Crear prompt para GPT (con la plantilla del perfil si hay una)
+    let prompt = match template.as_deref().filter(|t| !t.trim().is_empty()) {
+        Some(template) => render_prompt_template(template, nombre_jugador, gap_label, &data),
+        None => create_analysis_prompt(nombre_jugador, gap_label, &data),
+    };
+    
+    let full_response = request_completion(&prompt, &api_key, 300).await?;
+    
+    // Limpiar respuesta
+    let analysis = if full_response.contains("📊 Stats") {
+        full_response.split("📊 Stats").next().unwrap_or(&full_response).trim()
+    } else {
+        &full_response
+    };
     
-    // Verificar si hay una API key válida
+    Ok(analysis.to_string())
+}
+
+// Analiza el informe de las manos propias como lo haría un entrenador
+pub async fn analyze_hero_report(report: String, api_key: String) -> Result<String, String> {
+    let prompt = format!(
+        r#"Eres un entrenador de cash online. Revisa el informe de resultados y stats de tu alumno y responde en español con:
+
+1️⃣ Resumen de resultados (2–3 líneas).
+2️⃣ Los leaks más importantes por orden de impacto, con el ajuste concreto para cada uno.
+3️⃣ Un plan de estudio corto para la próxima semana.
+
+Si la muestra es pequeña (menos de 5000 manos), dilo y trata las conclusiones como preliminares.
+
+---
+
+{}"#,
+        report
+    );
+    request_completion(&prompt, &api_key, 600).await
+}
+
+// Comprueba que hay una API key. Se llama antes de descontar el análisis del límite diario.
+pub fn check_api_key(api_key: &str) -> Result<(), String> {
     if api_key.trim().is_empty() {
         return Err("Se requiere una API key de OpenAI para realizar el análisis".to_string());
     }
+    Ok(())
+}
+
+// Envía un prompt a la API de OpenAI y devuelve el texto generado
+async fn request_completion(prompt: &str, api_key: &str, max_tokens: u32) -> Result<String, String> {
+    let client = Client::new();
+    check_api_key(api_key)?;
 
     // Preparar la solicitud para la API de OpenAI
     let mut headers = HeaderMap::new();
@@ -127,7 +194,7 @@ pub async fn analyze_stats(data: PlayerStats, api_key: String) -> Result<String,
                 "content": prompt
             }
         ],
-        "max_tokens": 300,
+        "max_tokens": max_tokens,
         "temperature": 0.7
     });
     
@@ -148,16 +215,7 @@ pub async fn analyze_stats(data: PlayerStats, api_key: String) -> Result<String,
                             if let Some(choice) = choices.get(0) {
                                 if let Some(message) = choice["message"].as_object() {
                                     if let Some(content) = message["content"].as_str() {
-                                        let full_response = content.trim();
-                                        
-                                        // Limpiar respuesta
-                                        let analysis = if full_response.contains("📊 Stats") {
-                                            full_response.split("📊 Stats").next().unwrap_or(full_response).trim()
-                                        } else {
-                                            full_response
-                                        };
-                                        
-                                        return Ok(analysis.to_string());
+                                        return Ok(content.trim().to_string());
                                     }
                                 }
                             }
@@ -189,6 +247,23 @@ pub async fn analyze_stats(data: PlayerStats, api_key: String) -> Result<String,
     Err("No se pudo obtener respuesta de la API de GPT después de varios intentos".to_string())
 }
 
+// Rellena una plantilla de prompt personalizada. Admite {nombre_jugador}, {gap_label}
+// y cualquier campo de PlayerStats entre llaves, p. ej. {vpip} o {fold_to_3bet_pct}.
+pub fn render_prompt_template(template: &str, nombre_jugador: &str, gap_label: &str, data: &PlayerStats) -> String {
+    let mut prompt = template
+        .replace("{nombre_jugador}", nombre_jugador)
+        .replace("{gap_label}", gap_label);
+    
+    if let Ok(serde_json::Value::Object(fields)) = serde_json::to_value(data) {
+        for (key, value) in fields {
+            let value = value.as_str().unwrap_or("0").to_string();
+            prompt = prompt.replace(&format!("{{{}}}", key), &value);
+        }
+    }
+    
+    prompt
+}
+
 // Función para crear el prompt para análisis con GPT
 fn create_analysis_prompt(nombre_jugador: &str, gap_label: &str, data: &PlayerStats) -> String {
     format!(
@@ -1,7 +1,29 @@
 // src-tauri/src/auth.rs
 use serde::{self, Deserialize, Deserializer, Serialize};
-use reqwest::Client;
-use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use reqwest::{Client, StatusCode};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE, AUTHORIZATION};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use once_cell::sync::Lazy;
+use tauri::Manager;
+use crate::config_state::ConfigState;
+use crate::entitlements;
+use crate::secrets::{self, SecretKind};
+
+// Nombre del evento emitido al frontend cuando la sesión deja de ser válida
+pub const SESSION_EXPIRED_EVENT: &str = "session_expired";
+
+// Mensaje de error usado por la API cuando el servidor responde 401
+pub const SESSION_EXPIRED_ERROR: &str = "Sesión expirada, vuelve a iniciar sesión";
+
+// Margen (en segundos) antes de la expiración en el que se renueva el token
+const REFRESH_MARGIN_SECS: i64 = 300;
+
+// Intervalo (en segundos) entre comprobaciones de expiración del token
+const REFRESH_CHECK_INTERVAL_SECS: u64 = 60;
+
+// Usuario de la sesión activa (solo en memoria)
+static CURRENT_USER: Lazy<Mutex<Option<User>>> = Lazy::new(|| Mutex::new(None));
 
 // Función auxiliar para deserializar campos que pueden ser String o número
 pub fn string_or_number<'de, D>(deserializer: D) -> Result<String, D::Error>
@@ -97,6 +119,13 @@ pub struct AuthResponse {
     pub usuario: User,
 }
 
+// Sesión iniciada que se devuelve al frontend; el token se queda en el backend
+#[derive(Debug, Serialize, Clone)]
+pub struct Session {
+    pub mensaje: Option<String>,
+    pub usuario: User,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct LoginRequest {
     email: String,
@@ -146,13 +175,259 @@ pub async fn login(email: String, password: String, server_url: &str) -> Result<
         }
 }
 
+#[derive(Debug, Deserialize)]
+struct VerifyResponse {
+    usuario: Option<User>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RefreshResponse {
+    token: String,
+    usuario: Option<User>,
+}
+
+// Claims mínimos que nos interesan del payload de un JWT
+#[derive(Debug, Deserialize)]
+struct JwtClaims {
+    exp: Option<i64>,
+}
+
+// Construye las cabeceras con el token de autenticación
+fn auth_headers(token: &str) -> Result<HeaderMap, String> {
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    let auth_value = HeaderValue::from_str(&format!("Bearer {}", token))
+        .map_err(|_| "Token con caracteres no válidos".to_string())?;
+    headers.insert(AUTHORIZATION, auth_value);
+    Ok(headers)
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0)
+}
+
+// Decodifica localmente la fecha de expiración (claim `exp`) de un JWT.
+// Devuelve None si el token no es un JWT o no incluye expiración.
+pub fn decode_token_expiry(token: &str) -> Option<i64> {
+    // Cabecera, contenido y firma
+    let parts: Vec<&str> = token.split('.').collect();
+    let [_, payload, _] = parts[..] else {
+        return None;
+    };
+    let bytes = base64::decode_config(payload.trim_end_matches('='), base64::URL_SAFE_NO_PAD).ok()?;
+    let claims: JwtClaims = serde_json::from_slice(&bytes).ok()?;
+    claims.exp
+}
+
+// Indica si el token expira dentro de `margin_secs` segundos (o ya expiró)
+pub fn is_token_expiring(token: &str, margin_secs: i64) -> bool {
+    match decode_token_expiry(token) {
+        Some(exp) => exp - now_secs() <= margin_secs,
+        None => false,
+    }
+}
+
+// Indica si un error devuelto por la API corresponde a una sesión expirada
+pub fn is_session_expired_error(error: &str) -> bool {
+    error.contains(SESSION_EXPIRED_ERROR)
+}
+
 // Función para verificar si un token es válido
 pub async fn verify_token(token: &str, server_url: &str) -> Result<bool, String> {
     if token.is_empty() {
         return Ok(false);
     }
     
-    // Para una verificación real, deberíamos llamar a un endpoint del servidor
-    // Por ahora, solo devolvemos true si el token no está vacío
-    Ok(true)
-}
\ No newline at end of file
+    // Si el token es un JWT ya expirado no hace falta consultar al servidor
+    if is_token_expiring(token, 0) {
+        return Ok(false);
+    }
+    
+    let client = Client::new();
+    let verify_url = format!("{}/api/auth/verify", server_url);
+    
+    match client.get(&verify_url)
+        .headers(auth_headers(token)?)
+        .send()
+        .await {
+            Ok(response) => {
+                let status = response.status();
+                if status.is_success() {
+                    // Aprovechar la respuesta para refrescar los datos del usuario
+                    if let Ok(verify) = response.json::<VerifyResponse>().await {
+                        if let Some(usuario) = verify.usuario {
+                            set_current_user(Some(usuario));
+                        }
+                    }
+                    Ok(true)
+                } else if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+                    Ok(false)
+                } else {
+                    Err(format!("Error al verificar token: {}", status))
+                }
+            },
+            Err(e) => Err(format!("Error de conexión: {}", e)),
+        }
+}
+
+// Solicita al servidor un token nuevo a partir del actual
+pub async fn refresh_token(token: &str, server_url: &str) -> Result<String, String> {
+    let client = Client::new();
+    let refresh_url = format!("{}/api/auth/refresh", server_url);
+    
+    match client.post(&refresh_url)
+        .headers(auth_headers(token)?)
+        .send()
+        .await {
+            Ok(response) => {
+                let status = response.status();
+                if status.is_success() {
+                    let refresh = response.json::<RefreshResponse>().await
+                        .map_err(|e| format!("Error al decodificar respuesta: {}", e))?;
+                    if let Some(usuario) = refresh.usuario {
+                        set_current_user(Some(usuario));
+                    }
+                    Ok(refresh.token)
+                } else if status == StatusCode::UNAUTHORIZED {
+                    Err(SESSION_EXPIRED_ERROR.to_string())
+                } else {
+                    match response.json::<ApiError>().await {
+                        Ok(api_error) => Err(api_error.error),
+                        Err(_) => Err(format!("Error al renovar token: {}", status)),
+                    }
+                }
+            },
+            Err(e) => Err(format!("Error de conexión: {}", e)),
+        }
+}
+
+// Cierra la sesión en el servidor (si es posible) y en local
+pub async fn logout(token: &str, server_url: &str) -> Result<(), String> {
+    if !token.is_empty() {
+        // El cierre en el servidor es best-effort: la sesión local se limpia igualmente
+        let client = Client::new();
+        let logout_url = format!("{}/api/auth/logout", server_url);
+        if let Ok(headers) = auth_headers(token) {
+            if let Err(e) = client.post(&logout_url).headers(headers).send().await {
+                println!("No se pudo notificar el cierre de sesión al servidor: {}", e);
+            }
+        }
+    }
+    
+    set_current_user(None);
+    Ok(())
+}
+
+// Establece el usuario de la sesión activa y actualiza sus capacidades
+pub fn set_current_user(user: Option<User>) {
+    entitlements::refresh_for_user(user.as_ref());
+    if let Ok(mut current) = CURRENT_USER.lock() {
+        *current = user;
+    }
+}
+
+// Nombre para mostrar del usuario de la sesión activa (nombre o email)
+pub fn current_user_name() -> Option<String> {
+    let current = CURRENT_USER.lock().ok()?;
+    current.as_ref().map(|user| user.nombre.clone().unwrap_or_else(|| user.email.clone()))
+}
+
+// Obtiene el usuario de la sesión activa, verificando el token si no está en memoria
+pub async fn get_current_user(token: &str, server_url: &str) -> Result<Option<User>, String> {
+    if let Ok(current) = CURRENT_USER.lock() {
+        if current.is_some() {
+            return Ok(current.clone());
+        }
+    }
+    
+    if token.is_empty() || !verify_token(token, server_url).await? {
+        return Ok(None);
+    }
+    
+    Ok(CURRENT_USER.lock().map(|current| current.clone()).unwrap_or(None))
+}
+
+// Limpia la sesión local y avisa al frontend de que debe volver a iniciar sesión
+pub fn handle_session_expired<R: tauri::Runtime>(app_handle: &tauri::AppHandle<R>) {
+    set_current_user(None);
+    
+    if let Err(e) = secrets::clear_secret(SecretKind::Token) {
+        eprintln!("Error al limpiar token expirado: {}", e);
+    }
+    
+    let _ = app_handle.emit_all(SESSION_EXPIRED_EVENT, serde_json::json!({
+        "mensaje": SESSION_EXPIRED_ERROR
+    }));
+}
+
+// Inicia una tarea en segundo plano que renueva el token antes de que expire
+pub fn start_token_refresh<R: tauri::Runtime>(app_handle: tauri::AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(REFRESH_CHECK_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            
+            let token = match secrets::get_secret(SecretKind::Token) {
+                Ok(token) => token,
+                Err(_) => continue,
+            };
+            if token.is_empty() || !is_token_expiring(&token, REFRESH_MARGIN_SECS) {
+                continue;
+            }
+            
+            // El token ya expiró: no se puede renovar
+            if is_token_expiring(&token, 0) {
+                handle_session_expired(&app_handle);
+                continue;
+            }
+            
+            let server_url = app_handle.state::<ConfigState>().get().server_url;
+            match refresh_token(&token, &server_url).await {
+                Ok(new_token) => {
+                    if let Err(e) = secrets::set_secret(SecretKind::Token, &new_token) {
+                        eprintln!("Error al guardar token renovado: {}", e);
+                    }
+                },
+                Err(e) if is_session_expired_error(&e) => handle_session_expired(&app_handle),
+                Err(e) => eprintln!("Error al renovar token: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jwt(claims: &str) -> String {
+        let encode = |text: &str| base64::encode_config(text, base64::URL_SAFE_NO_PAD);
+        format!("{}.{}.firma", encode(r#"{"alg":"HS256","typ":"JWT"}"#), encode(claims))
+    }
+
+    #[test]
+    fn decodes_the_expiry_claim() {
+        assert_eq!(decode_token_expiry(&jwt(r#"{"sub":"42","exp":1717171717}"#)), Some(1717171717));
+        assert_eq!(decode_token_expiry(&jwt(r#"{"sub":"42"}"#)), None);
+    }
+
+    #[test]
+    fn malformed_tokens_have_no_expiry() {
+        let valid = jwt(r#"{"exp":1717171717}"#);
+        let payload = valid.split('.').nth(1).unwrap();
+        assert_eq!(decode_token_expiry("token-opaco"), None);
+        assert_eq!(decode_token_expiry(&format!("cabecera.{}", payload)), None);
+        assert_eq!(decode_token_expiry(&format!("{}.extra", valid)), None);
+        assert_eq!(decode_token_expiry("cabecera.no*es*base64.firma"), None);
+        assert_eq!(decode_token_expiry(&jwt("no es json")), None);
+    }
+
+    #[test]
+    fn refreshes_within_the_margin() {
+        let expiring_in = |secs: i64| jwt(&format!(r#"{{"exp":{}}}"#, now_secs() + secs));
+        assert!(is_token_expiring(&expiring_in(-10), 0));
+        assert!(is_token_expiring(&expiring_in(60), REFRESH_MARGIN_SECS));
+        assert!(!is_token_expiring(&expiring_in(3600), REFRESH_MARGIN_SECS));
+        // Sin expiración conocida no se renueva
+        assert!(!is_token_expiring("token-opaco", REFRESH_MARGIN_SECS));
+    }
+}
@@ -0,0 +1,174 @@
+// src-tauri/src/config_backups.rs
+use std::fs;
+use std::path::PathBuf;
+use chrono::{Local, NaiveDateTime, TimeZone};
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::api::path::app_config_dir;
+use crate::settings::{self, AppConfig};
+use crate::config_migrations::LEGACY_SECRET_FIELDS;
+use crate::config_validation;
+use crate::secrets;
+
+// Número de copias de seguridad que se conservan
+pub const MAX_BACKUPS: usize = 10;
+
+const BACKUP_DIR: &str = "backups";
+const BACKUP_PREFIX: &str = "config_";
+const DATE_FORMAT: &str = "%Y%m%d_%H%M%S_%3f";
+
+// Copia de seguridad de config.json disponible para restaurar
+#[derive(Debug, Serialize, Clone)]
+pub struct BackupInfo {
+    pub nombre: String,
+    pub fecha: String,
+    // Motivo de la copia ("" para un guardado normal, "v1" antes de migrar, etc.)
+    pub motivo: String,
+    pub tamano: u64,
+}
+
+fn backup_dir() -> Result<PathBuf, String> {
+    app_config_dir(&tauri::Config::default())
+        .map(|dir| dir.join(BACKUP_DIR))
+        .ok_or_else(|| "No se pudo determinar el directorio de configuración".to_string())
+}
+
+// Fecha y motivo a partir del nombre "config_AAAAMMDD_HHMMSS_mmm[_motivo].json"
+fn parse_backup_name(nombre: &str) -> Option<(NaiveDateTime, String)> {
+    let stem = nombre.strip_prefix(BACKUP_PREFIX)?.strip_suffix(".json")?;
+    // La fecha ocupa siempre 19 caracteres
+    let date_part = stem.get(..19)?;
+    let fecha = NaiveDateTime::parse_from_str(date_part, DATE_FORMAT).ok()?;
+    let motivo = stem.get(19..)?.trim_start_matches('_').to_string();
+    Some((fecha, motivo))
+}
+
+// Contenido de config.json sin los secretos que guardaban las versiones anteriores.
+// Si el archivo no es JSON válido se vacían los valores con una expresión regular.
+fn strip_secrets(content: &str) -> String {
+    if let Ok(Value::Object(mut object)) = serde_json::from_str::<Value>(content) {
+        for (_, field) in LEGACY_SECRET_FIELDS {
+            object.remove(field);
+        }
+        if let Ok(serialized) = serde_json::to_string_pretty(&object) {
+            return serialized;
+        }
+    }
+
+    let mut stripped = content.to_string();
+    for (_, field) in LEGACY_SECRET_FIELDS {
+        let pattern = format!(r#""{}"\s*:\s*"(?:[^"\\]|\\.)*""#, field);
+        if let Ok(re) = Regex::new(&pattern) {
+            stripped = re.replace_all(&stripped, format!(r#""{}": """#, field).as_str()).into_owned();
+        }
+    }
+    stripped
+}
+
+// Copia el config.json actual (sin secretos) a la carpeta de copias y elimina las más antiguas
+pub fn create_backup(motivo: &str) -> Result<Option<PathBuf>, String> {
+    let config_path = settings::config_path()?;
+    if !config_path.exists() {
+        return Ok(None);
+    }
+
+    let dir = backup_dir()?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Error al crear directorio de copias de seguridad: {}", e))?;
+
+    let mut nombre = format!("{}{}", BACKUP_PREFIX, Local::now().format(DATE_FORMAT));
+    if !motivo.is_empty() {
+        nombre.push('_');
+        nombre.push_str(motivo);
+    }
+    let backup_path = dir.join(format!("{}.json", nombre));
+
+    let content = fs::read_to_string(&config_path)
+        .map_err(|e| format!("Error al crear copia de seguridad: {}", e))?;
+    fs::write(&backup_path, strip_secrets(&content))
+        .map_err(|e| format!("Error al crear copia de seguridad: {}", e))?;
+
+    prune_backups()?;
+    Ok(Some(backup_path))
+}
+
+// Elimina las copias que superan MAX_BACKUPS, empezando por las más antiguas
+fn prune_backups() -> Result<(), String> {
+    let dir = backup_dir()?;
+    for backup in list_backups()?.into_iter().skip(MAX_BACKUPS) {
+        if let Err(e) = fs::remove_file(dir.join(&backup.nombre)) {
+            eprintln!("Error al eliminar copia de seguridad antigua {}: {}", backup.nombre, e);
+        }
+    }
+    Ok(())
+}
+
+// Lista las copias de seguridad, de la más reciente a la más antigua
+pub fn list_backups() -> Result<Vec<BackupInfo>, String> {
+    let dir = backup_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(&dir)
+        .map_err(|e| format!("Error al leer copias de seguridad: {}", e))?;
+
+    let mut backups: Vec<(NaiveDateTime, BackupInfo)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let nombre = entry.file_name().to_string_lossy().to_string();
+            let (fecha, motivo) = parse_backup_name(&nombre)?;
+            let tamano = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            let fecha_local = Local.from_local_datetime(&fecha).single()?;
+            Some((fecha, BackupInfo { nombre, fecha: fecha_local.to_rfc3339(), motivo, tamano }))
+        })
+        .collect();
+
+    backups.sort_by_key(|(fecha, _)| std::cmp::Reverse(*fecha));
+    Ok(backups.into_iter().map(|(_, info)| info).collect())
+}
+
+// Lee una copia de seguridad aplicando migraciones y correcciones.
+// No la guarda: el llamador la publica para que se notifique el cambio.
+pub fn read_backup(nombre: &str) -> Result<AppConfig, String> {
+    // Solo se aceptan nombres de la lista, nunca rutas
+    if parse_backup_name(nombre).is_none() || nombre.contains(['/', '\\']) {
+        return Err(format!("Copia de seguridad no válida: '{}'", nombre));
+    }
+
+    let path = backup_dir()?.join(nombre);
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Error al leer copia de seguridad: {}", e))?;
+
+    let mut parsed = settings::parse_config(&content)?;
+    config_validation::sanitize(&mut parsed.config);
+
+    // Las copias anteriores a la migración pueden contener secretos en texto plano
+    if !parsed.legacy_secrets.is_empty() {
+        secrets::import_legacy(parsed.legacy_secrets);
+    }
+
+    Ok(parsed.config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backups_never_keep_legacy_secrets() {
+        let content = r#"{"token": "abc.def", "openai_api_key": "sk-123", "server_url": "https://example.com"}"#;
+        let stripped = strip_secrets(content);
+        assert!(!stripped.contains("abc.def"));
+        assert!(!stripped.contains("sk-123"));
+        assert!(stripped.contains("https://example.com"));
+
+        // Archivo dañado: se conserva el resto del texto para poder revisarlo
+        let corrupt = r#"{"token": "abc\"def", "openai_api_key":"sk-123", "mesas": ["#;
+        let stripped = strip_secrets(corrupt);
+        assert!(!stripped.contains("abc"));
+        assert!(!stripped.contains("sk-123"));
+        assert!(stripped.contains(r#""mesas": ["#));
+    }
+}
@@ -0,0 +1,284 @@
+// src-tauri/src/config_bundle.rs
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::settings::{AppConfig, OcrCoords};
+use crate::profiles::SiteProfile;
+use crate::config_validation::{self, ValidationReport};
+
+// Identificador del formato de archivo y versión actual
+const BUNDLE_FORMAT: &str = "poker-pro-track-settings";
+pub const BUNDLE_VERSION: u32 = 1;
+
+// Paquete de ajustes compartible entre equipos. Nunca incluye secretos
+// (token, API keys) ni ajustes propios de cada máquina.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SettingsBundle {
+    pub formato: String,
+    pub version: u32,
+    pub app_version: String,
+    pub exportado: String,
+    pub contenido: BundleContent,
+}
+
+// Campos que se comparten; los ausentes no se modifican al importar
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct BundleContent {
+    pub ocr_coords: Option<OcrCoords>,
+    pub stats_seleccionadas: Option<HashMap<String, bool>>,
+    pub stats_order: Option<Vec<String>>,
+    pub stats_format: Option<HashMap<String, String>>,
+    pub plantilla_analisis: Option<String>,
+    pub perfiles: Option<Vec<SiteProfile>>,
+}
+
+// Cambio de un campo al importar
+#[derive(Debug, Serialize, Clone)]
+pub struct FieldChange {
+    pub field: String,
+    pub actual: Value,
+    pub nuevo: Value,
+}
+
+// Vista previa de una importación
+#[derive(Debug, Serialize, Clone)]
+pub struct BundlePreview {
+    pub exportado: String,
+    pub app_version: String,
+    pub changes: Vec<FieldChange>,
+    pub validation: ValidationReport,
+}
+
+// Crea un paquete con los ajustes compartibles de la configuración
+pub fn build_bundle(config: &AppConfig) -> SettingsBundle {
+    SettingsBundle {
+        formato: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exportado: chrono::Local::now().to_rfc3339(),
+        contenido: BundleContent {
+            ocr_coords: Some(config.ocr_coords.clone()),
+            stats_seleccionadas: Some(config.stats_seleccionadas.clone()),
+            stats_order: Some(config.stats_order.clone()),
+            stats_format: Some(config.stats_format.clone()),
+            plantilla_analisis: config.plantilla_analisis.clone(),
+            perfiles: Some(config.perfiles.clone()),
+        },
+    }
+}
+
+// Exporta los ajustes compartibles a un archivo
+pub fn export_bundle(config: &AppConfig, path: &str) -> Result<(), String> {
+    let bundle = build_bundle(config);
+    let serialized = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Error al serializar paquete de ajustes: {}", e))?;
+    fs::write(path, serialized)
+        .map_err(|e| format!("Error al escribir paquete de ajustes: {}", e))
+}
+
+// Lee y comprueba un paquete de ajustes
+pub fn read_bundle(path: &str) -> Result<SettingsBundle, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Error al leer paquete de ajustes: {}", e))?;
+    let bundle: SettingsBundle = serde_json::from_str(&content)
+        .map_err(|e| format!("El archivo no es un paquete de ajustes válido: {}", e))?;
+
+    if bundle.formato != BUNDLE_FORMAT {
+        return Err(format!("Formato de paquete desconocido: '{}'", bundle.formato));
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!(
+            "El paquete se creó con una versión más reciente de la app ({}); actualiza antes de importarlo",
+            bundle.app_version
+        ));
+    }
+
+    Ok(bundle)
+}
+
+// Aplica el contenido de un paquete sobre la configuración.
+// Los perfiles se combinan por nombre: los del paquete sustituyen a los homónimos.
+pub fn merge_bundle(config: &AppConfig, content: &BundleContent) -> AppConfig {
+    let mut merged = config.clone();
+
+    if let Some(ocr_coords) = &content.ocr_coords {
+        merged.ocr_coords = ocr_coords.clone();
+    }
+    if let Some(stats_seleccionadas) = &content.stats_seleccionadas {
+        merged.stats_seleccionadas = stats_seleccionadas.clone();
+    }
+    if let Some(stats_order) = &content.stats_order {
+        merged.stats_order = stats_order.clone();
+    }
+    if let Some(stats_format) = &content.stats_format {
+        merged.stats_format = stats_format.clone();
+    }
+    if let Some(plantilla) = &content.plantilla_analisis {
+        merged.plantilla_analisis = Some(plantilla.clone());
+    }
+    if let Some(perfiles) = &content.perfiles {
+        for profile in perfiles {
+            match merged.perfiles.iter_mut().find(|p| p.nombre.eq_ignore_ascii_case(&profile.nombre)) {
+                Some(existing) => *existing = profile.clone(),
+                None => merged.perfiles.push(profile.clone()),
+            }
+        }
+    }
+
+    merged
+}
+
+// Escapa un segmento de ruta para que los nombres con '.' (p. ej. perfiles)
+// no se confundan con el separador: "Stars v1.2" -> "Stars v1\.2"
+fn escape_segment(key: &str) -> String {
+    key.replace('\\', "\\\\").replace('.', "\\.")
+}
+
+// Convierte la configuración en un mapa plano "ruta -> valor" para compararla.
+// Los perfiles se indexan por nombre para que el orden no genere cambios falsos.
+fn flatten_config(config: &AppConfig) -> BTreeMap<String, Value> {
+    let mut value = serde_json::to_value(config).unwrap_or(Value::Null);
+    if let Some(object) = value.as_object_mut() {
+        let perfiles: serde_json::Map<String, Value> = config.perfiles.iter()
+            .map(|p| (p.nombre.clone(), serde_json::to_value(p).unwrap_or(Value::Null)))
+            .collect();
+        object.insert("perfiles".to_string(), Value::Object(perfiles));
+    }
+
+    let mut flat = BTreeMap::new();
+    flatten_value("", &value, &mut flat);
+    flat
+}
+
+fn flatten_value(prefix: &str, value: &Value, flat: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(object) if !object.is_empty() => {
+            for (key, child) in object {
+                let key = escape_segment(key);
+                let path = if prefix.is_empty() { key } else { format!("{}.{}", prefix, key) };
+                flatten_value(&path, child, flat);
+            }
+        },
+        _ => {
+            flat.insert(prefix.to_string(), value.clone());
+        },
+    }
+}
+
+// Lista los campos que cambiarían entre dos configuraciones
+pub fn diff_configs(current: &AppConfig, updated: &AppConfig) -> Vec<FieldChange> {
+    let before = flatten_config(current);
+    let after = flatten_config(updated);
+
+    let mut fields: Vec<&String> = before.keys().chain(after.keys()).collect();
+    fields.sort();
+    fields.dedup();
+
+    fields.into_iter()
+        .filter_map(|field| {
+            let actual = before.get(field).cloned().unwrap_or(Value::Null);
+            let nuevo = after.get(field).cloned().unwrap_or(Value::Null);
+            if actual == nuevo {
+                None
+            } else {
+                Some(FieldChange { field: field.clone(), actual, nuevo })
+            }
+        })
+        .collect()
+}
+
+// Muestra qué cambiaría al importar un paquete, sin aplicar nada
+pub fn preview_bundle(config: &AppConfig, path: &str) -> Result<BundlePreview, String> {
+    let bundle = read_bundle(path)?;
+    let merged = merge_bundle(config, &bundle.contenido);
+
+    Ok(BundlePreview {
+        exportado: bundle.exportado,
+        app_version: bundle.app_version,
+        changes: diff_configs(config, &merged),
+        validation: config_validation::validate(&merged),
+    })
+}
+
+// Importa un paquete y devuelve la configuración resultante sin guardarla.
+// Si se indican `campos` (rutas de la vista previa), solo se aplican esos campos;
+// la granularidad es el campo de primer nivel o el perfil completo.
+pub fn import_bundle(config: &AppConfig, path: &str, campos: Option<Vec<String>>) -> Result<AppConfig, String> {
+    let bundle = read_bundle(path)?;
+    let mut content = bundle.contenido;
+
+    if let Some(campos) = campos {
+        let selected = |field: &str| campos.iter().any(|c| c == field || c.starts_with(&format!("{}.", field)));
+        if !selected("ocr_coords") { content.ocr_coords = None; }
+        if !selected("stats_seleccionadas") { content.stats_seleccionadas = None; }
+        if !selected("stats_order") { content.stats_order = None; }
+        if !selected("stats_format") { content.stats_format = None; }
+        if !selected("plantilla_analisis") { content.plantilla_analisis = None; }
+        content.perfiles = content.perfiles.map(|perfiles| {
+            perfiles.into_iter()
+                .filter(|p| campos.iter().any(|c| c == "perfiles") || selected(&format!("perfiles.{}", escape_segment(&p.nombre))))
+                .collect()
+        });
+    }
+
+    Ok(merge_bundle(config, &content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::parse_config;
+
+    fn profile(nombre: &str, sala: &str) -> SiteProfile {
+        SiteProfile {
+            nombre: nombre.to_string(),
+            sitios: vec!["PokerStars".to_string()],
+            sala: Some(sala.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn bundle_never_contains_secrets() {
+        // Configuración antigua con el token y la API key en texto plano
+        let parsed = parse_config(include_str!("../tests/fixtures/config/v1_full.json")).unwrap();
+        let serialized = serde_json::to_string(&build_bundle(&parsed.config)).unwrap();
+
+        for secret in ["tok-123", "sk-test", "\"token\"", "openai_api_key", "server_url"] {
+            assert!(!serialized.contains(secret), "el paquete incluye {}", secret);
+        }
+    }
+
+    #[test]
+    fn merge_replaces_profiles_by_name_and_diff_lists_changes() {
+        let current = AppConfig {
+            perfiles: vec![profile("Stars", "PS"), profile("Stars v1.2", "PS")],
+            ..Default::default()
+        };
+        let content = BundleContent {
+            stats_order: Some(vec!["vpip".to_string()]),
+            perfiles: Some(vec![profile("Stars v1.2", "PS2"), profile("GG", "GG")]),
+            ..Default::default()
+        };
+
+        let merged = merge_bundle(&current, &content);
+        assert_eq!(merged.perfiles.len(), 3);
+        assert_eq!(merged.perfiles[1].sala.as_deref(), Some("PS2"));
+        assert_eq!(merged.perfiles[0].sala.as_deref(), Some("PS"));
+
+        let changes = diff_configs(&current, &merged);
+        let fields: Vec<&str> = changes.iter().map(|change| change.field.as_str()).collect();
+        assert!(fields.contains(&"stats_order"));
+        assert!(fields.contains(&"perfiles.GG.sala"));
+        // El nombre con '.' se escapa y no se confunde con el perfil "Stars"
+        assert!(fields.contains(&"perfiles.Stars v1\\.2.sala"));
+        assert!(!fields.iter().any(|field| field.starts_with("perfiles.Stars.")));
+        assert!(!fields.contains(&"ocr_coords.x"));
+
+        let sala = changes.iter().find(|change| change.field == "perfiles.Stars v1\\.2.sala").unwrap();
+        assert_eq!(sala.actual, Value::from("PS"));
+        assert_eq!(sala.nuevo, Value::from("PS2"));
+    }
+}
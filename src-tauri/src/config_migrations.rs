@@ -0,0 +1,181 @@
+// src-tauri/src/config_migrations.rs
+use serde_json::{Map, Value};
+use crate::secrets::SecretKind;
+
+// Versión actual del esquema de config.json
+pub const CONFIG_VERSION: u32 = 2;
+
+// Datos que una migración extrae del archivo y que no pertenecen a la configuración
+#[derive(Debug, Default)]
+pub struct MigrationContext {
+    pub legacy_secrets: Vec<(SecretKind, String)>,
+}
+
+// Resultado de migrar un config.json a la versión actual
+#[derive(Debug)]
+pub struct MigrationOutcome {
+    pub value: Value,
+    pub from_version: u32,
+    pub context: MigrationContext,
+}
+
+// Campos de config.json anteriores a v2 que contenían secretos en texto plano
+pub const LEGACY_SECRET_FIELDS: [(SecretKind, &str); 2] = [
+    (SecretKind::Token, "token"),
+    (SecretKind::OpenaiApiKey, "openai_api_key"),
+];
+
+type Migration = fn(&mut Map<String, Value>, &mut MigrationContext);
+
+// Cadena de migraciones: la posición i migra de la versión i+1 a la i+2
+const MIGRATIONS: &[Migration] = &[
+    migrate_v1_to_v2,
+];
+
+// Versión del esquema de un config.json. Los archivos sin campo `version`
+// son de la versión 1 (anterior a la introducción del versionado).
+pub fn detect_version(raw: &Value) -> u32 {
+    raw.get("version")
+        .and_then(|v| v.as_u64())
+        .map(|v| v as u32)
+        .unwrap_or(1)
+}
+
+// Aplica en orden las migraciones necesarias para llevar el archivo a CONFIG_VERSION.
+// Las versiones más nuevas que la actual se devuelven sin modificar.
+pub fn migrate(raw: Value) -> Result<MigrationOutcome, String> {
+    let from_version = detect_version(&raw);
+    let mut object = match raw {
+        Value::Object(object) => object,
+        _ => return Err("La configuración no es un objeto JSON".to_string()),
+    };
+    let mut context = MigrationContext::default();
+
+    if from_version == 0 {
+        return Err("Versión de configuración no válida: 0".to_string());
+    }
+
+    let mut version = from_version;
+    while version < CONFIG_VERSION {
+        let migration = MIGRATIONS[(version - 1) as usize];
+        migration(&mut object, &mut context);
+        version += 1;
+        object.insert("version".to_string(), Value::from(version));
+    }
+
+    Ok(MigrationOutcome {
+        value: Value::Object(object),
+        from_version,
+        context,
+    })
+}
+
+// v1 -> v2: `token` y `openai_api_key` pasan al almacén de secretos
+fn migrate_v1_to_v2(object: &mut Map<String, Value>, context: &mut MigrationContext) {
+    for (kind, field) in LEGACY_SECRET_FIELDS {
+        if let Some(Value::String(value)) = object.remove(field) {
+            if !value.trim().is_empty() {
+                context.legacy_secrets.push((kind, value));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{parse_config, AppConfig};
+
+    const V1_FULL: &str = include_str!("../tests/fixtures/config/v1_full.json");
+    const V1_PARTIAL: &str = include_str!("../tests/fixtures/config/v1_partial.json");
+    const V2_FULL: &str = include_str!("../tests/fixtures/config/v2_full.json");
+
+    #[test]
+    fn detects_unversioned_files_as_v1() {
+        let raw: Value = serde_json::from_str(V1_FULL).unwrap();
+        assert_eq!(detect_version(&raw), 1);
+
+        let raw: Value = serde_json::from_str(V2_FULL).unwrap();
+        assert_eq!(detect_version(&raw), 2);
+    }
+
+    #[test]
+    fn v1_moves_secrets_out_of_the_config() {
+        let parsed = parse_config(V1_FULL).unwrap();
+
+        assert_eq!(parsed.from_version, 1);
+        assert_eq!(parsed.config.version, CONFIG_VERSION);
+        assert_eq!(parsed.legacy_secrets.len(), 2);
+        assert!(parsed.legacy_secrets.contains(&(SecretKind::Token, "tok-123".to_string())));
+        assert!(parsed.legacy_secrets.contains(&(SecretKind::OpenaiApiKey, "sk-test".to_string())));
+
+        let serialized = serde_json::to_value(&parsed.config).unwrap();
+        assert!(serialized.get("token").is_none());
+        assert!(serialized.get("openai_api_key").is_none());
+    }
+
+    #[test]
+    fn v1_keeps_user_preferences() {
+        let parsed = parse_config(V1_FULL).unwrap();
+        let config = parsed.config;
+
+        assert_eq!(config.server_url, "https://stats.example.com");
+        assert_eq!(config.sala_default, "GGPoker");
+        assert_eq!(config.ocr_coords.x, 120);
+        assert_eq!(config.auto_check_interval, 45);
+        assert_eq!(config.tema, "light");
+        assert_eq!(config.stats_order, vec!["pfr", "vpip"]);
+        assert_eq!(config.stats_seleccionadas.get("pfr"), Some(&true));
+        assert_eq!(config.stats_format.get("vpip").map(String::as_str), Some("V:{value}"));
+        assert!(parsed.dropped_fields.is_empty());
+    }
+
+    #[test]
+    fn v1_partial_fills_missing_fields_with_defaults() {
+        let parsed = parse_config(V1_PARTIAL).unwrap();
+        let defaults = AppConfig::default();
+
+        assert_eq!(parsed.config.sala_default, "PokerStars");
+        assert_eq!(parsed.config.hotkey, defaults.hotkey);
+        assert_eq!(parsed.config.idioma_ocr, defaults.idioma_ocr);
+        assert_eq!(parsed.config.ocr_coords.w, defaults.ocr_coords.w);
+        assert_eq!(parsed.config.ocr_coords.x, 10);
+        assert_eq!(parsed.config.stats_order, defaults.stats_order);
+        assert!(parsed.legacy_secrets.is_empty());
+    }
+
+    #[test]
+    fn current_version_is_loaded_unchanged() {
+        let parsed = parse_config(V2_FULL).unwrap();
+
+        assert_eq!(parsed.from_version, CONFIG_VERSION);
+        assert!(parsed.legacy_secrets.is_empty());
+        assert_eq!(parsed.config.server_url, "https://stats.example.com");
+        assert!(parsed.config.modo_automatico);
+    }
+
+    #[test]
+    fn invalid_field_is_dropped_instead_of_resetting_everything() {
+        let content = r#"{ "version": 2, "tema": "light", "auto_check_interval": "rápido" }"#;
+        let parsed = parse_config(content).unwrap();
+
+        assert_eq!(parsed.config.tema, "light");
+        assert_eq!(parsed.config.auto_check_interval, AppConfig::default().auto_check_interval);
+        assert_eq!(parsed.dropped_fields, vec!["auto_check_interval".to_string()]);
+    }
+
+    #[test]
+    fn newer_versions_are_not_downgraded() {
+        let content = r#"{ "version": 99, "tema": "light", "campo_futuro": true }"#;
+        let parsed = parse_config(content).unwrap();
+
+        assert_eq!(parsed.from_version, 99);
+        assert_eq!(parsed.config.tema, "light");
+    }
+
+    #[test]
+    fn rejects_non_object_files() {
+        assert!(parse_config("[1, 2, 3]").is_err());
+        assert!(parse_config("no es json").is_err());
+    }
+}
@@ -0,0 +1,146 @@
+// src-tauri/src/config_state.rs
+use std::fs;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::watch;
+use crate::settings::{self, AppConfig};
+use crate::config_bundle::{self, FieldChange};
+use crate::config_validation::{self, ValidationReport};
+
+// Nombre del evento emitido al frontend cuando cambia la configuración
+pub const CONFIG_CHANGED_EVENT: &str = "config_changed";
+
+// Intervalo de comprobación de cambios en config.json
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+// Configuración en memoria, fuente de verdad para comandos y tareas en segundo plano
+pub struct ConfigState {
+    current: Mutex<AppConfig>,
+    // Canal para que los hilos en segundo plano reciban la configuración nueva
+    sender: watch::Sender<AppConfig>,
+    // Fecha de modificación de config.json tras el último guardado de la app,
+    // para que el vigilante no lo trate como un cambio externo
+    last_saved: Mutex<Option<SystemTime>>,
+}
+
+impl ConfigState {
+    pub fn new(config: AppConfig) -> Self {
+        let (sender, _) = watch::channel(config.clone());
+        ConfigState {
+            current: Mutex::new(config),
+            sender,
+            last_saved: Mutex::new(None),
+        }
+    }
+
+    // Copia de la configuración actual
+    pub fn get(&self) -> AppConfig {
+        match self.current.lock() {
+            Ok(current) => current.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    // Suscripción para los trabajos en segundo plano
+    pub fn subscribe(&self) -> watch::Receiver<AppConfig> {
+        self.sender.subscribe()
+    }
+
+    // Guarda una configuración nueva en disco y la publica
+    pub fn update<R: Runtime>(&self, app_handle: &AppHandle<R>, config: AppConfig) -> Result<Vec<FieldChange>, String> {
+        self.replace(app_handle, config, true, "app")
+    }
+
+    // Valida la configuración recibida del frontend y la guarda si no tiene errores.
+    // Devuelve el informe con los avisos para mostrarlos en la interfaz.
+    pub fn update_validated<R: Runtime>(&self, app_handle: &AppHandle<R>, config: AppConfig) -> Result<ValidationReport, String> {
+        let report = config_validation::validate(&config);
+        if !report.is_valid() {
+            return Err(format!("Configuración no válida: {}", report.error_summary()));
+        }
+
+        self.update(app_handle, config)?;
+        Ok(report)
+    }
+
+    // Sustituye la configuración en memoria y notifica los cambios.
+    // `persist` es false cuando el cambio viene del propio archivo.
+    fn replace<R: Runtime>(&self, app_handle: &AppHandle<R>, config: AppConfig, persist: bool, origen: &str) -> Result<Vec<FieldChange>, String> {
+        let mut current = self.current.lock().map_err(|_| "No se pudo acceder a la configuración")?;
+
+        let changes = config_bundle::diff_configs(&current, &config);
+
+        // Primero la memoria y después el disco: el vigilante siempre compara
+        // con la configuración más reciente. Si no se puede guardar se deshace.
+        let previous = std::mem::replace(&mut *current, config.clone());
+        if persist {
+            if let Err(e) = settings::save_config(config.clone()) {
+                *current = previous;
+                return Err(e);
+            }
+            if let Ok(mut last_saved) = self.last_saved.lock() {
+                *last_saved = config_modified_time();
+            }
+        }
+        drop(current);
+        if changes.is_empty() {
+            return Ok(changes);
+        }
+
+        self.sender.send_replace(config.clone());
+        let _ = app_handle.emit_all(CONFIG_CHANGED_EVENT, serde_json::json!({
+            "origen": origen,
+            "cambios": changes,
+            "config": config
+        }));
+
+        Ok(changes)
+    }
+}
+
+fn config_modified_time() -> Option<SystemTime> {
+    let path = settings::config_path().ok()?;
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+// Vigila config.json y recarga la configuración cuando se modifica fuera de la app
+pub fn start_watcher<R: Runtime>(app_handle: AppHandle<R>) {
+    thread::spawn(move || {
+        let mut last_modified = config_modified_time();
+
+        loop {
+            thread::sleep(WATCH_INTERVAL);
+
+            let modified = config_modified_time();
+            if modified.is_none() || modified == last_modified {
+                continue;
+            }
+            last_modified = modified;
+
+            // Guardado de la propia app: la memoria ya está actualizada
+            let state = app_handle.state::<ConfigState>();
+            if state.last_saved.lock().map(|last_saved| *last_saved == modified).unwrap_or(false) {
+                continue;
+            }
+
+            // Solo se lee: un archivo a medio editar o no válido no se sobrescribe
+            // con valores por defecto, se espera a la siguiente modificación
+            let config = match settings::read_config() {
+                Ok(config) => config,
+                Err(e) => {
+                    eprintln!("Configuración modificada fuera de la app no válida, se ignora: {}", e);
+                    continue;
+                },
+            };
+            match state.replace(&app_handle, config, false, "disco") {
+                Ok(changes) if !changes.is_empty() => {
+                    println!("Configuración recargada desde disco: {} cambios", changes.len());
+                },
+                Ok(_) => {},
+                Err(e) => eprintln!("Error al recargar configuración: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,460 @@
+// src-tauri/src/config_validation.rs
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use serde::Serialize;
+use once_cell::sync::Lazy;
+use reqwest::Url;
+use crate::settings::{AppConfig, OcrCoords, STAT_KEYS};
+use crate::profiles;
+use crate::hero_report;
+
+// Idiomas soportados por el motor OCR (códigos de PaddleOCR)
+const OCR_LANGUAGES: &[&str] = &[
+    "ch", "chinese_cht", "en", "japan", "korean", "es", "fr", "german",
+    "it", "pt", "ru", "ar", "latin", "cyrillic",
+];
+
+const TEMAS: &[&str] = &["dark", "light"];
+
+// Límites razonables para el intervalo del modo automático (segundos)
+const MIN_AUTO_INTERVAL: i32 = 5;
+const MAX_AUTO_INTERVAL: i32 = 3600;
+
+// Problema detectado en un campo concreto de la configuración
+#[derive(Debug, Serialize, Clone)]
+pub struct FieldIssue {
+    pub field: String,
+    pub message: String,
+}
+
+// Resultado de validar una configuración.
+// Los errores impiden guardar; los avisos solo se muestran.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ValidationReport {
+    pub errors: Vec<FieldIssue>,
+    pub warnings: Vec<FieldIssue>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    fn error(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.errors.push(FieldIssue { field: field.into(), message: message.into() });
+    }
+
+    fn warning(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.warnings.push(FieldIssue { field: field.into(), message: message.into() });
+    }
+
+    // Resumen de los errores en una sola línea para los comandos que devuelven String
+    pub fn error_summary(&self) -> String {
+        self.errors.iter()
+            .map(|issue| format!("{}: {}", issue.field, issue.message))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+// Informe de la última carga desde disco, para mostrarlo en la interfaz
+static LAST_LOAD_REPORT: Lazy<Mutex<ValidationReport>> = Lazy::new(|| {
+    Mutex::new(ValidationReport::default())
+});
+
+// Valida una configuración sin modificarla
+pub fn validate(config: &AppConfig) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    validate_ocr_coords(&config.ocr_coords, "ocr_coords", &mut report);
+    validate_server_url(&config.server_url, &mut report);
+    validate_stats(&config.stats_order, &config.stats_seleccionadas, &config.stats_format, "", &mut report);
+    validate_profiles(config, &mut report);
+
+    if config.auto_check_interval < 1 {
+        report.error("auto_check_interval", "El intervalo debe ser de al menos 1 segundo");
+    } else if config.auto_check_interval < MIN_AUTO_INTERVAL {
+        report.warning("auto_check_interval", format!("Un intervalo menor de {} segundos puede saturar el servidor", MIN_AUTO_INTERVAL));
+    } else if config.auto_check_interval > MAX_AUTO_INTERVAL {
+        report.warning("auto_check_interval", "El intervalo es mayor de una hora");
+    }
+
+    if !OCR_LANGUAGES.contains(&config.idioma_ocr.as_str()) {
+        report.error("idioma_ocr", format!("Idioma OCR desconocido: '{}'", config.idioma_ocr));
+    }
+
+    if config.sala_default.trim().is_empty() {
+        report.error("sala_default", "La sala no puede estar vacía");
+    }
+
+    if config.hotkey.trim().is_empty() {
+        report.warning("hotkey", "No hay atajo de teclado configurado");
+    }
+
+    if !TEMAS.contains(&config.tema.as_str()) {
+        report.warning("tema", format!("Tema desconocido: '{}'", config.tema));
+    }
+
+    let filtro = &config.filtro_stats;
+    if filtro.ciega_min.is_some_and(|min| min < 0.0) || filtro.ciega_max.is_some_and(|max| max < 0.0) {
+        report.error("filtro_stats", "Las ciegas del filtro no pueden ser negativas");
+    }
+    if let (Some(min), Some(max)) = (filtro.ciega_min, filtro.ciega_max) {
+        if min > max {
+            report.error("filtro_stats", "La ciega mínima del filtro es mayor que la máxima");
+        }
+    }
+    if let (Some(desde), Some(hasta)) = (filtro.desde, filtro.hasta) {
+        if desde > hasta {
+            report.error("filtro_stats", "La fecha inicial del filtro es posterior a la final");
+        }
+    }
+
+    for (stat, rango) in config.rangos_referencia.iter() {
+        if !hero_report::is_known_stat_key(stat) {
+            report.warning(format!("rangos_referencia.{}", stat), format!("Estadística desconocida: '{}'", stat));
+        }
+        if rango.min > rango.max {
+            report.error(format!("rangos_referencia.{}", stat), "El mínimo es mayor que el máximo");
+        }
+    }
+
+    for (index, carpeta) in config.carpetas_historial.iter().enumerate() {
+        if !std::path::Path::new(carpeta).is_dir() {
+            report.warning(format!("carpetas_historial[{}]", index), format!("La carpeta no existe: '{}'", carpeta));
+        }
+    }
+
+    report
+}
+
+fn validate_ocr_coords(coords: &OcrCoords, prefix: &str, report: &mut ValidationReport) {
+    if coords.x < 0 {
+        report.error(format!("{}.x", prefix), "La coordenada x no puede ser negativa");
+    }
+    if coords.y < 0 {
+        report.error(format!("{}.y", prefix), "La coordenada y no puede ser negativa");
+    }
+    if coords.w <= 0 {
+        report.error(format!("{}.w", prefix), "El ancho debe ser mayor que 0");
+    } else if coords.w < 20 {
+        report.warning(format!("{}.w", prefix), "Un ancho tan pequeño puede cortar el nick");
+    }
+    if coords.h <= 0 {
+        report.error(format!("{}.h", prefix), "El alto debe ser mayor que 0");
+    } else if coords.h < 10 {
+        report.warning(format!("{}.h", prefix), "Un alto tan pequeño puede impedir leer el nick");
+    }
+}
+
+fn validate_server_url(server_url: &str, report: &mut ValidationReport) {
+    match Url::parse(server_url) {
+        Ok(url) => {
+            if url.scheme() != "http" && url.scheme() != "https" {
+                report.error("server_url", "La URL debe empezar por http:// o https://");
+            } else if url.host_str().is_none() {
+                report.error("server_url", "La URL no tiene servidor");
+            } else if url.scheme() == "http" && !matches!(url.host_str(), Some("localhost") | Some("127.0.0.1")) {
+                report.warning("server_url", "La conexión no está cifrada (http)");
+            }
+            if server_url.ends_with('/') {
+                report.warning("server_url", "La URL no debería terminar en '/'");
+            }
+        },
+        Err(e) => report.error("server_url", format!("URL no válida: {}", e)),
+    }
+}
+
+// Valida una disposición de estadísticas; `prefix` es la ruta del perfil ("" para el global)
+fn validate_stats(
+    stats_order: &[String],
+    stats_seleccionadas: &HashMap<String, bool>,
+    stats_format: &HashMap<String, String>,
+    prefix: &str,
+    report: &mut ValidationReport,
+) {
+    let mut seen = HashSet::new();
+    for (i, key) in stats_order.iter().enumerate() {
+        if !STAT_KEYS.contains(&key.as_str()) {
+            report.error(format!("{}stats_order[{}]", prefix, i), format!("Estadística desconocida: '{}'", key));
+        } else if !seen.insert(key) {
+            report.warning(format!("{}stats_order[{}]", prefix, i), format!("Estadística repetida: '{}'", key));
+        }
+    }
+
+    for (key, selected) in stats_seleccionadas {
+        if !STAT_KEYS.contains(&key.as_str()) {
+            report.error(format!("{}stats_seleccionadas.{}", prefix, key), format!("Estadística desconocida: '{}'", key));
+        } else if *selected {
+            if !stats_order.contains(key) {
+                report.warning(format!("{}stats_seleccionadas.{}", prefix, key), "Seleccionada pero no aparece en el orden, no se mostrará");
+            }
+            if !stats_format.contains_key(key) {
+                report.warning(format!("{}stats_format.{}", prefix, key), "Seleccionada pero sin formato, no se mostrará");
+            }
+        }
+    }
+
+    for (key, format) in stats_format {
+        if !STAT_KEYS.contains(&key.as_str()) {
+            report.warning(format!("{}stats_format.{}", prefix, key), format!("Formato para una estadística desconocida: '{}'", key));
+        } else if !format.contains("{value}") {
+            report.warning(format!("{}stats_format.{}", prefix, key), "El formato no incluye {value}");
+        }
+    }
+}
+
+fn validate_profiles(config: &AppConfig, report: &mut ValidationReport) {
+    let known_sites = profiles::known_sites();
+    let mut names = HashSet::new();
+    let mut sites_in_use: HashMap<String, &str> = HashMap::new();
+
+    for (i, profile) in config.perfiles.iter().enumerate() {
+        let prefix = format!("perfiles[{}].", i);
+
+        if profile.nombre.trim().is_empty() {
+            report.error(format!("{}nombre", prefix), "El perfil necesita un nombre");
+        } else if profile.nombre.eq_ignore_ascii_case(profiles::GLOBAL_PROFILE) || !names.insert(profile.nombre.to_lowercase()) {
+            report.error(format!("{}nombre", prefix), format!("Nombre de perfil repetido: '{}'", profile.nombre));
+        }
+
+        if profile.sitios.is_empty() {
+            report.warning(format!("{}sitios", prefix), "El perfil no tiene salas asignadas y nunca se usará");
+        }
+        for (j, site) in profile.sitios.iter().enumerate() {
+            if !known_sites.iter().any(|known| known.eq_ignore_ascii_case(site)) {
+                report.warning(format!("{}sitios[{}]", prefix, j), format!("Sala no reconocida en títulos de ventana: '{}'", site));
+            }
+            if let Some(other) = sites_in_use.insert(site.to_lowercase(), &profile.nombre) {
+                report.warning(format!("{}sitios[{}]", prefix, j), format!("La sala '{}' ya está asignada al perfil '{}'", site, other));
+            }
+        }
+
+        if let Some(sala) = &profile.sala {
+            if sala.trim().is_empty() {
+                report.error(format!("{}sala", prefix), "La sala no puede estar vacía");
+            }
+        }
+        if let Some(coords) = &profile.ocr_coords {
+            validate_ocr_coords(coords, &format!("{}ocr_coords", prefix), report);
+        }
+
+        // Los campos no definidos se heredan del perfil global
+        let overrides_stats = profile.stats_order.is_some()
+            || profile.stats_seleccionadas.is_some()
+            || profile.stats_format.is_some();
+        if !overrides_stats {
+            continue;
+        }
+        validate_stats(
+            profile.stats_order.as_deref().unwrap_or(&config.stats_order),
+            profile.stats_seleccionadas.as_ref().unwrap_or(&config.stats_seleccionadas),
+            profile.stats_format.as_ref().unwrap_or(&config.stats_format),
+            &prefix,
+            report,
+        );
+    }
+}
+
+// Corrige los campos con errores usando los valores por defecto y devuelve el informe original
+pub fn sanitize(config: &mut AppConfig) -> ValidationReport {
+    let report = validate(config);
+    if report.is_valid() {
+        return report;
+    }
+
+    let defaults = AppConfig::default();
+    let mut invalid_profiles = Vec::new();
+    for issue in &report.errors {
+        // Los perfiles con errores se descartan enteros
+        if let Some(index) = profile_index(&issue.field) {
+            invalid_profiles.push(index);
+            continue;
+        }
+
+        let field = issue.field.split(['.', '[']).next().unwrap_or("");
+        match field {
+            "ocr_coords" => config.ocr_coords = defaults.ocr_coords.clone(),
+            "server_url" => config.server_url = defaults.server_url.clone(),
+            "auto_check_interval" => config.auto_check_interval = defaults.auto_check_interval,
+            "idioma_ocr" => config.idioma_ocr = defaults.idioma_ocr.clone(),
+            "sala_default" => config.sala_default = defaults.sala_default.clone(),
+            "filtro_stats" => config.filtro_stats = defaults.filtro_stats.clone(),
+            // Un rango invertido vuelve al valor por defecto de su stat o, si no lo hay, se quita
+            "rangos_referencia" => {
+                let stat = issue.field.strip_prefix("rangos_referencia.").unwrap_or("");
+                match defaults.rangos_referencia.get(stat) {
+                    Some(rango) => { config.rangos_referencia.insert(stat.to_string(), *rango); },
+                    None => { config.rangos_referencia.remove(stat); },
+                }
+            },
+            // En las estadísticas solo se quitan las claves desconocidas
+            "stats_order" => config.stats_order.retain(|key| STAT_KEYS.contains(&key.as_str())),
+            "stats_seleccionadas" => config.stats_seleccionadas.retain(|key, _| STAT_KEYS.contains(&key.as_str())),
+            _ => {},
+        }
+    }
+
+    invalid_profiles.sort_unstable();
+    invalid_profiles.dedup();
+    for index in invalid_profiles.into_iter().rev() {
+        if index < config.perfiles.len() {
+            config.perfiles.remove(index);
+        }
+    }
+
+    report
+}
+
+// Índice del perfil al que pertenece una ruta "perfiles[i]...."
+fn profile_index(field: &str) -> Option<usize> {
+    let rest = field.strip_prefix("perfiles[")?;
+    rest[..rest.find(']')?].parse().ok()
+}
+
+// Guarda el informe de la última carga
+pub fn set_load_report(report: ValidationReport) {
+    if let Ok(mut last) = LAST_LOAD_REPORT.lock() {
+        *last = report;
+    }
+}
+
+// Informe de la última carga desde disco
+pub fn last_load_report() -> ValidationReport {
+    LAST_LOAD_REPORT.lock().map(|report| report.clone()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiles::SiteProfile;
+
+    fn fields(issues: &[FieldIssue]) -> Vec<&str> {
+        issues.iter().map(|issue| issue.field.as_str()).collect()
+    }
+
+    #[test]
+    fn default_config_has_no_errors() {
+        assert!(validate(&AppConfig::default()).is_valid());
+    }
+
+    #[test]
+    fn errors_point_to_the_field_path() {
+        let mut config = AppConfig {
+            stats_order: vec!["vpip".to_string(), "desconocida".to_string()],
+            idioma_ocr: "klingon".to_string(),
+            perfiles: vec![SiteProfile {
+                nombre: " ".to_string(),
+                sitios: vec!["PokerStars".to_string()],
+                ocr_coords: Some(OcrCoords { x: -1, y: 0, w: 100, h: 20 }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        config.ocr_coords.w = 0;
+
+        let report = validate(&config);
+        let errors = fields(&report.errors);
+        assert!(!report.is_valid());
+        for field in ["ocr_coords.w", "stats_order[1]", "idioma_ocr", "perfiles[0].nombre", "perfiles[0].ocr_coords.x"] {
+            assert!(errors.contains(&field), "falta {} en {:?}", field, errors);
+        }
+        assert!(report.error_summary().contains("idioma_ocr: Idioma OCR desconocido: 'klingon'"));
+    }
+
+    #[test]
+    fn warnings_do_not_invalidate_the_config() {
+        let config = AppConfig {
+            auto_check_interval: 2,
+            server_url: "http://example.com/".to_string(),
+            tema: "azul".to_string(),
+            stats_order: vec!["vpip".to_string(), "vpip".to_string()],
+            perfiles: vec![
+                SiteProfile { nombre: "Stars".to_string(), sitios: vec!["PokerStars".to_string()], ..Default::default() },
+                SiteProfile { nombre: "Zoom".to_string(), sitios: vec!["pokerstars".to_string(), "SalaRara".to_string()], ..Default::default() },
+            ],
+            ..Default::default()
+        };
+
+        let report = validate(&config);
+        assert!(report.is_valid(), "{}", report.error_summary());
+        let warnings = fields(&report.warnings);
+        for field in ["auto_check_interval", "server_url", "tema", "stats_order[1]", "perfiles[1].sitios[0]", "perfiles[1].sitios[1]"] {
+            assert!(warnings.contains(&field), "falta {} en {:?}", field, warnings);
+        }
+        // http sin cifrar y la barra final son dos avisos distintos
+        assert_eq!(warnings.iter().filter(|field| **field == "server_url").count(), 2);
+    }
+
+    #[test]
+    fn profile_overrides_are_validated_with_their_prefix() {
+        let config = AppConfig {
+            perfiles: vec![SiteProfile {
+                nombre: "GG".to_string(),
+                sitios: vec!["GGPoker".to_string()],
+                stats_order: Some(vec!["inventada".to_string()]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let report = validate(&config);
+        assert_eq!(fields(&report.errors), ["perfiles[0].stats_order[0]"]);
+    }
+
+    #[test]
+    fn sanitize_resets_invalid_fields_and_drops_invalid_profiles() {
+        let mut config = AppConfig {
+            server_url: "ftp://example.com".to_string(),
+            auto_check_interval: 0,
+            perfiles: vec![
+                SiteProfile { nombre: String::new(), sitios: vec!["XPK".to_string()], ..Default::default() },
+                SiteProfile { nombre: "Stars".to_string(), sitios: vec!["PokerStars".to_string()], ..Default::default() },
+            ],
+            ..Default::default()
+        };
+        config.stats_order.push("desconocida".to_string());
+
+        let report = sanitize(&mut config);
+        assert!(!report.is_valid());
+
+        let defaults = AppConfig::default();
+        assert_eq!(config.server_url, defaults.server_url);
+        assert_eq!(config.auto_check_interval, defaults.auto_check_interval);
+        assert!(!config.stats_order.contains(&"desconocida".to_string()));
+        assert_eq!(config.perfiles.len(), 1);
+        assert_eq!(config.perfiles[0].nombre, "Stars");
+        assert!(validate(&config).is_valid());
+    }
+
+    #[test]
+    fn sanitized_filter_is_valid_on_the_next_load() {
+        let mut config = AppConfig::default();
+        config.filtro_stats.ciega_min = Some(0.5);
+        config.filtro_stats.ciega_max = Some(0.1);
+        config.filtro_stats.desde = chrono::NaiveDate::from_ymd_opt(2024, 5, 1);
+        config.filtro_stats.hasta = chrono::NaiveDate::from_ymd_opt(2024, 1, 1);
+
+        let report = sanitize(&mut config);
+        assert_eq!(fields(&report.errors), ["filtro_stats", "filtro_stats"]);
+        assert_eq!(config.filtro_stats, AppConfig::default().filtro_stats);
+
+        // Una segunda carga ya no tiene nada que corregir (ni copia de seguridad que crear)
+        assert!(sanitize(&mut config).is_valid());
+    }
+
+    #[test]
+    fn sanitize_fixes_reversed_reference_ranges() {
+        let mut config = AppConfig::default();
+        let defaults = config.rangos_referencia.clone();
+        config.rangos_referencia.insert("vpip".to_string(), hero_report::RangoReferencia { min: 40.0, max: 10.0 });
+        config.rangos_referencia.insert("rfi_bb".to_string(), hero_report::RangoReferencia { min: 30.0, max: 20.0 });
+
+        let report = sanitize(&mut config);
+        assert!(fields(&report.errors).contains(&"rangos_referencia.vpip"));
+        assert_eq!(config.rangos_referencia.get("vpip"), defaults.get("vpip"));
+        // Sin valor por defecto el rango se quita
+        assert!(!config.rangos_referencia.contains_key("rfi_bb"));
+        assert!(sanitize(&mut config).is_valid());
+    }
+}
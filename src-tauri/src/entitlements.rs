@@ -0,0 +1,303 @@
+// src-tauri/src/entitlements.rs
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use once_cell::sync::Lazy;
+use thiserror::Error;
+use crate::auth::User;
+
+// Tiempo sin análisis tras el que una mesa deja de contar como "en modo automático"
+const AUTO_TABLE_IDLE: Duration = Duration::from_secs(600);
+
+// Niveles de suscripción
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Tier {
+    Free,
+    Basic,
+    Pro,
+    Premium,
+}
+
+impl Tier {
+    // Interpreta el campo `suscripcion` del usuario devuelto por el servidor
+    pub fn from_suscripcion(suscripcion: Option<&str>) -> Tier {
+        // Se comparan palabras enteras ("pro_mensual" es Pro, "promo" no)
+        let value = suscripcion.unwrap_or("").to_lowercase();
+        let words: Vec<&str> = value.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+        let has = |names: &[&str]| words.iter().any(|word| names.contains(word));
+        if has(&["premium", "elite", "equipo", "team"]) {
+            Tier::Premium
+        } else if has(&["pro"]) {
+            Tier::Pro
+        } else if words.iter().any(|word| word.starts_with("basic") || word.starts_with("básic")) {
+            Tier::Basic
+        } else {
+            Tier::Free
+        }
+    }
+}
+
+impl std::fmt::Display for Tier {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            Tier::Free => "Gratuita",
+            Tier::Basic => "Básica",
+            Tier::Pro => "Pro",
+            Tier::Premium => "Premium",
+        };
+        f.write_str(name)
+    }
+}
+
+// Capacidades de una suscripción (None = sin límite)
+#[derive(Debug, Serialize, Clone)]
+pub struct Entitlements {
+    pub tier: Tier,
+    pub max_auto_tables: Option<u32>,
+    pub gpt_analyses_per_day: Option<u32>,
+    pub history_retention_days: Option<u32>,
+    pub export_enabled: bool,
+}
+
+impl Entitlements {
+    pub fn for_tier(tier: Tier) -> Self {
+        match tier {
+            Tier::Free => Entitlements {
+                tier,
+                max_auto_tables: Some(1),
+                gpt_analyses_per_day: Some(3),
+                history_retention_days: Some(7),
+                export_enabled: false,
+            },
+            Tier::Basic => Entitlements {
+                tier,
+                max_auto_tables: Some(4),
+                gpt_analyses_per_day: Some(20),
+                history_retention_days: Some(30),
+                export_enabled: false,
+            },
+            Tier::Pro => Entitlements {
+                tier,
+                max_auto_tables: Some(12),
+                gpt_analyses_per_day: Some(100),
+                history_retention_days: Some(365),
+                export_enabled: true,
+            },
+            Tier::Premium => Entitlements {
+                tier,
+                max_auto_tables: None,
+                gpt_analyses_per_day: None,
+                history_retention_days: None,
+                export_enabled: true,
+            },
+        }
+    }
+}
+
+// Errores devueltos cuando se usa una función por encima de la suscripción
+#[derive(Error, Debug, Serialize, Clone)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum EntitlementError {
+    #[error("Tu suscripción {tier} permite como máximo {limit} mesas en modo automático")]
+    AutoTablesLimit { tier: Tier, limit: u32 },
+
+    #[error("Has alcanzado el límite de {limit} análisis GPT diarios de tu suscripción {tier}")]
+    DailyAnalysesLimit { tier: Tier, limit: u32 },
+
+    #[error("La exportación no está disponible en la suscripción {tier}")]
+    ExportNotAvailable { tier: Tier },
+
+    #[error("No se pudieron comprobar los límites de la suscripción")]
+    Unavailable,
+}
+
+// Contador de análisis del día (persistido para que no se reinicie al cerrar la app)
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct DailyUsage {
+    date: String,
+    gpt_analyses: u32,
+}
+
+static ENTITLEMENTS: Lazy<Mutex<Entitlements>> = Lazy::new(|| {
+    Mutex::new(Entitlements::for_tier(Tier::Free))
+});
+
+// Mesas que se están analizando en modo automático y su último análisis
+static AUTO_TABLES: Lazy<Mutex<HashMap<u32, Instant>>> = Lazy::new(|| {
+    Mutex::new(HashMap::new())
+});
+
+static USAGE: Lazy<Mutex<DailyUsage>> = Lazy::new(|| Mutex::new(load_usage()));
+
+fn usage_path() -> Option<PathBuf> {
+    tauri::api::path::app_config_dir(&tauri::Config::default()).map(|dir| dir.join("usage.json"))
+}
+
+fn load_usage() -> DailyUsage {
+    usage_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_usage(usage: &DailyUsage) {
+    if let (Some(path), Ok(serialized)) = (usage_path(), serde_json::to_string(usage)) {
+        if let Err(e) = fs::write(&path, serialized) {
+            eprintln!("Error al guardar uso diario: {}", e);
+        }
+    }
+}
+
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+// Recalcula las capacidades a partir del usuario de la sesión (None = sin sesión)
+pub fn refresh_for_user(user: Option<&User>) {
+    let tier = Tier::from_suscripcion(user.and_then(|u| u.suscripcion.as_deref()));
+    if let Ok(mut entitlements) = ENTITLEMENTS.lock() {
+        *entitlements = Entitlements::for_tier(tier);
+    }
+    println!("Suscripción activa: {}", tier);
+}
+
+// Capacidades de la sesión actual
+pub fn current() -> Entitlements {
+    ENTITLEMENTS.lock()
+        .map(|entitlements| entitlements.clone())
+        .unwrap_or_else(|_| Entitlements::for_tier(Tier::Free))
+}
+
+// Registra una mesa en modo automático, fallando si se supera el límite de la suscripción
+pub fn register_auto_table(hwnd: u32) -> Result<(), EntitlementError> {
+    let entitlements = current();
+    let mut tables = AUTO_TABLES.lock().map_err(|_| EntitlementError::Unavailable)?;
+
+    tables.retain(|_, last_seen| last_seen.elapsed() < AUTO_TABLE_IDLE);
+
+    if let Some(limit) = entitlements.max_auto_tables {
+        if !tables.contains_key(&hwnd) && tables.len() as u32 >= limit {
+            return Err(EntitlementError::AutoTablesLimit { tier: entitlements.tier, limit });
+        }
+    }
+
+    tables.insert(hwnd, Instant::now());
+    Ok(())
+}
+
+// Consume un análisis GPT del cupo diario
+pub fn consume_analysis() -> Result<(), EntitlementError> {
+    let entitlements = current();
+    let mut usage = USAGE.lock().map_err(|_| EntitlementError::Unavailable)?;
+
+    let today = today();
+    if usage.date != today {
+        usage.date = today;
+        usage.gpt_analyses = 0;
+    }
+
+    if let Some(limit) = entitlements.gpt_analyses_per_day {
+        if usage.gpt_analyses >= limit {
+            return Err(EntitlementError::DailyAnalysesLimit { tier: entitlements.tier, limit });
+        }
+    }
+
+    usage.gpt_analyses += 1;
+    save_usage(&usage);
+    Ok(())
+}
+
+// Devuelve al cupo diario un análisis consumido que no se llegó a completar
+pub fn refund_analysis() {
+    let Ok(mut usage) = USAGE.lock() else {
+        return;
+    };
+    if usage.date == today() && usage.gpt_analyses > 0 {
+        usage.gpt_analyses -= 1;
+        save_usage(&usage);
+    }
+}
+
+// Análisis GPT restantes hoy (None = sin límite)
+pub fn remaining_analyses() -> Option<u32> {
+    let limit = current().gpt_analyses_per_day?;
+    let used = USAGE.lock()
+        .map(|usage| if usage.date == today() { usage.gpt_analyses } else { 0 })
+        .unwrap_or(0);
+    Some(limit.saturating_sub(used))
+}
+
+// Fecha (UTC) de la mano más antigua visible con la retención de la suscripción
+pub fn retention_cutoff() -> Option<chrono::NaiveDateTime> {
+    let days = current().history_retention_days?;
+    Some(chrono::Utc::now().naive_utc() - chrono::Duration::days(days as i64))
+}
+
+// Comprueba que la suscripción permite exportar
+pub fn require_export() -> Result<(), EntitlementError> {
+    let entitlements = current();
+    if entitlements.export_enabled {
+        Ok(())
+    } else {
+        Err(EntitlementError::ExportNotAvailable { tier: entitlements.tier })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suscripcion_maps_to_tier() {
+        assert_eq!(Tier::from_suscripcion(None), Tier::Free);
+        assert_eq!(Tier::from_suscripcion(Some("")), Tier::Free);
+        assert_eq!(Tier::from_suscripcion(Some("Básica")), Tier::Basic);
+        assert_eq!(Tier::from_suscripcion(Some("basic_mensual")), Tier::Basic);
+        assert_eq!(Tier::from_suscripcion(Some(" PRO ")), Tier::Pro);
+        assert_eq!(Tier::from_suscripcion(Some("Elite")), Tier::Premium);
+        assert_eq!(Tier::from_suscripcion(Some("equipo")), Tier::Premium);
+        assert_eq!(Tier::from_suscripcion(Some("desconocida")), Tier::Free);
+        assert_eq!(Tier::from_suscripcion(Some("pro_mensual")), Tier::Pro);
+        assert_eq!(Tier::from_suscripcion(Some("Plan Pro anual")), Tier::Pro);
+        assert_eq!(Tier::from_suscripcion(Some("promo")), Tier::Free);
+        assert_eq!(Tier::from_suscripcion(Some("prueba-promocional")), Tier::Free);
+        assert_eq!(Tier::from_suscripcion(Some("steam")), Tier::Free);
+    }
+
+    #[test]
+    fn capabilities_grow_with_the_tier() {
+        let free = Entitlements::for_tier(Tier::Free);
+        assert_eq!(free.max_auto_tables, Some(1));
+        assert_eq!(free.gpt_analyses_per_day, Some(3));
+        assert_eq!(free.history_retention_days, Some(7));
+        assert!(!free.export_enabled);
+
+        let basic = Entitlements::for_tier(Tier::Basic);
+        assert_eq!(basic.history_retention_days, Some(30));
+        assert!(!basic.export_enabled);
+
+        let pro = Entitlements::for_tier(Tier::Pro);
+        assert_eq!(pro.max_auto_tables, Some(12));
+        assert!(pro.export_enabled);
+
+        let premium = Entitlements::for_tier(Tier::Premium);
+        assert_eq!(premium.tier, Tier::Premium);
+        assert_eq!(premium.max_auto_tables, None);
+        assert_eq!(premium.gpt_analyses_per_day, None);
+        assert_eq!(premium.history_retention_days, None);
+        assert!(premium.export_enabled);
+    }
+
+    #[test]
+    fn errors_serialize_with_their_code() {
+        let error = EntitlementError::DailyAnalysesLimit { tier: Tier::Free, limit: 3 };
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(value["code"], "daily_analyses_limit");
+        assert_eq!(value["tier"], "free");
+        assert_eq!(value["limit"], 3);
+    }
+}
@@ -0,0 +1,214 @@
+// src-tauri/src/equity/evaluator.rs
+// Evaluador de manos de 5 a 7 cartas con máscaras de bits. Devuelve un valor
+// numérico: a mayor valor, mejor mano; dos manos empatan si el valor es igual.
+
+// Carta como índice 0..52: valor (0 = 2 ... 12 = A) * 4 + palo
+pub type CardIndex = u8;
+
+pub fn rank_of(card: CardIndex) -> u8 {
+    card / 4
+}
+
+pub fn suit_of(card: CardIndex) -> u8 {
+    card % 4
+}
+
+// Categorías de mano, de peor a mejor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Category {
+    HighCard,
+    Pair,
+    TwoPair,
+    Trips,
+    Straight,
+    Flush,
+    FullHouse,
+    Quads,
+    StraightFlush,
+}
+
+const CATEGORY_SHIFT: u32 = 20;
+
+// Valor en construcción: categoría en los bits altos y hasta cinco valores de
+// desempate de 4 bits cada uno, del más al menos importante
+struct Value {
+    bits: u32,
+    shift: u32,
+}
+
+impl Value {
+    fn new(category: Category) -> Value {
+        Value { bits: (category as u32) << CATEGORY_SHIFT, shift: 16 }
+    }
+
+    fn rank(mut self, rank: u8) -> Value {
+        self.bits |= (rank as u32) << self.shift;
+        self.shift = self.shift.saturating_sub(4);
+        self
+    }
+
+    // Añade los `n` valores más altos de la máscara
+    fn top(mut self, mut mask: u16, n: usize) -> Value {
+        for _ in 0..n {
+            let Some(rank) = highest(mask) else {
+                break;
+            };
+            self = self.rank(rank);
+            mask &= !(1 << rank);
+        }
+        self
+    }
+}
+
+fn highest(mask: u16) -> Option<u8> {
+    (mask != 0).then(|| 15 - mask.leading_zeros() as u8)
+}
+
+// Carta más alta de una escalera en la máscara de valores (la rueda A-5 vale 5)
+fn straight_high(mask: u16) -> Option<u8> {
+    for high in (4..=12u8).rev() {
+        let run = 0b11111u16 << (high - 4);
+        if mask & run == run {
+            return Some(high);
+        }
+    }
+    // A-2-3-4-5
+    const WHEEL: u16 = 0b1_0000_0000_1111;
+    if mask & WHEEL == WHEEL {
+        return Some(3);
+    }
+    None
+}
+
+// Evalúa la mejor mano de cinco cartas entre las dadas (de 5 a 7)
+pub fn evaluate(cards: &[CardIndex]) -> u32 {
+    let mut suit_masks = [0u16; 4];
+    let mut counts = [0u8; 13];
+    for &card in cards {
+        suit_masks[suit_of(card) as usize] |= 1 << rank_of(card);
+        counts[rank_of(card) as usize] += 1;
+    }
+    let rank_mask = suit_masks.iter().fold(0, |acc, m| acc | m);
+
+    // Color y escalera de color
+    if let Some(flush_mask) = suit_masks.iter().copied().find(|m| m.count_ones() >= 5) {
+        if let Some(high) = straight_high(flush_mask) {
+            return Value::new(Category::StraightFlush).rank(high).bits;
+        }
+        return Value::new(Category::Flush).top(flush_mask, 5).bits;
+    }
+
+    // Valores agrupados por repeticiones
+    let mut quads = 0u16;
+    let mut trips = 0u16;
+    let mut pairs = 0u16;
+    for (rank, count) in counts.iter().enumerate() {
+        match count {
+            4 => quads |= 1 << rank,
+            3 => trips |= 1 << rank,
+            2 => pairs |= 1 << rank,
+            _ => {},
+        }
+    }
+
+    if let Some(quad) = highest(quads) {
+        return Value::new(Category::Quads).rank(quad).top(rank_mask & !(1 << quad), 1).bits;
+    }
+    if let Some(trip) = highest(trips) {
+        // Con dos tríos, el segundo cuenta como pareja
+        if let Some(pair) = highest((trips & !(1 << trip)) | pairs) {
+            return Value::new(Category::FullHouse).rank(trip).rank(pair).bits;
+        }
+    }
+    if let Some(high) = straight_high(rank_mask) {
+        return Value::new(Category::Straight).rank(high).bits;
+    }
+    if let Some(trip) = highest(trips) {
+        return Value::new(Category::Trips).rank(trip).top(rank_mask & !(1 << trip), 2).bits;
+    }
+    if let Some(high_pair) = highest(pairs) {
+        let rest = pairs & !(1 << high_pair);
+        if let Some(low_pair) = highest(rest) {
+            let used = (1 << high_pair) | (1 << low_pair);
+            return Value::new(Category::TwoPair).rank(high_pair).rank(low_pair).top(rank_mask & !used, 1).bits;
+        }
+        return Value::new(Category::Pair).rank(high_pair).top(rank_mask & !(1 << high_pair), 3).bits;
+    }
+    Value::new(Category::HighCard).top(rank_mask, 5).bits
+}
+
+// Omaha: la mejor mano usando exactamente dos cartas propias y tres de la mesa
+pub fn evaluate_omaha(hole: &[CardIndex], board: &[CardIndex]) -> u32 {
+    let mut best = 0;
+    let mut cards = [0u8; 5];
+    for a in 0..hole.len() {
+        for b in a + 1..hole.len() {
+            cards[0] = hole[a];
+            cards[1] = hole[b];
+            for i in 0..board.len() {
+                for j in i + 1..board.len() {
+                    for k in j + 1..board.len() {
+                        cards[2] = board[i];
+                        cards[3] = board[j];
+                        cards[4] = board[k];
+                        best = best.max(evaluate(&cards));
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equity::{card_index, parse_cards};
+
+    fn value(text: &str) -> u32 {
+        let cards: Vec<CardIndex> = parse_cards(text).unwrap().iter().map(card_index).collect();
+        evaluate(&cards)
+    }
+
+    #[test]
+    fn categories_are_ordered() {
+        let hands = [
+            "Ah Kd 9c 7s 3h",
+            "Ah Ad 9c 7s 3h",
+            "Ah Ad 9c 9s 3h",
+            "Ah Ad Ac 7s 3h",
+            "5h 4d 3c 2s Ah",
+            "6h 4d 3c 2s 5h",
+            "Kh 9h 7h 3h 2h",
+            "Kh Kd Kc 3s 3h",
+            "2h 2d 2c 2s 3h",
+            "Ah Kh Qh Jh Th",
+        ];
+        for pair in hands.windows(2) {
+            assert!(value(pair[0]) < value(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn best_five_of_seven_and_kickers() {
+        // La rueda es la escalera más baja
+        assert!(value("Ah 2d 3c 4s 5h Kd Kc") < value("2d 3c 4s 5h 6d Kd Kc"));
+        // Dos tríos forman full con el mayor
+        assert_eq!(value("Kh Kd Kc 3s 3h 3d 2c"), value("Kh Kd Kc 3s 3h 9d 2c"));
+        // Con pareja en la mesa decide el kicker y la quinta carta iguala
+        assert!(value("Ah Td 9c 9s 5h 4d 2c") > value("Kh Td 9c 9s 5h 4d 2c"));
+        assert_eq!(value("Ah Kd Qc Js 9h 3d 2c"), value("Ah Kd Qc Js 9h 4d 2c"));
+    }
+
+    #[test]
+    fn omaha_uses_exactly_two_hole_cards() {
+        let hole: Vec<CardIndex> = parse_cards("Ah Kh 7c 2d").unwrap().iter().map(card_index).collect();
+        // Cuatro corazones en la mesa: con una sola carta de corazones no hay color
+        let board: Vec<CardIndex> = parse_cards("Qh Jh 9h 3h 8s").unwrap().iter().map(card_index).collect();
+        let with_flush = evaluate_omaha(&hole, &board);
+        assert!(with_flush >= value("Ah Kh Qh Jh 9h"));
+
+        let one_heart: Vec<CardIndex> = parse_cards("Ah Kd 7c 2d").unwrap().iter().map(card_index).collect();
+        assert!(evaluate_omaha(&one_heart, &board) < value("Kh 9h 7h 3h 2h"));
+    }
+}
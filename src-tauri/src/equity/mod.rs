@@ -0,0 +1,367 @@
+// src-tauri/src/equity/mod.rs
+// Equidad de las manos en un all-in: enumeración exacta de los boards posibles
+// cuando es asumible (siempre en heads-up de Hold'em) y Monte Carlo en el resto
+pub mod evaluator;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use crate::hand_history::{Card, Hand, Street, Variant};
+use evaluator::CardIndex;
+
+// Muestras por defecto del Monte Carlo
+pub const DEFAULT_ITERATIONS: u32 = 100_000;
+// Muestras para el EV de los all-in de los informes (muchas manos seguidas)
+const EV_ITERATIONS: u32 = 20_000;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct PlayerEquity {
+    pub cartas: String,
+    // Fracción de boards que gana solo y que empata (0..1)
+    pub gana: f64,
+    pub empata: f64,
+    // Parte del bote que le corresponde de media (0..1)
+    pub equidad: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct EquityResult {
+    pub jugadores: Vec<PlayerEquity>,
+    // true si se enumeraron todos los boards posibles
+    pub exacto: bool,
+    // Boards evaluados
+    pub muestras: u64,
+}
+
+pub fn card_index(card: &Card) -> CardIndex {
+    let suit = match card.suit {
+        'c' => 0,
+        'd' => 1,
+        'h' => 2,
+        _ => 3,
+    };
+    (card.rank - 2) * 4 + suit
+}
+
+// Lee cartas separadas por espacios ("Ah Kd") o seguidas ("AhKd")
+pub fn parse_cards(text: &str) -> Result<Vec<Card>, String> {
+    let compact: String = text.split_whitespace().collect();
+    if !compact.len().is_multiple_of(2) {
+        return Err(format!("Cartas no válidas: '{}'", text));
+    }
+    compact.as_bytes().chunks(2)
+        .map(|chunk| {
+            let card = std::str::from_utf8(chunk).unwrap_or_default();
+            Card::parse(card).ok_or_else(|| format!("Carta no válida: '{}'", card))
+        })
+        .collect()
+}
+
+// Equidad de cada mano con el board y las cartas muertas dados
+pub fn calculate(hands: &[Vec<Card>], board: &[Card], dead: &[Card], iterations: Option<u32>) -> Result<EquityResult, String> {
+    if iterations == Some(0) {
+        return Err("El número de iteraciones debe ser mayor que cero".to_string());
+    }
+    run(hands, board, dead, iterations.unwrap_or(DEFAULT_ITERATIONS), is_heads_up_holdem(hands), &mut StdRng::from_entropy())
+}
+
+// En heads-up de Hold'em se enumeran siempre todos los boards (1.712.304 como mucho)
+fn is_heads_up_holdem(hands: &[Vec<Card>]) -> bool {
+    hands.len() == 2 && hands.iter().all(|h| h.len() == 2)
+}
+
+// Enumera si hay que hacerlo o si hay menos boards posibles que muestras;
+// si no, hace `iterations` muestras aleatorias
+fn run(hands: &[Vec<Card>], board: &[Card], dead: &[Card], iterations: u32, force_exact: bool, rng: &mut StdRng) -> Result<EquityResult, String> {
+    if hands.len() < 2 {
+        return Err("Se necesitan al menos dos manos".to_string());
+    }
+    let hole_cards = hands[0].len();
+    let variant = [Variant::Holdem, Variant::Omaha].into_iter()
+        .find(|v| v.hole_cards() == hole_cards)
+        .ok_or_else(|| format!("Número de cartas por mano no válido: {}", hole_cards))?;
+    if hands.iter().any(|h| h.len() != hole_cards) {
+        return Err("Todas las manos deben tener el mismo número de cartas".to_string());
+    }
+    if board.len() > 5 {
+        return Err("El board no puede tener más de cinco cartas".to_string());
+    }
+
+    let mut used = [false; 52];
+    for card in hands.iter().flatten().chain(board).chain(dead) {
+        let index = card_index(card) as usize;
+        if used[index] {
+            return Err(format!("Carta repetida: {}", card));
+        }
+        used[index] = true;
+    }
+    let deck: Vec<CardIndex> = (0..52u8).filter(|c| !used[*c as usize]).collect();
+    let needed = 5 - board.len();
+    if deck.len() < needed {
+        return Err("No quedan cartas suficientes para completar el board".to_string());
+    }
+
+    let holes: Vec<Vec<CardIndex>> = hands.iter().map(|h| h.iter().map(card_index).collect()).collect();
+    let mut tally = Tally::new(hands.len());
+    let mut full_board: Vec<CardIndex> = board.iter().map(card_index).collect();
+    let known = full_board.len();
+    full_board.resize(5, 0);
+
+    let exact = force_exact || combinations(deck.len() as u64, needed as u64) <= iterations as u64;
+    if exact {
+        let mut indices: Vec<usize> = (0..needed).collect();
+        loop {
+            for (slot, &i) in indices.iter().enumerate() {
+                full_board[known + slot] = deck[i];
+            }
+            tally.add(&holes, &full_board, variant);
+            if !next_combination(&mut indices, deck.len()) {
+                break;
+            }
+        }
+    } else {
+        let mut deck = deck;
+        for _ in 0..iterations {
+            // Fisher-Yates parcial: las primeras `needed` cartas son la muestra
+            for slot in 0..needed {
+                let pick = rng.gen_range(slot..deck.len());
+                deck.swap(slot, pick);
+                full_board[known + slot] = deck[slot];
+            }
+            tally.add(&holes, &full_board, variant);
+        }
+    }
+
+    Ok(tally.result(hands, exact))
+}
+
+// Victorias, empates y parte del bote acumulados por jugador
+struct Tally {
+    wins: Vec<u64>,
+    ties: Vec<u64>,
+    shares: Vec<f64>,
+    boards: u64,
+    values: Vec<u32>,
+}
+
+impl Tally {
+    fn new(players: usize) -> Tally {
+        Tally {
+            wins: vec![0; players],
+            ties: vec![0; players],
+            shares: vec![0.0; players],
+            boards: 0,
+            values: vec![0; players],
+        }
+    }
+
+    fn add(&mut self, holes: &[Vec<CardIndex>], board: &[CardIndex], variant: Variant) {
+        let mut cards = [0u8; 7];
+        cards[2..].copy_from_slice(board);
+        for (value, hole) in self.values.iter_mut().zip(holes) {
+            *value = match variant {
+                Variant::Holdem => {
+                    cards[..2].copy_from_slice(hole);
+                    evaluator::evaluate(&cards)
+                },
+                Variant::Omaha => evaluator::evaluate_omaha(hole, board),
+            };
+        }
+
+        let best = self.values.iter().copied().max().unwrap_or(0);
+        let winners = self.values.iter().filter(|v| **v == best).count();
+        for (i, value) in self.values.iter().enumerate() {
+            if *value == best {
+                if winners == 1 {
+                    self.wins[i] += 1;
+                } else {
+                    self.ties[i] += 1;
+                }
+                self.shares[i] += 1.0 / winners as f64;
+            }
+        }
+        self.boards += 1;
+    }
+
+    fn result(&self, hands: &[Vec<Card>], exact: bool) -> EquityResult {
+        let boards = self.boards.max(1) as f64;
+        EquityResult {
+            jugadores: hands.iter().enumerate()
+                .map(|(i, hand)| PlayerEquity {
+                    cartas: hand.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(" "),
+                    gana: self.wins[i] as f64 / boards,
+                    empata: self.ties[i] as f64 / boards,
+                    equidad: self.shares[i] / boards,
+                })
+                .collect(),
+            exacto: exact,
+            muestras: self.boards,
+        }
+    }
+}
+
+fn combinations(n: u64, k: u64) -> u64 {
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+// Siguiente combinación de índices crecientes en 0..n; false si era la última
+fn next_combination(indices: &mut [usize], n: usize) -> bool {
+    let k = indices.len();
+    for i in (0..k).rev() {
+        if indices[i] < n - k + i {
+            indices[i] += 1;
+            for j in i + 1..k {
+                indices[j] = indices[j - 1] + 1;
+            }
+            return true;
+        }
+    }
+    false
+}
+
+// Semilla del Monte Carlo de una mano (FNV-1a de la sala y el ID), para que su
+// EV no cambie de una consulta a otra
+fn hand_seed(hand: &Hand) -> u64 {
+    format!("{}#{}", hand.site.code(), hand.id).bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3))
+}
+
+// Resultado del jugador ajustado por la equidad si la mano terminó en un all-in
+// antes del river con las cartas de todos los jugadores que seguían a la vista.
+// None si no hubo all-in o no se puede calcular (se usa el resultado real).
+pub fn all_in_adjusted_net(hand: &Hand, player: &str) -> Option<f64> {
+    if !hand.actions.iter().any(|a| a.all_in) || !hand.side_pots.is_empty() {
+        return None;
+    }
+
+    let remaining: Vec<&str> = hand.seats.iter()
+        .filter(|s| !s.sitting_out && !hand.folded(&s.player) && hand.invested(&s.player) > 0)
+        .map(|s| s.player.as_str())
+        .collect();
+    if remaining.len() < 2 || !remaining.contains(&player) {
+        return None;
+    }
+
+    // Cartas en la mesa cuando terminaron las apuestas
+    let last_street = hand.actions.iter().map(|a| a.street).max()?;
+    let visible = match last_street {
+        Street::Preflop => 0,
+        Street::Flop => 3,
+        Street::Turn => 4,
+        Street::River => return None,
+    };
+    if hand.board.len() < visible {
+        return None;
+    }
+
+    let mut hands = Vec::new();
+    for name in remaining.iter() {
+        let cards = if hand.hero.as_deref() == Some(*name) && !hand.hero_cards.is_empty() {
+            hand.hero_cards.clone()
+        } else {
+            hand.showdown.iter().find(|s| s.player == *name && !s.cards.is_empty())?.cards.clone()
+        };
+        hands.push(cards);
+    }
+
+    let mut rng = StdRng::seed_from_u64(hand_seed(hand));
+    let result = run(&hands, &hand.board[..visible], &[], EV_ITERATIONS, is_heads_up_holdem(&hands), &mut rng).ok()?;
+    let index = remaining.iter().position(|name| *name == player)?;
+    let pot: i64 = hand.collected.iter().map(|c| c.amount).sum();
+    Some(result.jugadores[index].equidad * pot as f64 - hand.invested(player) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand_history::fixtures::pokerstars_hands;
+
+    fn hands(texts: &[&str]) -> Vec<Vec<Card>> {
+        texts.iter().map(|text| parse_cards(text).unwrap()).collect()
+    }
+
+    #[test]
+    fn heads_up_preflop_is_enumerated() {
+        let result = calculate(&hands(&["AhAd", "KsKc"]), &[], &[], None).unwrap();
+        assert!(result.exacto);
+        assert_eq!(result.muestras, 1_712_304);
+        let aces = &result.jugadores[0];
+        assert!((aces.equidad - 0.82).abs() < 0.01, "{:?}", aces);
+        assert!((aces.equidad + result.jugadores[1].equidad - 1.0).abs() < 1e-9);
+        assert_eq!(aces.cartas, "Ah Ad");
+    }
+
+    #[test]
+    fn multiway_uses_monte_carlo_and_splits_ties() {
+        let players = hands(&["Ah Kh", "Ad Kd", "2c 2s"]);
+        let result = calculate(&players, &parse_cards("Qs Js Tc").unwrap(), &[], Some(50)).unwrap();
+        assert!(!result.exacto);
+        assert_eq!(result.muestras, 50);
+        let total: f64 = result.jugadores.iter().map(|p| p.equidad).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+
+        // Board completo: las dos escaleras reparten el bote
+        let board = parse_cards("Qs Js Tc 3d 4h").unwrap();
+        let result = calculate(&players, &board, &[], None).unwrap();
+        assert_eq!(result.muestras, 1);
+        assert_eq!(result.jugadores[0].empata, 1.0);
+        assert_eq!(result.jugadores[0].equidad, 0.5);
+        assert_eq!(result.jugadores[2].equidad, 0.0);
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert!(calculate(&hands(&["AhAd"]), &[], &[], None).is_err());
+        assert!(calculate(&hands(&["AhAd", "AhKc"]), &[], &[], None).is_err());
+        assert!(calculate(&hands(&["AhAd", "KsKcQd2c"]), &[], &[], None).is_err());
+        assert!(calculate(&hands(&["AhAd", "KsKc"]), &[], &parse_cards("Kc").unwrap(), None).is_err());
+        assert!(calculate(&hands(&["AhAd", "KsKc", "2c2d"]), &[], &[], Some(0)).is_err());
+        assert!(parse_cards("AhK").is_err());
+        assert!(parse_cards("Ah Xx").is_err());
+    }
+
+    #[test]
+    fn all_in_net_uses_equity_when_called_before_the_river() {
+        let parsed = pokerstars_hands();
+
+        // AK contra trío de jotas en el flop: solo gana con Q y T en turn y river
+        let equity = 16.0 / 990.0;
+        let net = all_in_adjusted_net(&parsed[0], "HeroPlayer").unwrap();
+        assert!((net - (equity * 291.0 - 150.0)).abs() < 1e-6, "{}", net);
+
+        // Sin all-in, o con botes secundarios, se usa el resultado real
+        assert_eq!(all_in_adjusted_net(&parsed[1], "HeroPlayer"), None);
+        assert_eq!(all_in_adjusted_net(&parsed[2], "bb:player"), None);
+    }
+
+    #[test]
+    fn monte_carlo_is_repeatable_for_the_same_hand() {
+        let parsed = pokerstars_hands();
+        assert_eq!(hand_seed(&parsed[0]), hand_seed(&parsed[0].clone()));
+        assert_ne!(hand_seed(&parsed[0]), hand_seed(&parsed[1]));
+
+        let players = hands(&["Ah Kh", "Ad Kd", "2c 2s"]);
+        let flop = parse_cards("Qs Js Tc").unwrap();
+        let sample = || {
+            let result = run(&players, &flop, &[], 500, false, &mut StdRng::seed_from_u64(hand_seed(&parsed[0]))).unwrap();
+            result.jugadores.iter().map(|p| p.equidad).collect::<Vec<f64>>()
+        };
+        assert_eq!(sample(), sample());
+    }
+
+    #[test]
+    fn heads_up_preflop_all_in_is_enumerated() {
+        let mut hand = pokerstars_hands().remove(0);
+        // El mismo all-in, pero antes del flop
+        for action in hand.actions.iter_mut() {
+            action.street = Street::Preflop;
+        }
+        let villain = hand.showdown.iter().find(|s| s.player != "HeroPlayer").unwrap();
+        let exact = calculate(&[hand.hero_cards.clone(), villain.cards.clone()], &[], &[], None).unwrap();
+        assert!(exact.exacto);
+
+        let pot: i64 = hand.collected.iter().map(|c| c.amount).sum();
+        let expected = exact.jugadores[0].equidad * pot as f64 - hand.invested("HeroPlayer") as f64;
+        assert_eq!(all_in_adjusted_net(&hand, "HeroPlayer"), Some(expected));
+    }
+}
@@ -1,4 +1,5 @@
 // src-tauri/src/error.rs
+use serde::{Serialize, Serializer};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -24,8 +25,38 @@ pub enum AppError {
     #[error("Error en la API: {0}")]
     Api(String),
     
+    #[error("{0}")]
+    Entitlement(#[from] crate::entitlements::EntitlementError),
+    
     #[error("Error desconocido: {0}")]
     Unknown(String),
+    
+    #[error("{0}")]
+    Message(String),
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Message(message)
+    }
+}
+
+// Los errores de suscripción se envían al frontend con su código y datos
+// ({ code, tier, limit, message }) para que pueda distinguirlos; el resto como texto
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct EntitlementPayload<'a> {
+            #[serde(flatten)]
+            error: &'a crate::entitlements::EntitlementError,
+            message: String,
+        }
+
+        match self {
+            AppError::Entitlement(error) => EntitlementPayload { error, message: error.to_string() }.serialize(serializer),
+            _ => serializer.serialize_str(&self.to_string()),
+        }
+    }
 }
 
 impl From<AppError> for String {
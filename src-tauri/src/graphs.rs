@@ -0,0 +1,212 @@
+// src-tauri/src/graphs.rs
+// Series para las gráficas de ganancias de las manos propias: ganancia acumulada,
+// línea de EV de los all-in y ganancias con y sin showdown, por mano o por sesión
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use crate::equity;
+use crate::hand_db;
+use crate::hand_history::{Chips, Hand};
+use crate::hero_report;
+use crate::stats_filter::StatsFilter;
+
+// Puntos por defecto de cada serie; con más manos se muestrea para que la
+// gráfica siga siendo fluida en la interfaz
+pub const DEFAULT_MAX_POINTS: usize = 1000;
+
+// Eje X de la gráfica
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphAxis {
+    #[default]
+    Mano,
+    Sesion,
+}
+
+// Valores acumulados hasta una mano o hasta el final de una sesión
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct GraphPoint {
+    // Número de mano o de sesión, empezando en 1
+    pub indice: u32,
+    pub fecha: NaiveDateTime,
+    // Ganancia en centésimas (sumando monedas distintas si las hay) y en ciegas grandes
+    pub ganancia: Chips,
+    pub ganancia_bb: f64,
+    // Ganancia contando los all-in por su equidad
+    pub ev_bb: f64,
+    pub showdown_bb: f64,
+    pub sin_showdown_bb: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct WinningsGraph {
+    pub eje: GraphAxis,
+    pub filtro: String,
+    // Manos incluidas, aunque la serie esté muestreada
+    pub manos: u32,
+    // true si se descartaron puntos para no superar el máximo
+    pub muestreado: bool,
+    pub puntos: Vec<GraphPoint>,
+}
+
+// Gráfica de ganancias de las manos propias de la base de datos local
+pub fn winnings_graph(filter: &StatsFilter, eje: GraphAxis, max_points: Option<usize>) -> Result<WinningsGraph, String> {
+    let hands = hand_db::hero_hands(filter)?;
+    Ok(build_graph(&hands, eje, max_points.unwrap_or(DEFAULT_MAX_POINTS), filter.describe()))
+}
+
+// Construye la gráfica a partir de manos ordenadas por fecha
+pub fn build_graph(hands: &[Hand], eje: GraphAxis, max_points: usize, filtro: String) -> WinningsGraph {
+    let mut total = GraphPoint {
+        indice: 0,
+        fecha: NaiveDateTime::default(),
+        ganancia: 0,
+        ganancia_bb: 0.0,
+        ev_bb: 0.0,
+        showdown_bb: 0.0,
+        sin_showdown_bb: 0.0,
+    };
+    let mut manos = 0;
+    let mut puntos = Vec::new();
+
+    for session in hero_report::split_sessions(hands) {
+        let session_start = manos;
+        for hand in session {
+            if add_hand(&mut total, hand) {
+                manos += 1;
+                if eje == GraphAxis::Mano {
+                    total.indice = manos;
+                    puntos.push(total.clone());
+                }
+            }
+        }
+        if eje == GraphAxis::Sesion && manos > session_start {
+            total.indice += 1;
+            puntos.push(total.clone());
+        }
+    }
+
+    let total_points = puntos.len();
+    let puntos = downsample(puntos, max_points);
+    WinningsGraph {
+        eje,
+        filtro,
+        manos,
+        muestreado: puntos.len() < total_points,
+        puntos,
+    }
+}
+
+// Suma la mano a los acumulados; false si no cuenta (sin hero o sin ciegas)
+fn add_hand(total: &mut GraphPoint, hand: &Hand) -> bool {
+    let Some(hero) = hand.hero.as_deref() else {
+        return false;
+    };
+    if hand.big_blind <= 0 {
+        return false;
+    }
+    let big_blind = hand.big_blind as f64;
+    let net = hand.net(hero);
+    let net_bb = net as f64 / big_blind;
+
+    total.fecha = hand.date;
+    total.ganancia += net;
+    total.ganancia_bb += net_bb;
+    total.ev_bb += equity::all_in_adjusted_net(hand, hero).unwrap_or(net as f64) / big_blind;
+    if hand.went_to_showdown(hero) {
+        total.showdown_bb += net_bb;
+    } else {
+        total.sin_showdown_bb += net_bb;
+    }
+    true
+}
+
+// Deja como mucho `max_points` puntos (al menos 3) tomando uno de cada N y siempre
+// el último y los de ganancia mínima y máxima, para no recortar los picos.
+// Al ser valores acumulados, la forma de la curva se mantiene.
+fn downsample(points: Vec<GraphPoint>, max_points: usize) -> Vec<GraphPoint> {
+    let max_points = max_points.max(3);
+    if points.len() <= max_points {
+        return points;
+    }
+    let by_winnings = |a: &(usize, &GraphPoint), b: &(usize, &GraphPoint)| a.1.ganancia_bb.total_cmp(&b.1.ganancia_bb);
+    let min = points.iter().enumerate().min_by(by_winnings).map_or(0, |(i, _)| i);
+    let max = points.iter().enumerate().max_by(by_winnings).map_or(0, |(i, _)| i);
+
+    // Dos puestos quedan reservados para el mínimo y el máximo
+    let step = points.len().div_ceil(max_points - 2);
+    let last = points.len() - 1;
+    points.into_iter()
+        .enumerate()
+        .filter(|(i, _)| (i + 1) % step == 0 || *i == last || *i == min || *i == max)
+        .map(|(_, point)| point)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use crate::hand_history::fixtures::pokerstars_hands;
+
+    #[test]
+    fn accumulates_by_hand_and_by_session() {
+        let mut hands = pokerstars_hands();
+        hands[2].date += Duration::hours(2);
+
+        let by_hand = build_graph(&hands, GraphAxis::Mano, DEFAULT_MAX_POINTS, String::new());
+        assert_eq!(by_hand.manos, 3);
+        assert!(!by_hand.muestreado);
+        let indices: Vec<u32> = by_hand.puntos.iter().map(|p| p.indice).collect();
+        assert_eq!(indices, [1, 2, 3]);
+
+        // Mano 1: all-in en el flop perdido con showdown; mano 2: gana sin showdown
+        let first = &by_hand.puntos[0];
+        assert_eq!(first.ganancia, -150);
+        assert!((first.showdown_bb + 15.0).abs() < 1e-9);
+        assert!(first.ev_bb > first.ganancia_bb);
+        let second = &by_hand.puntos[1];
+        assert!((second.sin_showdown_bb - 3.5).abs() < 1e-9);
+        assert!((second.showdown_bb + second.sin_showdown_bb - second.ganancia_bb).abs() < 1e-9);
+
+        let by_session = build_graph(&hands, GraphAxis::Sesion, DEFAULT_MAX_POINTS, String::new());
+        assert_eq!(by_session.puntos.len(), 2);
+        assert_eq!(by_session.puntos[1].indice, 2);
+        assert_eq!(by_session.puntos[0].ganancia, by_hand.puntos[1].ganancia);
+        assert_eq!(by_session.puntos[1].ganancia, by_hand.puntos[2].ganancia);
+    }
+
+    #[test]
+    fn downsampling_keeps_the_last_point() {
+        let hands: Vec<Hand> = std::iter::repeat_n(pokerstars_hands().remove(1), 25).collect();
+        let graph = build_graph(&hands, GraphAxis::Mano, 10, String::new());
+        assert!(graph.muestreado);
+        assert_eq!(graph.manos, 25);
+        assert!(graph.puntos.len() <= 10);
+        let last = graph.puntos.last().unwrap();
+        assert_eq!(last.indice, 25);
+        assert_eq!(last.ganancia, 25 * 35);
+    }
+
+    #[test]
+    fn downsampling_keeps_the_lowest_and_highest_points() {
+        let fixture = pokerstars_hands();
+        // Gana 20 manos, pierde 30 y vuelve a ganar 20: mínimo en la mano 50
+        let hands: Vec<Hand> = std::iter::repeat_n(fixture[1].clone(), 20)
+            .chain(std::iter::repeat_n(fixture[0].clone(), 30))
+            .chain(std::iter::repeat_n(fixture[1].clone(), 20))
+            .collect();
+        let full = build_graph(&hands, GraphAxis::Mano, hands.len(), String::new());
+        let lowest = full.puntos.iter().min_by(|a, b| a.ganancia_bb.total_cmp(&b.ganancia_bb)).unwrap();
+        let highest = full.puntos.iter().max_by(|a, b| a.ganancia_bb.total_cmp(&b.ganancia_bb)).unwrap();
+        assert_eq!(lowest.indice, 50);
+
+        for max_points in [1, 3, 7, 16] {
+            let graph = build_graph(&hands, GraphAxis::Mano, max_points, String::new());
+            assert!(graph.muestreado);
+            assert!(graph.puntos.len() <= max_points.max(3), "{}", graph.puntos.len());
+            assert!(graph.puntos.contains(lowest));
+            assert!(graph.puntos.contains(highest));
+            assert_eq!(graph.puntos.last().unwrap().indice, 70);
+        }
+    }
+}
@@ -0,0 +1,673 @@
+// src-tauri/src/hand_db.rs
+// Base de datos local (SQLite) con las manos importadas, los jugadores y sus
+// contadores de stats. Los contadores se actualizan al importar cada mano,
+// así que consultar las stats de un jugador no requiere recalcular nada.
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use crate::api::PlayerStats;
+use crate::entitlements;
+use crate::hand_history::{Hand, Position};
+use crate::local_stats::{self, LocalStats, PositionStats, StatCounter, COUNTER_NAMES, POSITION_COUNTER_NAMES};
+use crate::stats_filter::StatsFilter;
+
+// Formato de played_at; al ser texto ordenable se compara directamente en SQL
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+// Versión del esquema (PRAGMA user_version)
+// v2: contadores por posición (player_position_stats)
+// v3: la sala de las manos se compara sin distinguir mayúsculas
+// v4: bote total de la mano y si cada jugador enseñó sus cartas
+const SCHEMA_VERSION: i32 = 4;
+
+static DB: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| Mutex::new(None));
+
+fn db_path() -> Result<PathBuf, String> {
+    let app_data_dir = tauri::api::path::app_data_dir(&tauri::Config::default())
+        .ok_or("No se pudo determinar el directorio de datos")?;
+    Ok(app_data_dir.join("hands.db"))
+}
+
+fn counter_columns_sql(names: &[&str]) -> String {
+    names.iter()
+        .map(|name| format!("    {0}_veces INTEGER NOT NULL DEFAULT 0,\n    {0}_oportunidades INTEGER NOT NULL DEFAULT 0,\n", name))
+        .collect()
+}
+
+fn hands_table_sql(table: &str) -> String {
+    format!("
+CREATE TABLE IF NOT EXISTS {} (
+    id INTEGER PRIMARY KEY,
+    site TEXT NOT NULL COLLATE NOCASE,
+    hand_id TEXT NOT NULL,
+    played_at TEXT NOT NULL,
+    table_name TEXT NOT NULL,
+    big_blind INTEGER NOT NULL,
+    total_pot INTEGER NOT NULL DEFAULT 0,
+    max_players INTEGER NOT NULL,
+    hero TEXT,
+    data TEXT NOT NULL
+);
+", table)
+}
+
+fn schema_sql() -> String {
+    let counters = counter_columns_sql(&COUNTER_NAMES);
+    let position_counters = counter_columns_sql(&POSITION_COUNTER_NAMES);
+
+    format!("{}
+CREATE UNIQUE INDEX IF NOT EXISTS idx_hands_site_hand_id ON hands(site, hand_id);
+CREATE INDEX IF NOT EXISTS idx_hands_played_at ON hands(played_at);
+
+CREATE TABLE IF NOT EXISTS players (
+    id INTEGER PRIMARY KEY,
+    site TEXT NOT NULL COLLATE NOCASE,
+    nick TEXT NOT NULL COLLATE NOCASE,
+    anonymized INTEGER NOT NULL DEFAULT 0
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_players_site_nick ON players(site, nick);
+
+CREATE TABLE IF NOT EXISTS hand_players (
+    hand_id INTEGER NOT NULL REFERENCES hands(id),
+    player_id INTEGER NOT NULL REFERENCES players(id),
+    seat INTEGER NOT NULL,
+    net INTEGER NOT NULL,
+    showed INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (hand_id, player_id)
+);
+CREATE INDEX IF NOT EXISTS idx_hand_players_player ON hand_players(player_id);
+
+CREATE TABLE IF NOT EXISTS player_stats (
+    player_id INTEGER PRIMARY KEY REFERENCES players(id),
+    total_manos INTEGER NOT NULL DEFAULT 0,
+{}    ganancia INTEGER NOT NULL DEFAULT 0,
+    ganancia_bb REAL NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS player_position_stats (
+    player_id INTEGER NOT NULL REFERENCES players(id),
+    position TEXT NOT NULL,
+    total_manos INTEGER NOT NULL DEFAULT 0,
+{}    PRIMARY KEY (player_id, position)
+);
+", hands_table_sql("hands"), counters, position_counters)
+}
+
+pub(crate) fn init_schema(conn: &Connection) -> Result<(), String> {
+    let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| format!("Error al leer versión de la base de datos: {}", e))?;
+    if version > SCHEMA_VERSION {
+        return Err(format!("La base de datos es de una versión más nueva ({})", version));
+    }
+
+    conn.execute_batch(&schema_sql())
+        .map_err(|e| format!("Error al crear la base de datos: {}", e))?;
+    if version == 1 {
+        backfill_position_stats(conn)?;
+    }
+    if (1..3).contains(&version) {
+        rebuild_hands_table(conn)?;
+    }
+    if (1..4).contains(&version) {
+        backfill_showdown_columns(conn, version)?;
+    }
+    conn.execute_batch(&format!("PRAGMA user_version = {}", SCHEMA_VERSION))
+        .map_err(|e| format!("Error al actualizar la base de datos: {}", e))
+}
+
+// v1 -> v2: calcula los contadores por posición de las manos ya guardadas
+fn backfill_position_stats(conn: &Connection) -> Result<(), String> {
+    let db_error = |e: rusqlite::Error| format!("Error al actualizar la base de datos: {}", e);
+    let tx = conn.unchecked_transaction().map_err(db_error)?;
+    let mut stmt = tx.prepare(
+        "SELECT hp.player_id, p.nick, h.data
+         FROM hand_players hp
+         JOIN players p ON p.id = hp.player_id
+         JOIN hands h ON h.id = hp.hand_id",
+    ).map_err(db_error)?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+    }).map_err(db_error)?;
+
+    for row in rows {
+        let (player_id, nick, data) = row.map_err(db_error)?;
+        let Ok(hand) = serde_json::from_str::<Hand>(&data) else {
+            continue;
+        };
+        let Some(player) = hand.seat_player(&nick) else {
+            continue;
+        };
+        if let Some(stats) = local_stats::hand_stats(&hand, player) {
+            add_position_stats(&tx, player_id, &stats).map_err(db_error)?;
+        }
+    }
+    drop(stmt);
+    tx.commit().map_err(db_error)
+}
+
+// v2 -> v3: SQLite no permite cambiar la intercalación de una columna, así que
+// se copia la tabla de manos (los ID se conservan) y se vuelven a crear los índices
+fn rebuild_hands_table(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(&format!("
+PRAGMA foreign_keys = OFF;
+BEGIN;
+{}
+INSERT INTO hands_v3 (id, site, hand_id, played_at, table_name, big_blind, max_players, hero, data)
+    SELECT id, site, hand_id, played_at, table_name, big_blind, max_players, hero, data FROM hands;
+DROP TABLE hands;
+ALTER TABLE hands_v3 RENAME TO hands;
+COMMIT;
+PRAGMA foreign_keys = ON;
+", hands_table_sql("hands_v3")))
+        .map_err(|e| format!("Error al actualizar la base de datos: {}", e))?;
+    conn.execute_batch(&schema_sql())
+        .map_err(|e| format!("Error al actualizar la base de datos: {}", e))
+}
+
+// v3 -> v4: bote total y cartas enseñadas de las manos ya guardadas, para
+// filtrar los listados en SQL. Desde v1 y v2 la tabla de manos ya se ha
+// vuelto a crear con la columna total_pot.
+fn backfill_showdown_columns(conn: &Connection, version: i32) -> Result<(), String> {
+    let db_error = |e: rusqlite::Error| format!("Error al actualizar la base de datos: {}", e);
+    let tx = conn.unchecked_transaction().map_err(db_error)?;
+    if version == 3 {
+        tx.execute_batch("ALTER TABLE hands ADD COLUMN total_pot INTEGER NOT NULL DEFAULT 0;").map_err(db_error)?;
+    }
+    tx.execute_batch("ALTER TABLE hand_players ADD COLUMN showed INTEGER NOT NULL DEFAULT 0;").map_err(db_error)?;
+
+    let mut stmt = tx.prepare("SELECT id, site, data FROM hands").map_err(db_error)?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+    }).map_err(db_error)?;
+
+    for row in rows {
+        let (hand_row, site, data) = row.map_err(db_error)?;
+        let Ok(hand) = serde_json::from_str::<Hand>(&data) else {
+            continue;
+        };
+        tx.execute("UPDATE hands SET total_pot = ?1 WHERE id = ?2", params![hand.total_pot, hand_row])
+            .map_err(db_error)?;
+        for shown in hand.showdown.iter().filter(|s| !s.cards.is_empty()) {
+            tx.execute(
+                "UPDATE hand_players SET showed = 1
+                 WHERE hand_id = ?1 AND player_id IN (SELECT id FROM players WHERE site = ?2 AND nick = ?3)",
+                params![hand_row, site, shown.player],
+            ).map_err(db_error)?;
+        }
+    }
+    drop(stmt);
+    tx.commit().map_err(db_error)
+}
+
+fn open() -> Result<Connection, String> {
+    let path = db_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Error al crear directorio de datos: {}", e))?;
+    }
+
+    let conn = Connection::open(&path)
+        .map_err(|e| format!("Error al abrir la base de datos: {}", e))?;
+    conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;")
+        .map_err(|e| format!("Error al configurar la base de datos: {}", e))?;
+    init_schema(&conn)?;
+    Ok(conn)
+}
+
+// Ejecuta una función con la conexión abierta
+fn with_db<T>(f: impl FnOnce(&mut Connection) -> Result<T, String>) -> Result<T, String> {
+    let mut guard = DB.lock().map_err(|_| "No se pudo acceder a la base de datos")?;
+    if guard.is_none() {
+        *guard = Some(open()?);
+    }
+    f(guard.as_mut().unwrap())
+}
+
+// Guarda las manos que no estuvieran ya y actualiza los contadores de sus jugadores.
+// Devuelve (nuevas, duplicadas).
+pub fn insert_hands(hands: &[Hand]) -> Result<(usize, usize), String> {
+    with_db(|conn| {
+        let tx = conn.transaction()
+            .map_err(|e| format!("Error al iniciar transacción: {}", e))?;
+        let result = insert_hands_in(&tx, hands)?;
+        tx.commit().map_err(|e| format!("Error al guardar manos: {}", e))?;
+        Ok(result)
+    })
+}
+
+pub(crate) fn insert_hands_in(conn: &Connection, hands: &[Hand]) -> Result<(usize, usize), String> {
+    let db_error = |e: rusqlite::Error| format!("Error al guardar manos: {}", e);
+    let mut inserted = 0;
+
+    for hand in hands {
+        let data = serde_json::to_string(hand)
+            .map_err(|e| format!("Error al serializar mano: {}", e))?;
+        let changed = conn.execute(
+            "INSERT OR IGNORE INTO hands (site, hand_id, played_at, table_name, big_blind, total_pot, max_players, hero, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                hand.site.code(),
+                hand.id,
+                hand.date.format(DATE_FORMAT).to_string(),
+                hand.table,
+                hand.big_blind,
+                hand.total_pot,
+                hand.max_players,
+                hand.hero,
+                data,
+            ],
+        ).map_err(db_error)?;
+        if changed == 0 {
+            continue;
+        }
+        inserted += 1;
+        let hand_row = conn.last_insert_rowid();
+
+        for seat in hand.seats.iter() {
+            let Some(stats) = local_stats::hand_stats(hand, &seat.player) else {
+                continue;
+            };
+            let player_id = player_id(conn, hand.site.code(), &seat.player, seat.anonymized).map_err(db_error)?;
+            conn.execute(
+                "INSERT OR IGNORE INTO hand_players (hand_id, player_id, seat, net, showed) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![hand_row, player_id, seat.number, stats.ganancia, !hand.shown_cards(&seat.player).is_empty()],
+            ).map_err(db_error)?;
+            add_player_stats(conn, player_id, &stats).map_err(db_error)?;
+        }
+    }
+
+    Ok((inserted, hands.len() - inserted))
+}
+
+fn player_id(conn: &Connection, site: &str, nick: &str, anonymized: bool) -> rusqlite::Result<i64> {
+    conn.execute(
+        "INSERT OR IGNORE INTO players (site, nick, anonymized) VALUES (?1, ?2, ?3)",
+        params![site, nick, anonymized],
+    )?;
+    conn.query_row(
+        "SELECT id FROM players WHERE site = ?1 AND nick = ?2",
+        params![site, nick],
+        |row| row.get(0),
+    )
+}
+
+// Suma los contadores de una mano a los acumulados del jugador
+fn add_player_stats(conn: &Connection, player_id: i64, stats: &LocalStats) -> rusqlite::Result<()> {
+    let mut columns = vec![("player_id".to_string(), Value::Integer(player_id))];
+    columns.push(("total_manos".to_string(), Value::Integer(stats.total_manos as i64)));
+    push_counters(&mut columns, &COUNTER_NAMES, &stats.counters());
+    columns.push(("ganancia".to_string(), Value::Integer(stats.ganancia)));
+    columns.push(("ganancia_bb".to_string(), Value::Real(stats.ganancia_bb)));
+    upsert_add(conn, "player_stats", 1, columns)?;
+    add_position_stats(conn, player_id, stats)
+}
+
+fn add_position_stats(conn: &Connection, player_id: i64, stats: &LocalStats) -> rusqlite::Result<()> {
+    for (position, position_stats) in stats.por_posicion.iter() {
+        let mut columns = vec![
+            ("player_id".to_string(), Value::Integer(player_id)),
+            ("position".to_string(), Value::Text(position.label().to_string())),
+            ("total_manos".to_string(), Value::Integer(position_stats.total_manos as i64)),
+        ];
+        push_counters(&mut columns, &POSITION_COUNTER_NAMES, &position_stats.counters());
+        upsert_add(conn, "player_position_stats", 2, columns)?;
+    }
+    Ok(())
+}
+
+fn push_counters(columns: &mut Vec<(String, Value)>, names: &[&str], counters: &[StatCounter]) {
+    for (name, counter) in names.iter().zip(counters) {
+        columns.push((format!("{}_veces", name), Value::Integer(counter.veces as i64)));
+        columns.push((format!("{}_oportunidades", name), Value::Integer(counter.oportunidades as i64)));
+    }
+}
+
+// Inserta la fila o, si ya existe (mismas `key_columns` primeras columnas),
+// suma los valores al resto de columnas
+fn upsert_add(conn: &Connection, table: &str, key_columns: usize, columns: Vec<(String, Value)>) -> rusqlite::Result<()> {
+    let (names, values): (Vec<String>, Vec<Value>) = columns.into_iter().unzip();
+    let placeholders: Vec<String> = (1..=names.len()).map(|i| format!("?{}", i)).collect();
+    let updates: Vec<String> = names.iter().skip(key_columns)
+        .map(|column| format!("{0} = {0} + excluded.{0}", column))
+        .collect();
+    let sql = format!(
+        "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT({}) DO UPDATE SET {}",
+        table,
+        names.join(", "),
+        placeholders.join(", "),
+        names[..key_columns].join(", "),
+        updates.join(", "),
+    );
+    conn.execute(&sql, params_from_iter(values))?;
+    Ok(())
+}
+
+// Número de manos guardadas
+pub fn count() -> Result<usize, String> {
+    with_db(|conn| {
+        conn.query_row("SELECT COUNT(*) FROM hands", [], |row| row.get::<_, i64>(0))
+            .map(|count| count as usize)
+            .map_err(|e| format!("Error al contar manos: {}", e))
+    })
+}
+
+// Fecha mínima de las manos visibles con la retención de la suscripción
+// (cadena vacía = sin límite)
+fn retention_since() -> String {
+    entitlements::retention_cutoff()
+        .map(|cutoff| cutoff.format(DATE_FORMAT).to_string())
+        .unwrap_or_default()
+}
+
+// Contadores acumulados de un jugador (sala y nick sin distinguir mayúsculas)
+pub fn player_stats(sala: &str, nick: &str) -> Result<Option<LocalStats>, String> {
+    // Los contadores incluyen todas las manos; con retención limitada se
+    // recalculan solo con las visibles
+    if entitlements::current().history_retention_days.is_some() {
+        return player_stats_from_hands(sala, nick, &HashSet::new(), &StatsFilter::default());
+    }
+    with_db(|conn| player_stats_in(conn, sala, nick))
+}
+
+pub(crate) fn player_stats_in(conn: &Connection, sala: &str, nick: &str) -> Result<Option<LocalStats>, String> {
+    let counters: String = COUNTER_NAMES.iter()
+        .map(|name| format!("s.{0}_veces, s.{0}_oportunidades, ", name))
+        .collect();
+    let sql = format!(
+        "SELECT p.nick, s.total_manos, {}s.ganancia, s.ganancia_bb
+         FROM players p JOIN player_stats s ON s.player_id = p.id
+         WHERE p.site = ?1 AND p.nick = ?2",
+        counters
+    );
+
+    let db_error = |e: rusqlite::Error| format!("Error al consultar stats locales: {}", e);
+    let stats = conn.query_row(&sql, params![sala.trim(), nick.trim()], |row| {
+        let mut stats = LocalStats {
+            player_name: row.get(0)?,
+            total_manos: row.get(1)?,
+            ..Default::default()
+        };
+        let next = read_counters(row, 2, stats.counters_mut())?;
+        stats.ganancia = row.get(next)?;
+        stats.ganancia_bb = row.get(next + 1)?;
+        Ok(stats)
+    })
+    .optional()
+    .map_err(db_error)?;
+    let Some(mut stats) = stats else {
+        return Ok(None);
+    };
+
+    let counters: String = POSITION_COUNTER_NAMES.iter()
+        .map(|name| format!(", s.{0}_veces, s.{0}_oportunidades", name))
+        .collect();
+    let sql = format!(
+        "SELECT s.position, s.total_manos{}
+         FROM players p JOIN player_position_stats s ON s.player_id = p.id
+         WHERE p.site = ?1 AND p.nick = ?2",
+        counters
+    );
+    let mut stmt = conn.prepare(&sql).map_err(db_error)?;
+    let rows = stmt.query_map(params![sala.trim(), nick.trim()], |row| {
+        let label: String = row.get(0)?;
+        let mut position_stats = PositionStats {
+            total_manos: row.get(1)?,
+            ..Default::default()
+        };
+        read_counters(row, 2, position_stats.counters_mut())?;
+        Ok((label, position_stats))
+    }).map_err(db_error)?;
+    for row in rows {
+        let (label, position_stats) = row.map_err(db_error)?;
+        if let Some(position) = Position::from_label(&label) {
+            stats.por_posicion.insert(position, position_stats);
+        }
+    }
+    Ok(Some(stats))
+}
+
+// Lee pares (veces, oportunidades) desde la columna `first`; devuelve la siguiente columna
+fn read_counters<const N: usize>(row: &rusqlite::Row, first: usize, counters: [&mut StatCounter; N]) -> rusqlite::Result<usize> {
+    let mut column = first;
+    for counter in counters {
+        counter.veces = row.get(column)?;
+        counter.oportunidades = row.get(column + 1)?;
+        column += 2;
+    }
+    Ok(column)
+}
+
+// Stats de un jugador recalculadas a partir de sus manos guardadas, solo con las
+// que cumplen el filtro y sin las indicadas (por ID de mano de la sala)
+pub fn player_stats_from_hands(sala: &str, nick: &str, excluded: &HashSet<String>, filter: &StatsFilter) -> Result<Option<LocalStats>, String> {
+    with_db(|conn| {
+        let db_error = |e: rusqlite::Error| format!("Error al consultar manos del jugador: {}", e);
+        let mut stmt = conn.prepare(
+            "SELECT p.nick, h.hand_id, h.data
+             FROM hands h
+             JOIN hand_players hp ON hp.hand_id = h.id
+             JOIN players p ON p.id = hp.player_id
+             WHERE p.site = ?1 AND p.nick = ?2 AND h.played_at >= ?3
+             ORDER BY h.played_at",
+        ).map_err(db_error)?;
+        let rows = stmt.query_map(params![sala.trim(), nick.trim(), retention_since()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        }).map_err(db_error)?;
+
+        let mut player = None;
+        let mut hands = Vec::new();
+        for row in rows {
+            let (stored_nick, hand_id, data) = row.map_err(db_error)?;
+            let player = player.get_or_insert(stored_nick);
+            if excluded.contains(&hand_id) {
+                continue;
+            }
+            let hand: Hand = serde_json::from_str(&data)
+                .map_err(|e| format!("Error al leer mano {}: {}", hand_id, e))?;
+            if filter.matches(&hand, player) {
+                hands.push(hand);
+            }
+        }
+
+        Ok(player
+            .filter(|_| !hands.is_empty())
+            .map(|player| local_stats::compute(&hands, &player)))
+    })
+}
+
+// Manos propias (con hero) que cumplen el filtro, ordenadas por fecha
+pub fn hero_hands(filter: &StatsFilter) -> Result<Vec<Hand>, String> {
+    with_db(|conn| {
+        let db_error = |e: rusqlite::Error| format!("Error al consultar manos propias: {}", e);
+        let mut stmt = conn.prepare("SELECT data FROM hands WHERE hero IS NOT NULL AND played_at >= ?1 ORDER BY played_at, id")
+            .map_err(db_error)?;
+        let rows = stmt.query_map([retention_since()], |row| row.get::<_, String>(0)).map_err(db_error)?;
+
+        let mut hands = Vec::new();
+        for row in rows {
+            let hand: Hand = serde_json::from_str(&row.map_err(db_error)?)
+                .map_err(|e| format!("Error al leer mano: {}", e))?;
+            if hand.hero.as_deref().is_some_and(|hero| filter.matches(&hand, hero)) {
+                hands.push(hand);
+            }
+        }
+        Ok(hands)
+    })
+}
+
+// Mano guardada por sala e ID de mano de la sala
+pub fn get_hand(sala: &str, hand_id: &str) -> Result<Option<Hand>, String> {
+    with_db(|conn| {
+        let data: Option<String> = conn.query_row(
+            "SELECT data FROM hands WHERE site = ?1 AND hand_id = ?2 AND played_at >= ?3",
+            params![sala.trim(), hand_id.trim(), retention_since()],
+            |row| row.get(0),
+        ).optional().map_err(|e| format!("Error al consultar la mano: {}", e))?;
+
+        data.map(|data| serde_json::from_str(&data).map_err(|e| format!("Error al leer mano {}: {}", hand_id, e)))
+            .transpose()
+    })
+}
+
+// Manos guardadas en las que participa un jugador, de la más reciente a la más antigua,
+// como mucho `limit`. Con `solo_showdown`, solo las que el jugador enseñó sus cartas;
+// con `bote_min_bb`, solo las de un bote de al menos esas ciegas grandes.
+// Devuelve también el nick tal como está guardado.
+pub fn player_hands(sala: &str, nick: &str, solo_showdown: bool, bote_min_bb: Option<f64>, limit: usize) -> Result<Vec<(String, Hand)>, String> {
+    with_db(|conn| player_hands_in(conn, sala, nick, solo_showdown, bote_min_bb, limit, &retention_since()))
+}
+
+pub(crate) fn player_hands_in(
+    conn: &Connection,
+    sala: &str,
+    nick: &str,
+    solo_showdown: bool,
+    bote_min_bb: Option<f64>,
+    limit: usize,
+    since: &str,
+) -> Result<Vec<(String, Hand)>, String> {
+    let db_error = |e: rusqlite::Error| format!("Error al consultar manos del jugador: {}", e);
+    let mut stmt = conn.prepare(
+        "SELECT p.nick, h.hand_id, h.data
+         FROM hands h
+         JOIN hand_players hp ON hp.hand_id = h.id
+         JOIN players p ON p.id = hp.player_id
+         WHERE p.site = ?1 AND p.nick = ?2 AND h.played_at >= ?3 AND h.big_blind > 0
+           AND (?4 = 0 OR hp.showed = 1)
+           AND (?5 IS NULL OR h.total_pot >= ?5 * h.big_blind)
+         ORDER BY h.played_at DESC, h.id DESC
+         LIMIT ?6",
+    ).map_err(db_error)?;
+    let params = params![sala.trim(), nick.trim(), since, solo_showdown, bote_min_bb, limit as i64];
+    let rows = stmt.query_map(params, |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+    }).map_err(db_error)?;
+
+    let mut hands = Vec::new();
+    for row in rows {
+        let (stored_nick, hand_id, data) = row.map_err(db_error)?;
+        let hand: Hand = serde_json::from_str(&data)
+            .map_err(|e| format!("Error al leer mano {}: {}", hand_id, e))?;
+        hands.push((stored_nick, hand));
+    }
+    Ok(hands)
+}
+
+// Stats de un jugador con el mismo formato que api::get_player_stats. Sin filtro
+// se usan los contadores acumulados; con filtro se recalculan desde las manos.
+pub fn get_player_stats(sala: &str, nick: &str, filter: &StatsFilter) -> Result<PlayerStats, String> {
+    let stats = if filter.is_empty() {
+        player_stats(sala, nick)?
+    } else {
+        player_stats_from_hands(sala, nick, &HashSet::new(), filter)?
+    };
+    stats
+        .map(|stats| stats.to_player_stats())
+        .ok_or_else(|| format!("No hay manos de {} en {} en la base de datos local", nick, sala))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand_history::fixtures::all_hands;
+
+    fn memory_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        conn
+    }
+
+    fn hand_count(conn: &Connection, site: &str) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM hands WHERE site = ?1", [site], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn incremental_counters_match_full_computation() {
+        let hands = all_hands();
+        let conn = memory_db();
+        assert_eq!(insert_hands_in(&conn, &hands).unwrap(), (hands.len(), 0));
+
+        let mut checked = 0;
+        for hand in hands.iter() {
+            let site_hands: Vec<Hand> = hands.iter().filter(|h| h.site == hand.site).cloned().collect();
+            for seat in hand.seats.iter() {
+                let expected = local_stats::compute(&site_hands, &seat.player);
+                let stored = player_stats_in(&conn, hand.site.code(), &seat.player).unwrap();
+                if expected.total_manos == 0 {
+                    assert!(stored.is_none(), "{}", seat.player);
+                    continue;
+                }
+                assert_eq!(stored.as_ref(), Some(&expected), "{} en {}", seat.player, hand.site.code());
+                checked += 1;
+            }
+        }
+        assert!(checked > 10);
+    }
+
+    #[test]
+    fn reimport_skips_duplicates_without_counting_twice() {
+        let hands = all_hands();
+        let conn = memory_db();
+        insert_hands_in(&conn, &hands[..2]).unwrap();
+        let hero = hands[0].hero.clone().unwrap();
+        let before = player_stats_in(&conn, hands[0].site.code(), &hero).unwrap();
+
+        assert_eq!(insert_hands_in(&conn, &hands[..2]).unwrap(), (0, 2));
+        assert_eq!(player_stats_in(&conn, hands[0].site.code(), &hero).unwrap(), before);
+
+        // Solo se añaden las que faltaban
+        assert_eq!(insert_hands_in(&conn, &hands).unwrap(), (hands.len() - 2, 2));
+        let total: i64 = conn.query_row("SELECT COUNT(*) FROM hands", [], |row| row.get(0)).unwrap();
+        assert_eq!(total as usize, hands.len());
+    }
+
+    #[test]
+    fn site_and_nick_ignore_case() {
+        let hands = all_hands();
+        let conn = memory_db();
+        insert_hands_in(&conn, &hands).unwrap();
+        let stars = hands.iter().filter(|h| h.site.code() == "PokerStars").count() as i64;
+
+        assert_eq!(hand_count(&conn, "pokerstars"), stars);
+        let hero = hands[0].hero.clone().unwrap();
+        assert!(player_stats_in(&conn, "POKERSTARS", &hero.to_lowercase()).unwrap().is_some());
+
+        // Una base de datos v2 se actualiza conservando manos y contadores
+        conn.execute_batch("ALTER TABLE hand_players DROP COLUMN showed; PRAGMA user_version = 2").unwrap();
+        init_schema(&conn).unwrap();
+        assert_eq!(hand_count(&conn, "POKERSTARS"), stars);
+        let linked: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM hand_players hp JOIN hands h ON h.id = hp.hand_id", [], |row| row.get(0),
+        ).unwrap();
+        assert!(linked > 0);
+        assert_eq!(insert_hands_in(&conn, &hands).unwrap().0, 0);
+    }
+
+    #[test]
+    fn v3_upgrade_fills_pot_and_shown_cards() {
+        let hands = all_hands();
+        let conn = memory_db();
+        insert_hands_in(&conn, &hands).unwrap();
+        let list = |conn: &Connection| -> Vec<String> {
+            player_hands_in(conn, "PokerStars", "bb:player", true, Some(10.0), 10, "")
+                .unwrap()
+                .into_iter()
+                .map(|(_, hand)| hand.id)
+                .collect()
+        };
+        let expected = list(&conn);
+        assert!(!expected.is_empty());
+
+        conn.execute_batch("
+ALTER TABLE hands DROP COLUMN total_pot;
+ALTER TABLE hand_players DROP COLUMN showed;
+PRAGMA user_version = 3;
+").unwrap();
+        init_schema(&conn).unwrap();
+        assert_eq!(list(&conn), expected);
+    }
+}
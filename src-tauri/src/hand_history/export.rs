@@ -0,0 +1,370 @@
+// src-tauri/src/hand_history/export.rs
+// Exportación de manos a texto con el formato de PokerStars, sea cual sea la sala
+// de origen, para compartirlas con herramientas de terceros
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use super::model::*;
+
+// Separación entre manos en un mismo archivo, como en los historiales de PokerStars
+pub const HAND_SEPARATOR: &str = "\n\n\n";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(default)]
+pub struct ExportOptions {
+    // Sustituye los nicks por etiquetas de asiento ("Seat3") y el del héroe por "Hero"
+    pub anonimizar: bool,
+    // No incluye las cartas repartidas al héroe (las que enseñó en el showdown sí)
+    pub ocultar_cartas_hero: bool,
+}
+
+// Convierte una mano al texto de un historial de PokerStars
+pub fn to_pokerstars(hand: &Hand, options: &ExportOptions) -> String {
+    let names: HashMap<&str, String> = hand.seats.iter()
+        .map(|seat| {
+            let name = if !options.anonimizar {
+                seat.player.clone()
+            } else if hand.hero.as_deref() == Some(seat.player.as_str()) {
+                "Hero".to_string()
+            } else {
+                format!("Seat{}", seat.number)
+            };
+            (seat.player.as_str(), name)
+        })
+        .collect();
+    let name = |player: &str| names.get(player).cloned().unwrap_or_else(|| player.to_string());
+    let symbol = currency_symbol(hand.currency.as_deref());
+    let amount = |chips: Chips| format_amount(chips, symbol);
+    let is_hero = |player: &str| hand.hero.as_deref() == Some(player);
+
+    let mut lines = Vec::new();
+
+    // Cabecera y asientos
+    let game = match hand.variant {
+        Variant::Holdem => "Hold'em",
+        Variant::Omaha => "Omaha",
+    };
+    let limit = match hand.limit {
+        BettingLimit::NoLimit => "No Limit",
+        BettingLimit::PotLimit => "Pot Limit",
+        BettingLimit::Fixed => "Limit",
+    };
+    let stakes = match currency_code(hand.currency.as_deref()) {
+        Some(code) => format!("{}/{} {}", amount(hand.small_blind), amount(hand.big_blind), code),
+        None => format!("{}/{}", amount(hand.small_blind), amount(hand.big_blind)),
+    };
+    // Las fechas se guardan en UTC
+    let date = format!("{} UTC", hand.date.format("%Y/%m/%d %H:%M:%S"));
+    lines.push(format!(
+        "PokerStars {}Hand #{}:  {} {} ({}) - {}",
+        if hand.fast_fold { "Zoom " } else { "" }, numeric_id(&hand.id), game, limit, stakes, date
+    ));
+    lines.push(format!(
+        "Table '{}' {}-max{} Seat #{} is the button",
+        hand.table, hand.max_players, if hand.currency.is_none() { " (Play Money)" } else { "" }, hand.button_seat
+    ));
+    for seat in hand.seats.iter() {
+        lines.push(format!(
+            "Seat {}: {} ({} in chips){}",
+            seat.number, name(&seat.player), amount(seat.stack), if seat.sitting_out { " is sitting out" } else { "" }
+        ));
+    }
+
+    // Acciones, calle a calle. Las obligatorias van antes de repartir.
+    let mut betting = Betting::default();
+    for action in hand.actions.iter().filter(|a| a.street == Street::Preflop && a.kind.is_forced()) {
+        lines.push(format!("{}: {}", name(&action.player), betting.describe(action, &amount, hand.big_blind)));
+    }
+    lines.push("*** HOLE CARDS ***".to_string());
+    if let Some(hero) = hand.hero.as_deref().filter(|_| !options.ocultar_cartas_hero && !hand.hero_cards.is_empty()) {
+        lines.push(format!("Dealt to {} [{}]", name(hero), cards(&hand.hero_cards)));
+    }
+
+    for street in [Street::Preflop, Street::Flop, Street::Turn, Street::River] {
+        if street != Street::Preflop {
+            let dealt = match street {
+                Street::Flop => 3,
+                Street::Turn => 4,
+                _ => 5,
+            };
+            if hand.board.len() < dealt {
+                break;
+            }
+            let (header, previous) = match street {
+                Street::Flop => ("FLOP", 0),
+                Street::Turn => ("TURN", 3),
+                _ => ("RIVER", 4),
+            };
+            if previous == 0 {
+                lines.push(format!("*** {} *** [{}]", header, cards(&hand.board[..dealt])));
+            } else {
+                lines.push(format!("*** {} *** [{}] [{}]", header, cards(&hand.board[..previous]), cards(&hand.board[previous..dealt])));
+            }
+            betting.new_street();
+        }
+
+        let voluntary = hand.actions.iter().filter(|a| a.street == street && !(street == Street::Preflop && a.kind.is_forced()));
+        for action in voluntary {
+            lines.push(format!("{}: {}", name(&action.player), betting.describe(action, &amount, hand.big_blind)));
+        }
+
+        // La devolución va tras la última calle con apuestas
+        let last_street = hand.actions.iter().map(|a| a.street).max().unwrap_or(Street::Preflop);
+        if street == last_street {
+            for returned in hand.returned.iter() {
+                lines.push(format!("Uncalled bet ({}) returned to {}", amount(returned.amount), name(&returned.player)));
+            }
+        }
+    }
+
+    // Showdown y botes
+    if !hand.showdown.is_empty() {
+        lines.push("*** SHOW DOWN ***".to_string());
+        for shown in hand.showdown.iter() {
+            if shown.mucked {
+                lines.push(format!("{}: mucks hand", name(&shown.player)));
+            } else {
+                let description = shown.description.as_ref().map(|d| format!(" ({})", d)).unwrap_or_default();
+                lines.push(format!("{}: shows [{}]{}", name(&shown.player), cards(&shown.cards), description));
+            }
+        }
+    }
+    for collection in hand.collected.iter() {
+        let player = name(&collection.player);
+        let pot = match collection.pot.as_deref() {
+            None => "pot".to_string(),
+            Some("main") => "main pot".to_string(),
+            Some("cash-out") => {
+                lines.push(format!("{} cashed out the hand for {}", player, amount(collection.amount)));
+                continue;
+            },
+            Some(side) if hand.side_pots.len() > 1 => format!("side pot-{}", side.trim_start_matches("side-")),
+            Some(_) => "side pot".to_string(),
+        };
+        lines.push(format!("{} collected {} from {}", player, amount(collection.amount), pot));
+    }
+
+    // Resumen
+    lines.push("*** SUMMARY ***".to_string());
+    let mut pots = String::from(" ");
+    if !hand.side_pots.is_empty() {
+        let main = hand.total_pot - hand.rake - hand.side_pots.iter().sum::<Chips>();
+        pots = format!(" Main pot {}. ", amount(main));
+        for (i, side) in hand.side_pots.iter().enumerate() {
+            let label = if hand.side_pots.len() > 1 { format!("Side pot-{}", i + 1) } else { "Side pot".to_string() };
+            pots.push_str(&format!("{} {}. ", label, amount(*side)));
+        }
+    }
+    lines.push(format!("Total pot {}{}| Rake {}", amount(hand.total_pot), pots, amount(hand.rake)));
+    if !hand.board.is_empty() {
+        lines.push(format!("Board [{}]", cards(&hand.board)));
+    }
+    for seat in hand.seats.iter().filter(|s| !s.sitting_out) {
+        let posted = |kind: ActionKind| hand.actions.iter().any(|a| a.player == seat.player && a.kind == kind);
+        let mut line = format!("Seat {}: {}", seat.number, name(&seat.player));
+        if seat.number == hand.button_seat {
+            line.push_str(" (button)");
+        }
+        if posted(ActionKind::SmallBlind) {
+            line.push_str(" (small blind)");
+        } else if posted(ActionKind::BigBlind) {
+            line.push_str(" (big blind)");
+        }
+
+        let won = hand.won(&seat.player);
+        let shown = hand.showdown.iter().find(|s| s.player == seat.player);
+        let folded = hand.actions.iter().find(|a| a.player == seat.player && a.kind == ActionKind::Fold);
+        if let Some(shown) = shown.filter(|s| !s.mucked) {
+            let description = shown.description.as_ref().map(|d| format!(" with {}", d)).unwrap_or_default();
+            if won > 0 {
+                line.push_str(&format!(" showed [{}] and won ({}){}", cards(&shown.cards), amount(won), description));
+            } else {
+                line.push_str(&format!(" showed [{}] and lost{}", cards(&shown.cards), description));
+            }
+        } else if let Some(shown) = shown {
+            let hidden = options.ocultar_cartas_hero && is_hero(&seat.player);
+            if shown.cards.is_empty() || hidden {
+                line.push_str(" mucked");
+            } else {
+                line.push_str(&format!(" mucked [{}]", cards(&shown.cards)));
+            }
+        } else if let Some(fold) = folded {
+            line.push_str(match fold.street {
+                Street::Preflop => " folded before Flop",
+                Street::Flop => " folded on the Flop",
+                Street::Turn => " folded on the Turn",
+                Street::River => " folded on the River",
+            });
+        } else if won > 0 {
+            line.push_str(&format!(" collected ({})", amount(won)));
+        }
+        lines.push(line);
+    }
+
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+// Varias manos en un mismo texto, separadas como en un archivo de PokerStars
+pub fn hands_to_pokerstars(hands: &[Hand], options: &ExportOptions) -> String {
+    hands.iter()
+        .map(|hand| to_pokerstars(hand, options))
+        .collect::<Vec<_>>()
+        .join(HAND_SEPARATOR)
+}
+
+// Lo apostado en la calle, para escribir las subidas como "raises X to Y"
+#[derive(Default)]
+struct Betting {
+    committed: HashMap<String, Chips>,
+    highest: Chips,
+}
+
+impl Betting {
+    fn new_street(&mut self) {
+        self.committed.clear();
+        self.highest = 0;
+    }
+
+    fn describe(&mut self, action: &Action, amount: &impl Fn(Chips) -> String, big_blind: Chips) -> String {
+        let committed = self.committed.entry(action.player.clone()).or_insert(0);
+        let text = match action.kind {
+            ActionKind::Fold => "folds".to_string(),
+            ActionKind::Check => "checks".to_string(),
+            ActionKind::Call => format!("calls {}", amount(action.amount)),
+            ActionKind::Bet => format!("bets {}", amount(action.amount)),
+            ActionKind::Raise => {
+                let to = action.to.unwrap_or(*committed + action.amount);
+                format!("raises {} to {}", amount(to - self.highest), amount(to))
+            },
+            ActionKind::Ante => format!("posts the ante {}", amount(action.amount)),
+            ActionKind::SmallBlind => format!("posts small blind {}", amount(action.amount)),
+            ActionKind::BigBlind => format!("posts big blind {}", amount(action.amount)),
+            ActionKind::SmallAndBigBlind => format!("posts small & big blinds {}", amount(action.amount)),
+            ActionKind::Straddle => format!("posts straddle {}", amount(action.amount)),
+        };
+
+        // Igual que al leer: la ciega pequeña de "small & big blinds" es dinero muerto
+        match action.kind {
+            ActionKind::Ante | ActionKind::Fold | ActionKind::Check => {},
+            ActionKind::Raise => *committed = action.to.unwrap_or(*committed + action.amount),
+            ActionKind::SmallAndBigBlind => *committed += big_blind.min(action.amount),
+            _ => *committed += action.amount,
+        }
+        self.highest = self.highest.max(*committed);
+
+        if action.all_in {
+            format!("{} and is all-in", text)
+        } else {
+            text
+        }
+    }
+}
+
+// PokerStars solo admite identificadores numéricos ("HD1790345123" -> "1790345123")
+fn numeric_id(id: &str) -> String {
+    let digits: String = id.chars().filter(|c| c.is_ascii_digit()).collect();
+    if digits.is_empty() { "0".to_string() } else { digits }
+}
+
+fn currency_symbol(currency: Option<&str>) -> &'static str {
+    match currency {
+        Some("USD") | Some("$") => "$",
+        Some("EUR") | Some("€") => "€",
+        Some("GBP") | Some("£") => "£",
+        Some("¥") => "¥",
+        _ => "",
+    }
+}
+
+fn currency_code(currency: Option<&str>) -> Option<&str> {
+    match currency? {
+        "$" => Some("USD"),
+        "€" => Some("EUR"),
+        "£" => Some("GBP"),
+        "¥" => None,
+        code => Some(code),
+    }
+}
+
+// Importe con el símbolo de la moneda y sin decimales si es entero ("$2", "$0.30")
+fn format_amount(chips: Chips, symbol: &str) -> String {
+    if chips % 100 == 0 {
+        format!("{}{}", symbol, chips / 100)
+    } else {
+        format!("{}{}.{:02}", symbol, chips / 100, chips % 100)
+    }
+}
+
+fn cards(cards: &[Card]) -> String {
+    cards.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand_history::fixtures::all_hands;
+    use crate::hand_history::{parse_text, pokerstars};
+
+    // Datos que dependen de la sala de origen y no se conservan en el formato de PokerStars
+    fn normalized(mut hand: Hand, original: &Hand) -> Hand {
+        hand.site = original.site;
+        hand.id = original.id.clone();
+        hand.currency = original.currency.clone();
+        hand.time_zone = original.time_zone.clone();
+        for (seat, original) in hand.seats.iter_mut().zip(original.seats.iter()) {
+            seat.anonymized = original.anonymized;
+        }
+        hand
+    }
+
+    #[test]
+    fn exported_hands_parse_back_to_the_same_hand() {
+        let hands = all_hands();
+        assert!(hands.len() >= 8);
+        for hand in hands.iter() {
+            let text = to_pokerstars(hand, &ExportOptions::default());
+            let parsed = pokerstars::parse_hand(&text, 1).unwrap_or_else(|e| panic!("{:?}\n{}", e, text));
+            assert_eq!(&normalized(parsed, hand), hand, "\n{}", text);
+        }
+
+        // Varias manos en el mismo texto
+        let text = hands_to_pokerstars(&hands, &ExportOptions::default());
+        let outcome = parse_text(&text).unwrap();
+        assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+        assert_eq!(outcome.hands.len(), hands.len());
+    }
+
+    #[test]
+    fn keeps_pokerstars_text_format() {
+        let hand = &all_hands()[0];
+        let text = to_pokerstars(hand, &ExportOptions::default());
+        assert!(text.starts_with("PokerStars Hand #245000000001:  Hold'em No Limit ($0.05/$0.10 USD) - 2024/03/02 20:14:05 UTC\n"));
+        assert!(text.contains("\nHeroPlayer: raises $0.20 to $0.30\n"));
+        assert!(text.contains("\nbb:player: raises $0.90 to $1.20 and is all-in\n"));
+        assert!(text.contains("\n*** TURN *** [2c 7d Js] [Qh]\n"));
+        assert!(text.contains("\nSeat 5: bb:player (big blind) showed [Jh Jd] and won ($2.91) with three of a kind, Jacks\n"));
+    }
+
+    #[test]
+    fn anonymizes_names_and_hides_hero_cards() {
+        let options = ExportOptions { anonimizar: true, ocultar_cartas_hero: true };
+        for hand in all_hands().iter() {
+            let text = to_pokerstars(hand, &options);
+            for seat in hand.seats.iter().filter(|s| s.player != "Hero") {
+                assert!(!text.contains(&seat.player), "{} en\n{}", seat.player, text);
+            }
+            assert!(!text.contains("Dealt to"));
+
+            let parsed = pokerstars::parse_hand(&text, 1).unwrap();
+            assert_eq!(parsed.hero, None);
+            assert_eq!(parsed.total_pot, hand.total_pot);
+            assert_eq!(parsed.actions.len(), hand.actions.len());
+        }
+
+        let hand = &all_hands()[0];
+        let text = to_pokerstars(hand, &ExportOptions { anonimizar: true, ocultar_cartas_hero: false });
+        assert!(text.contains("\nDealt to Hero [Ah Kd]\n"));
+        assert!(text.contains("\nSeat5: raises $0.90 to $1.20 and is all-in\n"));
+        assert_eq!(pokerstars::parse_hand(&text, 1).unwrap().hero.as_deref(), Some("Hero"));
+    }
+}
@@ -0,0 +1,24 @@
+// src-tauri/src/hand_history/fixtures.rs
+// Manos de los historiales de ejemplo (tests/fixtures) para las pruebas de otros módulos
+use super::{parse_text, Hand};
+
+const PS_NLHE_6MAX: &str = include_str!("../../tests/fixtures/hand_history/pokerstars/nlhe_6max.txt");
+
+const ALL: &[&str] = &[
+    PS_NLHE_6MAX,
+    include_str!("../../tests/fixtures/hand_history/pokerstars/plo_9max.txt"),
+    include_str!("../../tests/fixtures/hand_history/pokerstars/nlhe_antes_zoom.txt"),
+    include_str!("../../tests/fixtures/hand_history/ggpoker/nlhe_6max.txt"),
+    include_str!("../../tests/fixtures/hand_history/ggpoker/rush_and_cash.txt"),
+    include_str!("../../tests/fixtures/hand_history/winamax/cash_5max.txt"),
+];
+
+// Manos de NLHE 6-max de PokerStars, en el orden del archivo
+pub(crate) fn pokerstars_hands() -> Vec<Hand> {
+    parse_text(PS_NLHE_6MAX).unwrap().hands
+}
+
+// Manos de todos los historiales de ejemplo, de todas las salas
+pub(crate) fn all_hands() -> Vec<Hand> {
+    ALL.iter().flat_map(|text| parse_text(text).unwrap().hands).collect()
+}
@@ -0,0 +1,107 @@
+// src-tauri/src/hand_history/ggpoker.rs
+// Historiales de GGPoker: mismo formato que PokerStars, con el héroe como "Hero"
+// y el resto de jugadores anonimizados con un identificador hexadecimal
+use once_cell::sync::Lazy;
+use regex::Regex;
+use super::model::*;
+use super::pokerstars::{parse_with, Dialect};
+
+static HEADER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Poker Hand #([A-Z]{2}\d+):\s+(.+?)\s+\(([^()]*)\)\s+-\s+(\d{4}/\d{1,2}/\d{1,2} \d{1,2}:\d{2}:\d{2})(?:\s+([A-Z]{2,4}))?").unwrap()
+});
+
+// Identificadores que GGPoker pone en lugar de los nicks ("4a7b9c2d")
+static HASHED_NAME_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[0-9a-f]{6,12}$").unwrap()
+});
+
+static GGPOKER: Dialect = Dialect {
+    site: Site::GGPoker,
+    header: &HEADER_RE,
+    hero_name: Some("Hero"),
+};
+
+pub fn is_hand_header(line: &str) -> bool {
+    line.starts_with("Poker Hand #")
+}
+
+// Interpreta una mano. `first_line` es el número de línea de la cabecera en el archivo.
+pub fn parse_hand(text: &str, first_line: usize) -> Result<Hand, ParseError> {
+    let mut hand = parse_with(&GGPOKER, text, first_line)?;
+
+    // Rush & Cash: ids "RC..." y mesas "RushAndCash..."
+    hand.fast_fold = hand.id.starts_with("RC") || hand.table.starts_with("RushAndCash");
+    for seat in hand.seats.iter_mut() {
+        seat.anonymized = HASHED_NAME_RE.is_match(&seat.player);
+    }
+    Ok(hand)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand_history::{detect_site, parse_text};
+
+    const NLHE: &str = include_str!("../../tests/fixtures/hand_history/ggpoker/nlhe_6max.txt");
+    const RUSH_AND_CASH: &str = include_str!("../../tests/fixtures/hand_history/ggpoker/rush_and_cash.txt");
+
+    #[test]
+    fn detects_ggpoker_files() {
+        assert_eq!(detect_site(NLHE), Some(Site::GGPoker));
+        assert_eq!(detect_site(RUSH_AND_CASH), Some(Site::GGPoker));
+    }
+
+    #[test]
+    fn parses_hero_and_anonymized_players() {
+        let outcome = parse_text(NLHE).unwrap();
+        assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+        let hand = &outcome.hands[0];
+
+        assert_eq!(hand.site, Site::GGPoker);
+        assert_eq!(hand.id, "HD1790345123");
+        assert_eq!(hand.table, "NLHGold7");
+        assert_eq!(hand.currency.as_deref(), Some("$"));
+        assert_eq!((hand.small_blind, hand.big_blind), (5, 10));
+        assert_eq!(hand.time_zone, None);
+        assert!(!hand.fast_fold);
+
+        // "Dealt to" incluye las cartas vistas de otros jugadores, pero el héroe es "Hero"
+        assert_eq!(hand.hero.as_deref(), Some("Hero"));
+        assert_eq!(hand.hero_cards, Card::parse_list("Qs Qd").unwrap());
+
+        let anonymized: Vec<&str> = hand.seats.iter().filter(|s| s.anonymized).map(|s| s.player.as_str()).collect();
+        assert_eq!(anonymized, vec!["4a7b9c2d", "91fe03bc", "5c0d1e2f", "e8a1b2c3", "77d4f0aa"]);
+        assert!(hand.seats.iter().any(|s| s.player == "Hero" && !s.anonymized));
+    }
+
+    #[test]
+    fn adds_jackpot_to_rake() {
+        let hand = &parse_text(NLHE).unwrap().hands[0];
+
+        assert_eq!(hand.board, Card::parse_list("Qh 8c 2d 5s Tc").unwrap());
+        assert_eq!(hand.showdown.len(), 2);
+        assert_eq!(hand.total_pot, 255);
+        // Rake de 0.11 más 0.01 de jackpot
+        assert_eq!(hand.rake, 12);
+        assert_eq!(hand.collected[0].amount, 243);
+    }
+
+    #[test]
+    fn parses_rush_and_cash_hands() {
+        let outcome = parse_text(RUSH_AND_CASH).unwrap();
+        assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+        assert_eq!(outcome.hands.len(), 2);
+
+        let hand = &outcome.hands[0];
+        assert!(hand.fast_fold);
+        assert_eq!(hand.table, "RushAndCash13579");
+        assert_eq!(hand.returned.len(), 1);
+        assert_eq!(hand.returned[0].player, "Hero");
+
+        let omaha = &outcome.hands[1];
+        assert_eq!(omaha.variant, Variant::Omaha);
+        assert_eq!(omaha.limit, BettingLimit::PotLimit);
+        assert_eq!(omaha.hero_cards.len(), 4);
+        assert!(omaha.fast_fold);
+    }
+}
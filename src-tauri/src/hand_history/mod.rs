@@ -0,0 +1,359 @@
+// src-tauri/src/hand_history/mod.rs
+// Lectura de historiales de manos en texto a un modelo común (ver model.rs)
+pub mod model;
+pub mod pokerstars;
+pub mod ggpoker;
+pub mod winamax;
+pub mod export;
+#[cfg(test)]
+pub(crate) mod fixtures;
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use serde::Serialize;
+pub use model::*;
+
+// Interpreta el texto de una mano; recibe el número de línea de su cabecera
+type HandParser = fn(&str, usize) -> Result<Hand, ParseError>;
+
+// Resultado de leer un archivo: las manos válidas y los errores de las que no se pudieron leer
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ParseOutcome {
+    pub hands: Vec<Hand>,
+    pub errors: Vec<ParseError>,
+}
+
+// Detecta la sala por la cabecera de la primera mano
+pub fn detect_site(content: &str) -> Option<Site> {
+    let first_line = content.trim_start_matches('\u{feff}').lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?;
+
+    if pokerstars::is_hand_header(first_line) {
+        return Some(Site::PokerStars);
+    }
+    if ggpoker::is_hand_header(first_line) {
+        return Some(Site::GGPoker);
+    }
+    if winamax::is_hand_header(first_line) {
+        return Some(Site::Winamax);
+    }
+    None
+}
+
+// Cabecera de mano de cualquiera de las salas soportadas
+pub fn is_hand_header(line: &str) -> bool {
+    let line = line.trim_start_matches('\u{feff}').trim();
+    pokerstars::is_hand_header(line) || ggpoker::is_hand_header(line) || winamax::is_hand_header(line)
+}
+
+// Interpreta todas las manos de un texto
+pub fn parse_text(content: &str) -> Result<ParseOutcome, String> {
+    let site = detect_site(content)
+        .ok_or("No se reconoce el formato del historial de manos")?;
+
+    let mut outcome = ParseOutcome::default();
+    let (is_header, parse_hand): (fn(&str) -> bool, HandParser) = match site {
+        Site::PokerStars => (pokerstars::is_hand_header, pokerstars::parse_hand),
+        Site::GGPoker => (ggpoker::is_hand_header, ggpoker::parse_hand),
+        Site::Winamax => (winamax::is_hand_header, winamax::parse_hand),
+    };
+
+    for (first_line, text) in split_hands(content, is_header) {
+        match parse_hand(&text, first_line) {
+            Ok(hand) => outcome.hands.push(hand),
+            Err(e) => outcome.errors.push(e),
+        }
+    }
+    Ok(outcome)
+}
+
+// Interpreta un archivo de historial
+pub fn parse_file(path: &str) -> Result<ParseOutcome, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Error al leer historial de manos: {}", e))?;
+    parse_text(&String::from_utf8_lossy(&bytes))
+}
+
+// Separa un archivo en manos. Devuelve el número de la primera línea de cada mano y su texto.
+pub(crate) fn split_hands(content: &str, is_header: fn(&str) -> bool) -> Vec<(usize, String)> {
+    let mut hands: Vec<(usize, String)> = Vec::new();
+
+    for (i, line) in content.trim_start_matches('\u{feff}').lines().enumerate() {
+        if is_header(line.trim()) {
+            hands.push((i + 1, String::new()));
+        }
+        if let Some((_, text)) = hands.last_mut() {
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+    }
+
+    hands
+}
+
+// Lee un importe ("$1,234.56", "0.05€", "1 500") en centésimas
+pub(crate) fn parse_amount(text: &str) -> Option<Chips> {
+    let cleaned: String = text.trim()
+        .trim_start_matches(['$', '€', '£', '¥'])
+        .trim_end_matches(['$', '€', '£', '¥'])
+        .chars()
+        .filter(|c| *c != ',' && !c.is_whitespace())
+        .collect();
+    if cleaned.is_empty() {
+        return None;
+    }
+
+    let (integer, fraction) = cleaned.split_once('.').unwrap_or((&cleaned, ""));
+    if integer.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    let integer: i64 = if integer.is_empty() { 0 } else { integer.parse().ok()? };
+    if !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    // Dos decimales; los siguientes se redondean
+    let mut cents = 0;
+    for (i, digit) in fraction.chars().take(3).enumerate() {
+        let digit = digit.to_digit(10)? as i64;
+        match i {
+            0 => cents += digit * 10,
+            1 => cents += digit,
+            _ => if digit >= 5 { cents += 1 },
+        }
+    }
+    Some(integer * 100 + cents)
+}
+
+// Símbolo de moneda de un importe, si tiene
+pub(crate) fn currency_symbol(text: &str) -> Option<String> {
+    text.trim().chars()
+        .find(|c| matches!(c, '$' | '€' | '£' | '¥'))
+        .map(|c| c.to_string())
+}
+
+// Fecha del historial en UTC según la zona horaria de la cabecera.
+// Sin zona (GGPoker) o con una zona desconocida se toma como UTC.
+pub(crate) fn to_utc(date: NaiveDateTime, zone: Option<&str>) -> NaiveDateTime {
+    let offset_hours = match zone.map(|z| z.to_ascii_uppercase()).as_deref() {
+        Some("ET") if is_us_summer_time(date) => -4,
+        Some("ET") | Some("EST") => -5,
+        Some("EDT") => -4,
+        Some("CET") if is_eu_summer_time(date, 1) => 2,
+        Some("CET") => 1,
+        Some("CEST") => 2,
+        Some("WET") if is_eu_summer_time(date, 0) => 1,
+        Some("WEST") | Some("BST") => 1,
+        _ => 0,
+    };
+    date - Duration::hours(offset_hours)
+}
+
+// Horario de verano de EE. UU.: del segundo domingo de marzo al primero de noviembre, a las 2:00
+fn is_us_summer_time(date: NaiveDateTime) -> bool {
+    let year = date.year();
+    let (Some(start), Some(end)) = (
+        NaiveDate::from_weekday_of_month_opt(year, 3, Weekday::Sun, 2),
+        NaiveDate::from_weekday_of_month_opt(year, 11, Weekday::Sun, 1),
+    ) else { return false };
+    let two = NaiveTime::from_hms_opt(2, 0, 0).unwrap_or_default();
+    date >= start.and_time(two) && date < end.and_time(two)
+}
+
+// Horario de verano europeo: del último domingo de marzo al último de octubre a la 1:00 UTC.
+// `standard_offset` es la diferencia horaria de invierno para pasar a hora local.
+fn is_eu_summer_time(date: NaiveDateTime, standard_offset: i64) -> bool {
+    let year = date.year();
+    let (Some(start), Some(end)) = (last_sunday(year, 3), last_sunday(year, 10)) else { return false };
+    let one = NaiveTime::from_hms_opt(1, 0, 0).unwrap_or_default();
+    let start = start.and_time(one) + Duration::hours(standard_offset);
+    let end = end.and_time(one) + Duration::hours(standard_offset + 1);
+    date >= start && date < end
+}
+
+fn last_sunday(year: i32, month: u32) -> Option<NaiveDate> {
+    let last_day = NaiveDate::from_ymd_opt(year, month + 1, 1)?.pred_opt()?;
+    Some(last_day - Duration::days(i64::from(last_day.weekday().num_days_from_sunday())))
+}
+
+// Separa "Jugador: resto" buscando el nombre más largo de los asientos que encaje.
+// Los nombres pueden contener espacios, dos puntos o paréntesis.
+pub(crate) fn split_player<'a>(line: &'a str, players: &[String], separator: &str) -> Option<(String, &'a str)> {
+    players.iter()
+        .filter(|name| line.starts_with(name.as_str()) && line[name.len()..].starts_with(separator))
+        .max_by_key(|name| name.len())
+        .map(|name| (name.clone(), &line[name.len() + separator.len()..]))
+}
+
+// Jugador al final de la línea ("... returned to Jugador")
+pub(crate) fn player_suffix(line: &str, players: &[String], prefix: &str) -> Option<String> {
+    let rest = line.strip_prefix(prefix)?;
+    players.iter()
+        .filter(|name| rest.trim_end() == name.as_str())
+        .max_by_key(|name| name.len())
+        .cloned()
+}
+
+// Texto entre el primer '[' y el ']' correspondiente
+pub(crate) fn bracketed(text: &str) -> Option<&str> {
+    let start = text.find('[')?;
+    let end = text[start..].find(']')? + start;
+    Some(&text[start + 1..end])
+}
+
+// Acción de apuesta leída de una línea (sin el nombre del jugador)
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ParsedAction {
+    pub kind: ActionKind,
+    // Importe tal como aparece: lo pagado en calls/bets/ciegas, lo subido en raises
+    pub amount: Chips,
+    pub to: Option<Chips>,
+    pub all_in: bool,
+}
+
+// Interpreta las acciones comunes al formato de PokerStars y derivados
+pub(crate) fn parse_betting_action(text: &str) -> Option<ParsedAction> {
+    let text = text.trim();
+    let (text, all_in) = match text.strip_suffix("and is all-in") {
+        Some(rest) => (rest.trim(), true),
+        None => (text, false),
+    };
+
+    let simple = |kind| Some(ParsedAction { kind, amount: 0, to: None, all_in });
+    let with_amount = |kind, amount: &str| Some(ParsedAction { kind, amount: parse_amount(amount)?, to: None, all_in });
+
+    if text == "folds" || text.starts_with("folds [") {
+        return simple(ActionKind::Fold);
+    }
+    if text == "checks" {
+        return simple(ActionKind::Check);
+    }
+    if let Some(amount) = text.strip_prefix("calls ") {
+        return with_amount(ActionKind::Call, amount);
+    }
+    if let Some(amount) = text.strip_prefix("bets ") {
+        return with_amount(ActionKind::Bet, amount);
+    }
+    if let Some(rest) = text.strip_prefix("raises ") {
+        let (by, to) = rest.split_once(" to ")?;
+        return Some(ParsedAction { kind: ActionKind::Raise, amount: parse_amount(by)?, to: Some(parse_amount(to)?), all_in });
+    }
+    if let Some(amount) = text.strip_prefix("posts small & big blinds ") {
+        return with_amount(ActionKind::SmallAndBigBlind, amount);
+    }
+    if let Some(amount) = text.strip_prefix("posts small blind ") {
+        return with_amount(ActionKind::SmallBlind, amount);
+    }
+    if let Some(amount) = text.strip_prefix("posts big blind ") {
+        return with_amount(ActionKind::BigBlind, amount);
+    }
+    if let Some(amount) = text.strip_prefix("posts the ante ").or_else(|| text.strip_prefix("posts ante ")) {
+        return with_amount(ActionKind::Ante, amount);
+    }
+    if let Some(amount) = text.strip_prefix("posts straddle ") {
+        return with_amount(ActionKind::Straddle, amount);
+    }
+    None
+}
+
+// Lleva la cuenta de lo apostado por cada jugador en la calle actual
+// para convertir "raises X to Y" en las fichas que realmente pone.
+#[derive(Debug, Default)]
+pub(crate) struct StreetCommitments {
+    committed: HashMap<String, Chips>,
+}
+
+impl StreetCommitments {
+    pub fn new_street(&mut self) {
+        self.committed.clear();
+    }
+
+    // Apuesta que nadie ha igualado en la calle: lo que el que más ha puesto supera al siguiente
+    pub fn uncalled(&self) -> Option<(String, Chips)> {
+        let mut amounts: Vec<(&String, Chips)> = self.committed.iter().map(|(p, c)| (p, *c)).collect();
+        amounts.sort_by_key(|(_, committed)| Reverse(*committed));
+        let (player, top) = amounts.first()?;
+        let second = amounts.get(1).map(|(_, committed)| *committed).unwrap_or(0);
+        (*top > second).then(|| (player.to_string(), top - second))
+    }
+
+    // Devuelve la acción del modelo con el importe puesto en el bote
+    pub fn apply(&mut self, street: Street, player: &str, parsed: ParsedAction, big_blind: Chips) -> Action {
+        let committed = self.committed.entry(player.to_string()).or_insert(0);
+        let amount = match parsed.kind {
+            ActionKind::Raise => {
+                let to = parsed.to.unwrap_or(parsed.amount);
+                let amount = to - *committed;
+                *committed = to;
+                amount
+            },
+            ActionKind::Ante | ActionKind::Fold | ActionKind::Check => parsed.amount,
+            // La parte de la ciega pequeña es dinero muerto
+            ActionKind::SmallAndBigBlind => {
+                *committed += big_blind.min(parsed.amount);
+                parsed.amount
+            },
+            _ => {
+                *committed += parsed.amount;
+                parsed.amount
+            },
+        };
+
+        Action {
+            street,
+            player: player.to_string(),
+            kind: parsed.kind,
+            amount,
+            to: parsed.to,
+            all_in: parsed.all_in,
+        }
+    }
+}
+
+// Comprobaciones comunes al terminar de leer una mano
+pub(crate) fn validate_hand(hand: &Hand) -> Result<(), String> {
+    if hand.seats.is_empty() {
+        return Err("La mano no tiene jugadores".to_string());
+    }
+    if !hand.hero_cards.is_empty() && hand.hero_cards.len() != hand.variant.hole_cards() {
+        return Err(format!("Se esperaban {} cartas propias y hay {}", hand.variant.hole_cards(), hand.hero_cards.len()));
+    }
+    if hand.board.len() > 5 {
+        return Err(format!("El board tiene {} cartas", hand.board.len()));
+    }
+
+    // Todo lo apostado (menos lo devuelto) tiene que estar en el bote
+    let invested: Chips = hand.seats.iter().map(|s| hand.invested(&s.player)).sum();
+    if invested != hand.total_pot {
+        return Err(format!("El bote total ({}) no coincide con lo apostado ({})", hand.total_pot, invested));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y/%m/%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn header_dates_are_converted_to_utc() {
+        // Misma mano en hora central europea y en hora del este
+        assert_eq!(to_utc(at("2024/03/02 21:14:05"), Some("CET")), at("2024/03/02 20:14:05"));
+        assert_eq!(to_utc(at("2024/03/02 15:14:05"), Some("ET")), at("2024/03/02 20:14:05"));
+
+        // Horario de verano (EE. UU. desde el 10 de marzo de 2024, Europa desde el 31)
+        assert_eq!(to_utc(at("2024/03/20 12:00:00"), Some("ET")), at("2024/03/20 16:00:00"));
+        assert_eq!(to_utc(at("2024/03/20 12:00:00"), Some("CET")), at("2024/03/20 11:00:00"));
+        assert_eq!(to_utc(at("2024/03/31 01:59:00"), Some("CET")), at("2024/03/31 00:59:00"));
+        assert_eq!(to_utc(at("2024/03/31 03:00:00"), Some("CET")), at("2024/03/31 01:00:00"));
+        assert_eq!(to_utc(at("2024/11/04 12:00:00"), Some("ET")), at("2024/11/04 17:00:00"));
+
+        assert_eq!(to_utc(at("2022/02/25 20:53:26"), Some("UTC")), at("2022/02/25 20:53:26"));
+        assert_eq!(to_utc(at("2024/03/05 18:02:44"), None), at("2024/03/05 18:02:44"));
+    }
+}
@@ -0,0 +1,350 @@
+// src-tauri/src/hand_history/model.rs
+use std::fmt;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+// Importes en centésimas de la unidad de la mesa (céntimos en cash, fichas × 100 en dinero ficticio)
+pub type Chips = i64;
+
+// Sala de la que procede el historial
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Site {
+    PokerStars,
+    GGPoker,
+    Winamax,
+}
+
+impl Site {
+    // Código de sala usado en la configuración (ver profiles::KNOWN_SITES)
+    pub fn code(&self) -> &'static str {
+        match self {
+            Site::PokerStars => "PokerStars",
+            Site::GGPoker => "GGPoker",
+            Site::Winamax => "Winamax",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Holdem,
+    Omaha,
+}
+
+impl Variant {
+    // Número de cartas propias
+    pub fn hole_cards(&self) -> usize {
+        match self {
+            Variant::Holdem => 2,
+            Variant::Omaha => 4,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BettingLimit {
+    NoLimit,
+    PotLimit,
+    Fixed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+// Posición en la mesa. EP y MP agrupan varias posiciones en mesas de más de 6 jugadores.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Position {
+    Ep,
+    Mp,
+    Co,
+    Btn,
+    Sb,
+    Bb,
+}
+
+impl Position {
+    pub const ALL: [Position; 6] = [Position::Ep, Position::Mp, Position::Co, Position::Btn, Position::Sb, Position::Bb];
+
+    pub fn from_label(label: &str) -> Option<Position> {
+        Position::ALL.into_iter().find(|p| p.label().eq_ignore_ascii_case(label.trim()))
+    }
+
+    // Posiciones desde las que una subida sin entradas previas es un robo de ciegas
+    pub fn is_steal_position(&self) -> bool {
+        matches!(self, Position::Co | Position::Btn | Position::Sb)
+    }
+
+    pub fn is_blind(&self) -> bool {
+        matches!(self, Position::Sb | Position::Bb)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Position::Ep => "EP",
+            Position::Mp => "MP",
+            Position::Co => "CO",
+            Position::Btn => "BTN",
+            Position::Sb => "SB",
+            Position::Bb => "BB",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ActionKind {
+    Ante,
+    SmallBlind,
+    BigBlind,
+    // Ciega pequeña y grande a la vez (al entrar en la mesa); la parte de la pequeña es dinero muerto
+    SmallAndBigBlind,
+    Straddle,
+    Fold,
+    Check,
+    Call,
+    Bet,
+    Raise,
+}
+
+impl ActionKind {
+    // Acciones obligatorias antes de repartir
+    pub fn is_forced(&self) -> bool {
+        matches!(self, ActionKind::Ante | ActionKind::SmallBlind | ActionKind::BigBlind | ActionKind::SmallAndBigBlind | ActionKind::Straddle)
+    }
+
+    pub fn is_aggressive(&self) -> bool {
+        matches!(self, ActionKind::Bet | ActionKind::Raise)
+    }
+}
+
+// Carta: valor 2..=14 (A = 14) y palo en minúscula ('c', 'd', 'h', 's')
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Card {
+    pub rank: u8,
+    pub suit: char,
+}
+
+impl Card {
+    pub fn parse(text: &str) -> Option<Card> {
+        let mut chars = text.trim().chars();
+        let rank = match chars.next()?.to_ascii_uppercase() {
+            c @ '2'..='9' => c as u8 - b'0',
+            'T' => 10,
+            'J' => 11,
+            'Q' => 12,
+            'K' => 13,
+            'A' => 14,
+            _ => return None,
+        };
+        let suit = chars.next()?.to_ascii_lowercase();
+        if !matches!(suit, 'c' | 'd' | 'h' | 's') || chars.next().is_some() {
+            return None;
+        }
+        Some(Card { rank, suit })
+    }
+
+    // Lee una lista de cartas separadas por espacios ("Ah Kd")
+    pub fn parse_list(text: &str) -> Option<Vec<Card>> {
+        text.split_whitespace().map(Card::parse).collect()
+    }
+
+    pub fn rank_char(&self) -> char {
+        match self.rank {
+            10 => 'T',
+            11 => 'J',
+            12 => 'Q',
+            13 => 'K',
+            14 => 'A',
+            r => (b'0' + r) as char,
+        }
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.rank_char(), self.suit)
+    }
+}
+
+// Las cartas se guardan como texto ("Ah") para que el JSON sea legible
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Card::parse(&text).ok_or_else(|| serde::de::Error::custom(format!("Carta no válida: '{}'", text)))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Seat {
+    pub number: u8,
+    pub player: String,
+    pub stack: Chips,
+    pub sitting_out: bool,
+    // Nombre sustituido por un identificador de la sala (GGPoker)
+    #[serde(default)]
+    pub anonymized: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Action {
+    pub street: Street,
+    pub player: String,
+    pub kind: ActionKind,
+    // Fichas que el jugador pone en el bote con esta acción
+    pub amount: Chips,
+    // Total de la apuesta tras una subida ("raises X to Y")
+    pub to: Option<Chips>,
+    pub all_in: bool,
+}
+
+// Cartas mostradas (o tiradas) en el showdown
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ShownHand {
+    pub player: String,
+    pub cards: Vec<Card>,
+    pub description: Option<String>,
+    pub mucked: bool,
+}
+
+// Importe que un jugador se lleva del bote
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Collection {
+    pub player: String,
+    pub amount: Chips,
+    // "main", "side-1"... o None si solo hay un bote
+    pub pot: Option<String>,
+}
+
+// Apuesta no igualada que se devuelve al jugador
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Returned {
+    pub player: String,
+    pub amount: Chips,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Hand {
+    pub site: Site,
+    pub id: String,
+    pub table: String,
+    // Fecha en UTC
+    pub date: NaiveDateTime,
+    // Zona horaria indicada en el historial (p. ej. "ET", "CET"); la fecha ya está convertida
+    pub time_zone: Option<String>,
+    pub variant: Variant,
+    pub limit: BettingLimit,
+    // Símbolo o código de moneda; None en dinero ficticio
+    pub currency: Option<String>,
+    pub small_blind: Chips,
+    pub big_blind: Chips,
+    pub ante: Chips,
+    pub max_players: u8,
+    // Mesa de fast fold (Zoom, Rush & Cash, Go Fast)
+    #[serde(default)]
+    pub fast_fold: bool,
+    pub button_seat: u8,
+    pub seats: Vec<Seat>,
+    pub hero: Option<String>,
+    pub hero_cards: Vec<Card>,
+    pub actions: Vec<Action>,
+    pub board: Vec<Card>,
+    pub showdown: Vec<ShownHand>,
+    pub returned: Vec<Returned>,
+    pub collected: Vec<Collection>,
+    pub total_pot: Chips,
+    pub side_pots: Vec<Chips>,
+    pub rake: Chips,
+}
+
+impl Hand {
+    // Fichas puestas en el bote por un jugador, descontando lo devuelto
+    pub fn invested(&self, player: &str) -> Chips {
+        let put: Chips = self.actions.iter().filter(|a| a.player == player).map(|a| a.amount).sum();
+        let returned: Chips = self.returned.iter().filter(|r| r.player == player).map(|r| r.amount).sum();
+        put - returned
+    }
+
+    pub fn won(&self, player: &str) -> Chips {
+        self.collected.iter().filter(|c| c.player == player).map(|c| c.amount).sum()
+    }
+
+    // Resultado neto de la mano para un jugador
+    pub fn net(&self, player: &str) -> Chips {
+        self.won(player) - self.invested(player)
+    }
+
+    pub fn folded(&self, player: &str) -> bool {
+        self.actions.iter().any(|a| a.player == player && a.kind == ActionKind::Fold)
+    }
+
+    // El jugador llega al showdown si no tira sus cartas y queda al menos otro
+    // jugador, también cuando el all-in es antes del river
+    pub fn went_to_showdown(&self, player: &str) -> bool {
+        let remaining = self.seats.iter().filter(|s| !s.sitting_out && !self.folded(&s.player)).count();
+        !self.folded(player) && remaining >= 2
+    }
+
+    // Nombre del jugador tal como aparece en la mano; los nicks guardados en la
+    // base de datos pueden diferir en mayúsculas
+    pub fn seat_player(&self, nick: &str) -> Option<&str> {
+        self.seats.iter().find(|s| s.player.eq_ignore_ascii_case(nick)).map(|s| s.player.as_str())
+    }
+
+    // Cartas que enseñó el jugador en el showdown (vacío si no las enseñó)
+    pub fn shown_cards(&self, player: &str) -> &[Card] {
+        self.showdown.iter().find(|s| s.player == player).map_or(&[], |s| s.cards.as_slice())
+    }
+
+    // Posición del jugador según el botón, contando solo los jugadores sentados a jugar.
+    // En heads-up el botón es la ciega pequeña.
+    pub fn position(&self, player: &str) -> Option<Position> {
+        let mut active: Vec<&Seat> = self.seats.iter().filter(|s| !s.sitting_out).collect();
+        active.sort_by_key(|s| s.number);
+        let n = active.len();
+        if n < 2 {
+            return None;
+        }
+
+        // Orden de reparto empezando por el primer asiento tras el botón
+        let start = active.iter().position(|s| s.number > self.button_seat).unwrap_or(0);
+        active.rotate_left(start);
+        let index = active.iter().position(|s| s.player == player)?;
+
+        if n == 2 {
+            return Some(if index == 1 { Position::Sb } else { Position::Bb });
+        }
+        let position = match index {
+            0 => Position::Sb,
+            1 => Position::Bb,
+            i if i == n - 1 => Position::Btn,
+            i if i == n - 2 => Position::Co,
+            // UTG siempre es EP; de las siguientes hasta el cutoff, las dos últimas son MP
+            i if i > 2 && n - 2 - i <= 2 => Position::Mp,
+            _ => Position::Ep,
+        };
+        Some(position)
+    }
+}
+
+// Error al interpretar una mano, con la posición exacta en el archivo
+#[derive(Debug, Error, Serialize, Clone, PartialEq)]
+#[error("{}línea {line}: {message} ('{content}')", hand_id.as_ref().map(|id| format!("Mano {}, ", id)).unwrap_or_default())]
+pub struct ParseError {
+    pub hand_id: Option<String>,
+    // Número de línea en el archivo (la primera es 1)
+    pub line: usize,
+    pub content: String,
+    pub message: String,
+}
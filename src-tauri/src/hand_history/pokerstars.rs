@@ -0,0 +1,567 @@
+// src-tauri/src/hand_history/pokerstars.rs
+// Historiales de PokerStars (cash, Hold'em y Omaha, de 2 a 9 jugadores)
+use chrono::NaiveDateTime;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use super::model::*;
+use super::{bracketed, currency_symbol, parse_amount, parse_betting_action, player_suffix, split_player, to_utc, validate_hand, StreetCommitments};
+
+static HEADER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^PokerStars (?:Zoom |Home Game )?(?:Hand|Game) #(\d+):\s+(.+?)\s+\(([^()]*)\)\s+-\s+(\d{4}/\d{1,2}/\d{1,2} \d{1,2}:\d{2}:\d{2})(?:\s+([A-Z]{2,4}))?").unwrap()
+});
+
+static TABLE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Table '(.+)' (\d+)-max(?: \(Play Money\))? Seat #(\d+) is the button").unwrap()
+});
+
+static SEAT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Seat (\d+): (.+?) \((\S+) in chips(?:, [^)]*)?\)(.*)$").unwrap()
+});
+
+static COLLECTED_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(.+?) collected (\S+) from (pot|main pot|side pot(?:-(\d+))?)$").unwrap()
+});
+
+static TOTAL_POT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Total pot (\S+)(.*?)\| Rake (\S+)").unwrap()
+});
+
+static SIDE_POT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"Side pot(?:-\d+)? (\S+?)\.?(?:\s|$)").unwrap()
+});
+
+// Otras comisiones que se descuentan del bote (GGPoker: "| Jackpot $0.02 | Bingo $0")
+static FEE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\| (?:Jackpot|Bingo|Fortune|Tax) (\S+)").unwrap()
+});
+
+// Mensajes de la mesa que no afectan a la mano
+const IGNORED_SUFFIXES: &[&str] = &[
+    " leaves the table",
+    " has timed out",
+    " has timed out while disconnected",
+    " has timed out while being disconnected",
+    " is disconnected",
+    " is connected",
+    " has returned",
+    " is sitting out",
+    " will be allowed to play after the button",
+    " was removed from the table for failing to post",
+];
+
+// Salas que usan el formato de PokerStars con pequeñas diferencias
+pub(crate) struct Dialect {
+    pub site: Site,
+    // Grupos: id, juego, ciegas, fecha y zona horaria opcional
+    pub header: &'static Lazy<Regex>,
+    // Nombre fijo del héroe si la sala lo usa ("Hero" en GGPoker).
+    // Con None el héroe es el único jugador con cartas en "Dealt to".
+    pub hero_name: Option<&'static str>,
+}
+
+static POKERSTARS: Dialect = Dialect {
+    site: Site::PokerStars,
+    header: &HEADER_RE,
+    hero_name: None,
+};
+
+pub fn is_hand_header(line: &str) -> bool {
+    line.starts_with("PokerStars ") && (line.contains(" Hand #") || line.contains(" Game #"))
+}
+
+struct Context {
+    hand_id: Option<String>,
+    first_line: usize,
+}
+
+impl Context {
+    fn error(&self, index: usize, content: &str, message: impl Into<String>) -> ParseError {
+        ParseError {
+            hand_id: self.hand_id.clone(),
+            line: self.first_line + index,
+            content: content.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum Section {
+    Seats,
+    Street(Street),
+    Showdown,
+    Summary,
+}
+
+// Variante y límite a partir de "Hold'em No Limit", "Omaha Pot Limit"...
+fn parse_game(game: &str) -> Option<(Variant, BettingLimit)> {
+    let (variant, limit) = if let Some(limit) = game.strip_prefix("Hold'em ") {
+        (Variant::Holdem, limit)
+    } else if let Some(limit) = game.strip_prefix("Omaha ") {
+        (Variant::Omaha, limit)
+    } else {
+        return None;
+    };
+
+    let limit = match limit {
+        "No Limit" => BettingLimit::NoLimit,
+        "Pot Limit" => BettingLimit::PotLimit,
+        "Limit" | "Fixed Limit" => BettingLimit::Fixed,
+        _ => return None,
+    };
+    Some((variant, limit))
+}
+
+// Interpreta una mano. `first_line` es el número de línea de la cabecera en el archivo.
+pub fn parse_hand(text: &str, first_line: usize) -> Result<Hand, ParseError> {
+    let mut hand = parse_with(&POKERSTARS, text, first_line)?;
+    hand.fast_fold = text.starts_with("PokerStars Zoom ");
+    Ok(hand)
+}
+
+pub(crate) fn parse_with(dialect: &Dialect, text: &str, first_line: usize) -> Result<Hand, ParseError> {
+    let mut ctx = Context { hand_id: None, first_line };
+    let lines: Vec<&str> = text.lines().collect();
+    let header = lines.first().copied().unwrap_or("");
+
+    // Cabecera: id, juego, ciegas y fecha
+    let caps = dialect.header.captures(header)
+        .ok_or_else(|| ctx.error(0, header, "Cabecera de mano no reconocida"))?;
+    ctx.hand_id = Some(caps[1].to_string());
+
+    let game = &caps[2];
+    if game.contains("Tournament") {
+        return Err(ctx.error(0, header, "Las manos de torneo no están soportadas"));
+    }
+    let (variant, limit) = parse_game(game)
+        .ok_or_else(|| ctx.error(0, header, format!("Juego no soportado: '{}'", game)))?;
+
+    let stakes = &caps[3];
+    let mut stake_parts = stakes.split_whitespace();
+    let blinds = stake_parts.next().unwrap_or("");
+    let (sb_text, bb_text) = blinds.split_once('/')
+        .ok_or_else(|| ctx.error(0, header, format!("Ciegas no reconocidas: '{}'", stakes)))?;
+    let small_blind = parse_amount(sb_text)
+        .ok_or_else(|| ctx.error(0, header, format!("Ciega pequeña no válida: '{}'", sb_text)))?;
+    let big_blind = parse_amount(bb_text)
+        .ok_or_else(|| ctx.error(0, header, format!("Ciega grande no válida: '{}'", bb_text)))?;
+    let currency = stake_parts.next().map(str::to_string).or_else(|| currency_symbol(sb_text));
+
+    let date = NaiveDateTime::parse_from_str(&caps[4], "%Y/%m/%d %H:%M:%S")
+        .map_err(|e| ctx.error(0, header, format!("Fecha no válida: {}", e)))?;
+    let time_zone = caps.get(5).map(|m| m.as_str().to_string());
+    let date = to_utc(date, time_zone.as_deref());
+
+    // Mesa y botón
+    let table_line = lines.get(1).copied().unwrap_or("");
+    let table_caps = TABLE_RE.captures(table_line)
+        .ok_or_else(|| ctx.error(1, table_line, "Línea de mesa no reconocida"))?;
+    let max_players: u8 = table_caps[2].parse()
+        .map_err(|_| ctx.error(1, table_line, "Número de asientos no válido"))?;
+    if !(2..=10).contains(&max_players) {
+        return Err(ctx.error(1, table_line, format!("Mesa de {} jugadores no soportada", max_players)));
+    }
+
+    let mut hand = Hand {
+        site: dialect.site,
+        id: caps[1].to_string(),
+        table: table_caps[1].to_string(),
+        date,
+        time_zone,
+        variant,
+        limit,
+        currency,
+        small_blind,
+        big_blind,
+        ante: 0,
+        max_players,
+        fast_fold: false,
+        button_seat: table_caps[3].parse().unwrap_or(0),
+        seats: Vec::new(),
+        hero: None,
+        hero_cards: Vec::new(),
+        actions: Vec::new(),
+        board: Vec::new(),
+        showdown: Vec::new(),
+        returned: Vec::new(),
+        collected: Vec::new(),
+        total_pot: 0,
+        side_pots: Vec::new(),
+        rake: 0,
+    };
+
+    let mut section = Section::Seats;
+    let mut players: Vec<String> = Vec::new();
+    let mut commitments = StreetCommitments::default();
+    let mut has_summary = false;
+
+    for (index, raw_line) in lines.iter().enumerate().skip(2) {
+        let line = raw_line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        // Cambios de sección
+        if line.starts_with("*** ") {
+            let street = if line.starts_with("*** HOLE CARDS ***") {
+                Some(Street::Preflop)
+            } else if line.starts_with("*** FLOP ***") {
+                Some(Street::Flop)
+            } else if line.starts_with("*** TURN ***") {
+                Some(Street::Turn)
+            } else if line.starts_with("*** RIVER ***") {
+                Some(Street::River)
+            } else {
+                None
+            };
+
+            if let Some(street) = street {
+                if street != Street::Preflop {
+                    // La última carta entre corchetes es la nueva ("[2c 7d Js] [Qh]")
+                    let new_cards = line.rfind('[')
+                        .and_then(|start| bracketed(&line[start..]))
+                        .and_then(Card::parse_list)
+                        .ok_or_else(|| ctx.error(index, line, "Cartas del board no válidas"))?;
+                    hand.board.extend(new_cards);
+                    commitments.new_street();
+                }
+                section = Section::Street(street);
+            } else if line.starts_with("*** SHOW DOWN ***") || line.starts_with("*** SHOWDOWN ***") {
+                section = Section::Showdown;
+            } else if line.starts_with("*** SUMMARY ***") {
+                section = Section::Summary;
+                has_summary = true;
+            } else if line.starts_with("*** FIRST") || line.starts_with("*** SECOND") {
+                return Err(ctx.error(index, line, "Las manos con el board repartido dos veces no están soportadas"));
+            } else {
+                return Err(ctx.error(index, line, "Sección no reconocida"));
+            }
+            continue;
+        }
+
+        match section {
+            Section::Seats if line.starts_with("Seat ") => {
+                let seat_caps = SEAT_RE.captures(line)
+                    .ok_or_else(|| ctx.error(index, line, "Asiento no reconocido"))?;
+                let stack = parse_amount(&seat_caps[3])
+                    .ok_or_else(|| ctx.error(index, line, "Stack no válido"))?;
+                let rest = &seat_caps[4];
+                hand.seats.push(Seat {
+                    number: seat_caps[1].parse().map_err(|_| ctx.error(index, line, "Número de asiento no válido"))?,
+                    player: seat_caps[2].to_string(),
+                    stack,
+                    sitting_out: rest.contains("sitting out") || rest.contains("out of hand"),
+                    anonymized: false,
+                });
+                players.push(seat_caps[2].to_string());
+            },
+            Section::Summary => {
+                if let Some(pot_caps) = TOTAL_POT_RE.captures(line) {
+                    hand.total_pot = parse_amount(&pot_caps[1])
+                        .ok_or_else(|| ctx.error(index, line, "Bote total no válido"))?;
+                    hand.rake = parse_amount(&pot_caps[3])
+                        .ok_or_else(|| ctx.error(index, line, "Rake no válido"))?;
+                    for fee in FEE_RE.captures_iter(line) {
+                        hand.rake += parse_amount(&fee[1])
+                            .ok_or_else(|| ctx.error(index, line, "Comisión no válida"))?;
+                    }
+                    for side in SIDE_POT_RE.captures_iter(&pot_caps[2]) {
+                        hand.side_pots.push(parse_amount(&side[1])
+                            .ok_or_else(|| ctx.error(index, line, "Bote secundario no válido"))?);
+                    }
+                }
+                // El resto del resumen (board, asientos) repite datos ya leídos
+            },
+            Section::Seats | Section::Street(_) | Section::Showdown => {
+                let street = match section {
+                    Section::Street(street) => street,
+                    Section::Showdown => Street::River,
+                    _ => Street::Preflop,
+                };
+                parse_action_line(dialect, &mut hand, &players, &mut commitments, street, line)
+                    .map_err(|message| ctx.error(index, line, message))?;
+            },
+        }
+    }
+
+    if !has_summary {
+        return Err(ctx.error(lines.len().saturating_sub(1), lines.last().copied().unwrap_or(""), "Mano incompleta: falta el resumen"));
+    }
+
+    hand.ante = hand.actions.iter()
+        .filter(|a| a.kind == ActionKind::Ante)
+        .map(|a| a.amount)
+        .max()
+        .unwrap_or(0);
+
+    validate_hand(&hand).map_err(|message| ctx.error(0, header, message))?;
+    Ok(hand)
+}
+
+// Interpreta una línea de las calles o del showdown
+fn parse_action_line(
+    dialect: &Dialect,
+    hand: &mut Hand,
+    players: &[String],
+    commitments: &mut StreetCommitments,
+    street: Street,
+    line: &str,
+) -> Result<(), String> {
+    if let Some(rest) = line.strip_prefix("Dealt to ") {
+        // GGPoker lista a todos los jugadores (con cartas solo si se vieron); nos quedamos con las del héroe
+        if let Some(cards) = bracketed(rest) {
+            let player = players.iter()
+                .filter(|name| rest.starts_with(&format!("{} [", name)))
+                .max_by_key(|name| name.len())
+                .ok_or_else(|| format!("Jugador desconocido en '{}'", line))?;
+            if dialect.hero_name.is_none_or(|hero| hero == player) {
+                hand.hero = Some(player.clone());
+                hand.hero_cards = Card::parse_list(cards).ok_or("Cartas propias no válidas")?;
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(rest) = line.strip_prefix("Uncalled bet (") {
+        let (amount, _) = rest.split_once(')').ok_or("Devolución no reconocida")?;
+        let player = player_suffix(rest, players, &format!("{}) returned to ", amount))
+            .ok_or("Jugador desconocido en la devolución")?;
+        hand.returned.push(Returned { player, amount: parse_amount(amount).ok_or("Importe devuelto no válido")? });
+        return Ok(());
+    }
+
+    if let Some(caps) = COLLECTED_RE.captures(line) {
+        if players.iter().any(|p| p == &caps[1]) {
+            let pot = match &caps[3] {
+                "pot" => None,
+                "main pot" => Some("main".to_string()),
+                _ => Some(format!("side-{}", caps.get(4).map(|m| m.as_str()).unwrap_or("1"))),
+            };
+            hand.collected.push(Collection {
+                player: caps[1].to_string(),
+                amount: parse_amount(&caps[2]).ok_or("Importe ganado no válido")?,
+                pot,
+            });
+            return Ok(());
+        }
+    }
+
+    if let Some((player, rest)) = split_player(line, players, " cashed out the hand for ") {
+        let amount = rest.split_whitespace().next().unwrap_or("");
+        hand.collected.push(Collection {
+            player,
+            amount: parse_amount(amount).ok_or("Importe del cash out no válido")?,
+            pot: Some("cash-out".to_string()),
+        });
+        return Ok(());
+    }
+
+    if let Some((player, rest)) = split_player(line, players, ": ") {
+        let rest = rest.trim();
+
+        if let Some(parsed) = parse_betting_action(rest) {
+            let action = commitments.apply(street, &player, parsed, hand.big_blind);
+            hand.actions.push(action);
+            return Ok(());
+        }
+        if let Some(shown) = rest.strip_prefix("shows ") {
+            let cards = bracketed(shown).and_then(Card::parse_list).ok_or("Cartas mostradas no válidas")?;
+            let description = shown.find("] (")
+                .map(|start| shown[start + 3..].trim_end_matches(')').to_string());
+            hand.showdown.push(ShownHand { player, cards, description, mucked: false });
+            return Ok(());
+        }
+        if let Some(mucked) = rest.strip_prefix("mucks hand") {
+            let cards = bracketed(mucked).and_then(Card::parse_list).unwrap_or_default();
+            hand.showdown.push(ShownHand { player, cards, description: None, mucked: true });
+            return Ok(());
+        }
+        if rest == "doesn't show hand" || rest == "sits out" || rest == "is sitting out" || rest.starts_with("shows a hand") {
+            return Ok(());
+        }
+        return Err(format!("Acción no reconocida: '{}'", rest));
+    }
+
+    // Chat y mensajes de la mesa
+    if line.contains(" said, \"") || line.contains(" joins the table at seat #") {
+        return Ok(());
+    }
+    if IGNORED_SUFFIXES.iter().any(|suffix| line.ends_with(suffix)) {
+        return Ok(());
+    }
+
+    Err("Línea no reconocida".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand_history::{parse_text, split_hands};
+
+    const NLHE_6MAX: &str = include_str!("../../tests/fixtures/hand_history/pokerstars/nlhe_6max.txt");
+    const PLO_9MAX: &str = include_str!("../../tests/fixtures/hand_history/pokerstars/plo_9max.txt");
+    const ANTES_ZOOM: &str = include_str!("../../tests/fixtures/hand_history/pokerstars/nlhe_antes_zoom.txt");
+    const INVALID: &str = include_str!("../../tests/fixtures/hand_history/pokerstars/invalid.txt");
+
+    fn action<'a>(hand: &'a Hand, street: Street, player: &str) -> Vec<&'a Action> {
+        hand.actions.iter().filter(|a| a.street == street && a.player == player).collect()
+    }
+
+    #[test]
+    fn splits_files_into_hands() {
+        let hands = split_hands(NLHE_6MAX, is_hand_header);
+        assert_eq!(hands.len(), 3);
+        assert_eq!(hands[0].0, 1);
+        assert!(hands[1].1.starts_with("PokerStars Hand #"));
+    }
+
+    #[test]
+    fn parses_header_seats_and_blinds() {
+        let outcome = parse_text(NLHE_6MAX).unwrap();
+        assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+        let hand = &outcome.hands[0];
+
+        assert_eq!(hand.site, Site::PokerStars);
+        assert_eq!(hand.id, "245000000001");
+        assert_eq!(hand.table, "Aaltje II");
+        assert_eq!(hand.variant, Variant::Holdem);
+        assert_eq!(hand.limit, BettingLimit::NoLimit);
+        assert_eq!(hand.currency.as_deref(), Some("USD"));
+        assert_eq!((hand.small_blind, hand.big_blind), (5, 10));
+        assert_eq!(hand.max_players, 6);
+        assert_eq!(hand.button_seat, 3);
+        assert_eq!(hand.time_zone.as_deref(), Some("CET"));
+        assert_eq!(hand.date.format("%Y-%m-%d %H:%M:%S").to_string(), "2024-03-02 20:14:05");
+
+        assert_eq!(hand.seats.len(), 6);
+        assert_eq!(hand.seats[3].player, "Villain One");
+        assert_eq!(hand.seats[2].stack, 1240);
+        assert!(hand.seats[5].sitting_out);
+
+        assert_eq!(hand.hero.as_deref(), Some("HeroPlayer"));
+        assert_eq!(hand.hero_cards, Card::parse_list("Ah Kd").unwrap());
+    }
+
+    #[test]
+    fn tracks_actions_per_street_and_raise_amounts() {
+        let hand = &parse_text(NLHE_6MAX).unwrap().hands[0];
+
+        let hero_pre = action(hand, Street::Preflop, "HeroPlayer");
+        assert_eq!(hero_pre.len(), 1);
+        assert_eq!(hero_pre[0].kind, ActionKind::Raise);
+        assert_eq!((hero_pre[0].amount, hero_pre[0].to), (30, Some(30)));
+
+        // La ciega grande completa hasta la subida: solo pone la diferencia
+        let bb_pre = action(hand, Street::Preflop, "bb:player");
+        assert_eq!(bb_pre[0].kind, ActionKind::BigBlind);
+        assert_eq!(bb_pre[1].kind, ActionKind::Call);
+        assert_eq!(bb_pre[1].amount, 20);
+
+        let flop = action(hand, Street::Flop, "bb:player");
+        assert_eq!(flop.iter().map(|a| a.kind).collect::<Vec<_>>(), vec![ActionKind::Check, ActionKind::Raise]);
+        assert_eq!(flop[1].amount, 120);
+        assert!(flop[1].all_in);
+
+        assert_eq!(hand.board, Card::parse_list("2c 7d Js Qh 3s").unwrap());
+    }
+
+    #[test]
+    fn reads_showdown_pot_and_rake() {
+        let hand = &parse_text(NLHE_6MAX).unwrap().hands[0];
+
+        assert_eq!(hand.showdown.len(), 2);
+        assert_eq!(hand.showdown[0].player, "bb:player");
+        assert_eq!(hand.showdown[0].description.as_deref(), Some("three of a kind, Jacks"));
+        assert_eq!(hand.total_pot, 305);
+        assert_eq!(hand.rake, 14);
+        assert_eq!(hand.collected, vec![Collection { player: "bb:player".to_string(), amount: 291, pot: None }]);
+
+        assert_eq!(hand.net("bb:player"), 291 - 150);
+        assert_eq!(hand.net("HeroPlayer"), -150);
+        assert_eq!(hand.net("Villain One"), -5);
+    }
+
+    #[test]
+    fn handles_uncalled_bets_and_side_pots() {
+        let outcome = parse_text(NLHE_6MAX).unwrap();
+
+        let walk = &outcome.hands[1];
+        assert_eq!(walk.returned, vec![Returned { player: "HeroPlayer".to_string(), amount: 70 }]);
+        assert_eq!(walk.net("HeroPlayer"), 35);
+        assert!(walk.board.is_empty());
+
+        let side = &outcome.hands[2];
+        assert_eq!(side.side_pots, vec![770]);
+        assert_eq!(side.collected.len(), 2);
+        assert_eq!(side.collected[0].pot.as_deref(), Some("side-1"));
+        assert_eq!(side.collected[1].pot.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn parses_pot_limit_omaha_full_ring() {
+        let outcome = parse_text(PLO_9MAX).unwrap();
+        assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+        let hand = &outcome.hands[0];
+
+        assert_eq!(hand.variant, Variant::Omaha);
+        assert_eq!(hand.limit, BettingLimit::PotLimit);
+        assert_eq!(hand.max_players, 9);
+        assert_eq!(hand.seats.len(), 9);
+        assert_eq!(hand.hero_cards.len(), 4);
+        assert!(hand.showdown[1].mucked);
+        assert_eq!(hand.currency.as_deref(), Some("EUR"));
+    }
+
+    #[test]
+    fn parses_heads_up_with_antes_and_dead_blinds() {
+        let outcome = parse_text(ANTES_ZOOM).unwrap();
+        assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+        let hand = &outcome.hands[0];
+
+        assert_eq!(hand.max_players, 2);
+        assert_eq!(hand.ante, 1000);
+        assert_eq!(hand.currency, None);
+        let antes = hand.actions.iter().filter(|a| a.kind == ActionKind::Ante).count();
+        assert_eq!(antes, 2);
+
+        let zoom = &outcome.hands[1];
+        assert_eq!(zoom.id, "245000000011");
+        assert!(zoom.fast_fold);
+        assert!(!hand.fast_fold);
+        assert_eq!(zoom.currency.as_deref(), Some("$"));
+        assert_eq!(zoom.hero, None);
+        let dead = zoom.actions.iter().find(|a| a.kind == ActionKind::SmallAndBigBlind).unwrap();
+        assert_eq!(dead.amount, 15);
+        assert_eq!(zoom.net("NewGuy"), -15);
+    }
+
+    #[test]
+    fn reports_unparseable_hands_with_line_numbers() {
+        let outcome = parse_text(INVALID).unwrap();
+
+        // Las manos válidas del archivo se leen aunque otras fallen
+        assert_eq!(outcome.hands.len(), 1);
+        assert_eq!(outcome.errors.len(), 3);
+
+        let unknown = &outcome.errors[0];
+        assert_eq!(unknown.hand_id.as_deref(), Some("245000000020"));
+        assert_eq!(unknown.line, 11);
+        assert_eq!(unknown.content, "Villain2: dances");
+
+        let tournament = &outcome.errors[1];
+        assert!(tournament.message.contains("torneo"));
+
+        let truncated = &outcome.errors[2];
+        assert!(truncated.message.contains("resumen"));
+    }
+
+    #[test]
+    fn parses_amounts_in_hundredths() {
+        use crate::hand_history::parse_amount;
+        assert_eq!(parse_amount("$1,234.56"), Some(123456));
+        assert_eq!(parse_amount("0.05€"), Some(5));
+        assert_eq!(parse_amount("$2"), Some(200));
+        assert_eq!(parse_amount("1.5"), Some(150));
+        assert_eq!(parse_amount("abc"), None);
+    }
+}
@@ -0,0 +1,413 @@
+// src-tauri/src/hand_history/winamax.rs
+// Historiales de Winamax (cash y Go Fast, Hold'em y Omaha)
+use chrono::NaiveDateTime;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use super::model::*;
+use super::{bracketed, currency_symbol, parse_amount, parse_betting_action, split_player, to_utc, validate_hand, StreetCommitments};
+
+static HEADER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Winamax Poker - (.+?) - HandId: #([\d-]+) - (.+?) \(([^()]*)\) - (\d{4}/\d{1,2}/\d{1,2} \d{1,2}:\d{2}:\d{2})(?: (\w+))?").unwrap()
+});
+
+static TABLE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Table: '(.+)' (\d+)-max \((real|play) money\) Seat #(\d+) is the button").unwrap()
+});
+
+static SEAT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Seat (\d+): (.+?) \(([^,()]+)(?:, [^)]*)?\)(.*)$").unwrap()
+});
+
+static TOTAL_POT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Total pot (\S+)(.*?)\| (?:No rake|Rake (\S+))").unwrap()
+});
+
+static SIDE_POT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"Side pot(?:[- ]\d+)? (\S+?)\.?(?:\s|$)").unwrap()
+});
+
+pub fn is_hand_header(line: &str) -> bool {
+    line.starts_with("Winamax Poker - ") && line.contains(" - HandId: #")
+}
+
+struct Context {
+    hand_id: Option<String>,
+    first_line: usize,
+}
+
+impl Context {
+    fn error(&self, index: usize, content: &str, message: impl Into<String>) -> ParseError {
+        ParseError {
+            hand_id: self.hand_id.clone(),
+            line: self.first_line + index,
+            content: content.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum Section {
+    Seats,
+    Street(Street),
+    Showdown,
+    Summary,
+}
+
+// "Holdem no limit", "Omaha pot limit"...
+fn parse_game(game: &str) -> Option<(Variant, BettingLimit)> {
+    let game = game.to_lowercase();
+    let (variant, limit) = if let Some(limit) = game.strip_prefix("holdem ") {
+        (Variant::Holdem, limit)
+    } else if let Some(limit) = game.strip_prefix("omaha ") {
+        (Variant::Omaha, limit)
+    } else {
+        return None;
+    };
+
+    let limit = match limit {
+        "no limit" => BettingLimit::NoLimit,
+        "pot limit" => BettingLimit::PotLimit,
+        "limit" | "fixed limit" => BettingLimit::Fixed,
+        _ => return None,
+    };
+    Some((variant, limit))
+}
+
+// Interpreta una mano. `first_line` es el número de línea de la cabecera en el archivo.
+pub fn parse_hand(text: &str, first_line: usize) -> Result<Hand, ParseError> {
+    let mut ctx = Context { hand_id: None, first_line };
+    let lines: Vec<&str> = text.lines().collect();
+    let header = lines.first().copied().unwrap_or("");
+
+    let caps = HEADER_RE.captures(header)
+        .ok_or_else(|| ctx.error(0, header, "Cabecera de mano no reconocida"))?;
+    ctx.hand_id = Some(caps[2].to_string());
+
+    let mode = &caps[1];
+    if mode.starts_with("Tournament") {
+        return Err(ctx.error(0, header, "Las manos de torneo no están soportadas"));
+    }
+    let game = &caps[3];
+    let (variant, limit) = parse_game(game)
+        .ok_or_else(|| ctx.error(0, header, format!("Juego no soportado: '{}'", game)))?;
+
+    // "0.01€/0.02€" o, con ante, "0.01€/0.01€/0.02€"
+    let stakes: Vec<&str> = caps[4].split('/').collect();
+    let (ante_text, sb_text, bb_text) = match stakes.as_slice() {
+        [sb, bb] => (None, *sb, *bb),
+        [ante, sb, bb] => (Some(*ante), *sb, *bb),
+        _ => return Err(ctx.error(0, header, format!("Ciegas no reconocidas: '{}'", &caps[4]))),
+    };
+    let small_blind = parse_amount(sb_text)
+        .ok_or_else(|| ctx.error(0, header, format!("Ciega pequeña no válida: '{}'", sb_text)))?;
+    let big_blind = parse_amount(bb_text)
+        .ok_or_else(|| ctx.error(0, header, format!("Ciega grande no válida: '{}'", bb_text)))?;
+    let ante = match ante_text {
+        Some(text) => parse_amount(text).ok_or_else(|| ctx.error(0, header, format!("Ante no válido: '{}'", text)))?,
+        None => 0,
+    };
+
+    let date = NaiveDateTime::parse_from_str(&caps[5], "%Y/%m/%d %H:%M:%S")
+        .map_err(|e| ctx.error(0, header, format!("Fecha no válida: {}", e)))?;
+    let time_zone = caps.get(6).map(|m| m.as_str().to_string());
+    let date = to_utc(date, time_zone.as_deref());
+
+    let table_line = lines.get(1).copied().unwrap_or("");
+    let table_caps = TABLE_RE.captures(table_line)
+        .ok_or_else(|| ctx.error(1, table_line, "Línea de mesa no reconocida"))?;
+    let max_players: u8 = table_caps[2].parse()
+        .map_err(|_| ctx.error(1, table_line, "Número de asientos no válido"))?;
+    if !(2..=10).contains(&max_players) {
+        return Err(ctx.error(1, table_line, format!("Mesa de {} jugadores no soportada", max_players)));
+    }
+    let currency = if &table_caps[3] == "real" { currency_symbol(bb_text) } else { None };
+
+    let mut hand = Hand {
+        site: Site::Winamax,
+        id: caps[2].to_string(),
+        table: table_caps[1].to_string(),
+        date,
+        time_zone,
+        variant,
+        limit,
+        currency,
+        small_blind,
+        big_blind,
+        ante,
+        max_players,
+        fast_fold: mode.starts_with("Go Fast"),
+        button_seat: table_caps[4].parse().unwrap_or(0),
+        seats: Vec::new(),
+        hero: None,
+        hero_cards: Vec::new(),
+        actions: Vec::new(),
+        board: Vec::new(),
+        showdown: Vec::new(),
+        returned: Vec::new(),
+        collected: Vec::new(),
+        total_pot: 0,
+        side_pots: Vec::new(),
+        rake: 0,
+    };
+
+    let mut section = Section::Seats;
+    let mut players: Vec<String> = Vec::new();
+    let mut commitments = StreetCommitments::default();
+    // Winamax no escribe las apuestas no igualadas: se deducen de las acciones
+    let mut uncalled: Vec<(String, Chips)> = Vec::new();
+    let mut has_summary = false;
+
+    for (index, raw_line) in lines.iter().enumerate().skip(2) {
+        let line = raw_line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with("*** ") {
+            let street = if line.starts_with("*** ANTE/BLINDS ***") || line.starts_with("*** PRE-FLOP ***") {
+                Some(Street::Preflop)
+            } else if line.starts_with("*** FLOP ***") {
+                Some(Street::Flop)
+            } else if line.starts_with("*** TURN ***") {
+                Some(Street::Turn)
+            } else if line.starts_with("*** RIVER ***") {
+                Some(Street::River)
+            } else {
+                None
+            };
+
+            if let Some(street) = street {
+                if street != Street::Preflop {
+                    let new_cards = line.rfind('[')
+                        .and_then(|start| bracketed(&line[start..]))
+                        .and_then(Card::parse_list)
+                        .ok_or_else(|| ctx.error(index, line, "Cartas del board no válidas"))?;
+                    hand.board.extend(new_cards);
+                    uncalled.extend(commitments.uncalled());
+                    commitments.new_street();
+                }
+                section = Section::Street(street);
+            } else if line.starts_with("*** SHOW DOWN ***") {
+                section = Section::Showdown;
+            } else if line.starts_with("*** SUMMARY ***") {
+                section = Section::Summary;
+                has_summary = true;
+            } else {
+                return Err(ctx.error(index, line, "Sección no reconocida"));
+            }
+            continue;
+        }
+
+        match section {
+            Section::Seats => {
+                let seat_caps = SEAT_RE.captures(line)
+                    .ok_or_else(|| ctx.error(index, line, "Asiento no reconocido"))?;
+                let stack = parse_amount(&seat_caps[3])
+                    .ok_or_else(|| ctx.error(index, line, "Stack no válido"))?;
+                let rest = &seat_caps[4];
+                hand.seats.push(Seat {
+                    number: seat_caps[1].parse().map_err(|_| ctx.error(index, line, "Número de asiento no válido"))?,
+                    player: seat_caps[2].to_string(),
+                    stack,
+                    sitting_out: rest.contains("sitting out") || rest.contains("out of hand"),
+                    anonymized: false,
+                });
+                players.push(seat_caps[2].to_string());
+            },
+            Section::Summary => {
+                if let Some(pot_caps) = TOTAL_POT_RE.captures(line) {
+                    hand.total_pot = parse_amount(&pot_caps[1])
+                        .ok_or_else(|| ctx.error(index, line, "Bote total no válido"))?;
+                    if let Some(rake) = pot_caps.get(3) {
+                        hand.rake = parse_amount(rake.as_str())
+                            .ok_or_else(|| ctx.error(index, line, "Rake no válido"))?;
+                    }
+                    for side in SIDE_POT_RE.captures_iter(&pot_caps[2]) {
+                        hand.side_pots.push(parse_amount(&side[1])
+                            .ok_or_else(|| ctx.error(index, line, "Bote secundario no válido"))?);
+                    }
+                }
+            },
+            Section::Street(_) | Section::Showdown => {
+                let street = match section {
+                    Section::Street(street) => street,
+                    _ => Street::River,
+                };
+                parse_action_line(&mut hand, &players, &mut commitments, street, line)
+                    .map_err(|message| ctx.error(index, line, message))?;
+            },
+        }
+    }
+
+    if !has_summary {
+        return Err(ctx.error(lines.len().saturating_sub(1), lines.last().copied().unwrap_or(""), "Mano incompleta: falta el resumen"));
+    }
+    uncalled.extend(commitments.uncalled());
+
+    // Según la versión del cliente el bote total incluye o no la apuesta no igualada
+    let invested: Chips = hand.seats.iter().map(|s| hand.invested(&s.player)).sum();
+    let uncalled_total: Chips = uncalled.iter().map(|(_, amount)| amount).sum();
+    if invested != hand.total_pot && invested - uncalled_total == hand.total_pot {
+        hand.returned = uncalled.into_iter()
+            .map(|(player, amount)| Returned { player, amount })
+            .collect();
+    }
+
+    if hand.ante == 0 {
+        hand.ante = hand.actions.iter()
+            .filter(|a| a.kind == ActionKind::Ante)
+            .map(|a| a.amount)
+            .max()
+            .unwrap_or(0);
+    }
+
+    validate_hand(&hand).map_err(|message| ctx.error(0, header, message))?;
+    Ok(hand)
+}
+
+// Interpreta una línea de las calles o del showdown ("Jugador acción", sin dos puntos)
+fn parse_action_line(
+    hand: &mut Hand,
+    players: &[String],
+    commitments: &mut StreetCommitments,
+    street: Street,
+    line: &str,
+) -> Result<(), String> {
+    if let Some(rest) = line.strip_prefix("Dealt to ") {
+        if let Some(cards) = bracketed(rest) {
+            let player = players.iter()
+                .filter(|name| rest.starts_with(&format!("{} [", name)))
+                .max_by_key(|name| name.len())
+                .ok_or_else(|| format!("Jugador desconocido en '{}'", line))?;
+            hand.hero = Some(player.clone());
+            hand.hero_cards = Card::parse_list(cards).ok_or("Cartas propias no válidas")?;
+        }
+        return Ok(());
+    }
+
+    if let Some((player, rest)) = split_player(line, players, " collected ") {
+        let (amount, pot) = rest.split_once(" from ").ok_or("Bote no reconocido")?;
+        let pot = match pot.trim() {
+            "pot" => None,
+            "main pot" => Some("main".to_string()),
+            side => {
+                let number = side.trim_start_matches("side pot").trim_start_matches(['-', ' ']);
+                Some(format!("side-{}", if number.is_empty() { "1" } else { number }))
+            },
+        };
+        hand.collected.push(Collection {
+            player,
+            amount: parse_amount(amount).ok_or("Importe ganado no válido")?,
+            pot,
+        });
+        return Ok(());
+    }
+
+    if let Some((player, rest)) = split_player(line, players, " ") {
+        let rest = rest.trim();
+
+        if let Some(parsed) = parse_betting_action(rest) {
+            let action = commitments.apply(street, &player, parsed, hand.big_blind);
+            hand.actions.push(action);
+            return Ok(());
+        }
+        if let Some(shown) = rest.strip_prefix("shows ") {
+            let cards = bracketed(shown).and_then(Card::parse_list).ok_or("Cartas mostradas no válidas")?;
+            let description = shown.find("] (")
+                .map(|start| shown[start + 3..].trim_end_matches(')').to_string());
+            hand.showdown.push(ShownHand { player, cards, description, mucked: false });
+            return Ok(());
+        }
+        if rest.starts_with("mucks") {
+            hand.showdown.push(ShownHand { player, cards: Vec::new(), description: None, mucked: true });
+            return Ok(());
+        }
+        if rest == "doesn't show hand" || rest == "sits out" || rest == "is back" || rest == "leaves the table" {
+            return Ok(());
+        }
+        return Err(format!("Acción no reconocida: '{}'", rest));
+    }
+
+    Err("Línea no reconocida".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand_history::{detect_site, parse_text};
+
+    const CASH: &str = include_str!("../../tests/fixtures/hand_history/winamax/cash_5max.txt");
+
+    #[test]
+    fn detects_winamax_files() {
+        assert_eq!(detect_site(CASH), Some(Site::Winamax));
+    }
+
+    #[test]
+    fn parses_cash_hand_with_showdown() {
+        let outcome = parse_text(CASH).unwrap();
+        let hand = &outcome.hands[0];
+
+        assert_eq!(hand.site, Site::Winamax);
+        assert_eq!(hand.id, "18876587-492-1645822406");
+        assert_eq!(hand.table, "Nice 05");
+        assert_eq!(hand.max_players, 5);
+        assert_eq!(hand.button_seat, 3);
+        assert_eq!(hand.currency.as_deref(), Some("€"));
+        assert_eq!(hand.time_zone.as_deref(), Some("UTC"));
+        assert_eq!((hand.small_blind, hand.big_blind), (1, 2));
+
+        assert_eq!(hand.seats[2].player, "el tigre");
+        assert_eq!(hand.seats[1].stack, 204);
+        assert_eq!(hand.hero.as_deref(), Some("HeroW"));
+        assert_eq!(hand.hero_cards, Card::parse_list("Kd Kc").unwrap());
+
+        let raise = hand.actions.iter().find(|a| a.kind == ActionKind::Raise).unwrap();
+        assert_eq!((raise.player.as_str(), raise.amount, raise.to), ("HeroW", 6, Some(6)));
+        assert_eq!(hand.board, Card::parse_list("6c 4h 2d Js 9h").unwrap());
+
+        assert_eq!(hand.showdown.len(), 2);
+        assert_eq!(hand.showdown[1].description.as_deref(), Some("One pair : Jacks"));
+        assert_eq!((hand.total_pot, hand.rake), (69, 3));
+        assert_eq!(hand.collected[0].amount, 66);
+        assert!(hand.returned.is_empty());
+    }
+
+    #[test]
+    fn infers_uncalled_bets() {
+        let outcome = parse_text(CASH).unwrap();
+
+        // El bote total excluye la apuesta que nadie igualó
+        let uncalled = &outcome.hands[1];
+        assert_eq!(uncalled.total_pot, 15);
+        assert_eq!(uncalled.returned, vec![Returned { player: "HeroW".to_string(), amount: 10 }]);
+        assert_eq!(uncalled.invested("HeroW"), 6);
+
+        // Aquí el bote incluye la ciega no igualada: no se devuelve nada
+        let walk = &outcome.hands[2];
+        assert!(walk.returned.is_empty());
+        assert_eq!(walk.total_pot, 7);
+    }
+
+    #[test]
+    fn parses_go_fast_omaha() {
+        let outcome = parse_text(CASH).unwrap();
+        let hand = &outcome.hands[2];
+
+        assert!(hand.fast_fold);
+        assert_eq!(hand.variant, Variant::Omaha);
+        assert_eq!(hand.limit, BettingLimit::PotLimit);
+        assert_eq!(hand.hero_cards.len(), 4);
+    }
+
+    #[test]
+    fn rejects_tournament_hands() {
+        let outcome = parse_text(CASH).unwrap();
+
+        assert_eq!(outcome.hands.len(), 3);
+        assert_eq!(outcome.errors.len(), 1);
+        assert_eq!(outcome.errors[0].hand_id.as_deref(), Some("2033443-87-1645822800"));
+        assert!(outcome.errors[0].message.contains("torneo"));
+    }
+}
@@ -0,0 +1,454 @@
+// src-tauri/src/hand_import.rs
+// Importación en segundo plano de las carpetas de historiales configuradas.
+// Por cada archivo se guarda hasta dónde se ha leído, de modo que solo se
+// interpretan las manos nuevas cuando la sala añade más al final.
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+use crate::config_state::ConfigState;
+use crate::hand_history::{self, ParseError};
+use crate::hand_db;
+
+// Eventos emitidos al frontend
+pub const HAND_IMPORT_PROGRESS_EVENT: &str = "hand_import_progress";
+pub const HANDS_IMPORTED_EVENT: &str = "hands_imported";
+
+// Cada cuánto se revisan las carpetas
+const SCAN_INTERVAL: Duration = Duration::from_secs(5);
+
+// Si un archivo lleva este tiempo sin cambios, su última mano se da por completa
+// aunque no termine en línea en blanco
+const IDLE_COMPLETE: Duration = Duration::from_secs(30);
+
+// Evita dos importaciones a la vez (automática y manual)
+static IMPORT_RUNNING: AtomicBool = AtomicBool::new(false);
+
+static IMPORT_STATUS: Lazy<Mutex<ImportStatus>> = Lazy::new(|| Mutex::new(ImportStatus::default()));
+
+// Posición de lectura de un archivo de historial
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct FileState {
+    // Bytes ya interpretados
+    offset: u64,
+    // Líneas ya interpretadas, para dar números de línea absolutos en los errores
+    lineas: usize,
+    manos: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct ImportState {
+    archivos: HashMap<String, FileState>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ImportStatus {
+    pub en_curso: bool,
+    pub ultimo_escaneo: Option<String>,
+    pub ultimo_error: Option<String>,
+    pub archivos: usize,
+    pub manos_guardadas: usize,
+}
+
+// Progreso enviado por cada archivo con manos nuevas
+#[derive(Debug, Serialize, Clone)]
+pub struct ImportProgress {
+    pub archivo: String,
+    pub indice: usize,
+    pub total: usize,
+    pub nuevas: usize,
+    pub duplicadas: usize,
+    pub errores: Vec<ParseError>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ScanSummary {
+    pub archivos: usize,
+    pub archivos_modificados: usize,
+    pub nuevas: usize,
+    pub duplicadas: usize,
+    pub errores: usize,
+}
+
+fn state_path() -> Result<PathBuf, String> {
+    let app_data_dir = tauri::api::path::app_data_dir(&tauri::Config::default())
+        .ok_or("No se pudo determinar el directorio de datos")?;
+    Ok(app_data_dir.join("hand_import_state.json"))
+}
+
+fn read_state() -> Result<ImportState, String> {
+    let path = state_path()?;
+    if !path.exists() {
+        return Ok(ImportState::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Error al leer estado de importación: {}", e))?;
+    // Si el estado se pierde se vuelve a leer todo; los duplicados se descartan
+    Ok(serde_json::from_str(&content).unwrap_or_default())
+}
+
+fn write_state(state: &ImportState) -> Result<(), String> {
+    let path = state_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Error al crear directorio de datos: {}", e))?;
+    }
+
+    let serialized = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Error al serializar estado de importación: {}", e))?;
+
+    let tmp_path = path.with_extension("json.tmp");
+    {
+        let mut file = fs::File::create(&tmp_path)
+            .map_err(|e| format!("Error al escribir estado de importación: {}", e))?;
+        file.write_all(serialized.as_bytes())
+            .and_then(|_| file.sync_all())
+            .map_err(|e| format!("Error al escribir estado de importación: {}", e))?;
+    }
+    fs::rename(&tmp_path, &path)
+        .map_err(|e| format!("Error al reemplazar estado de importación: {}", e))
+}
+
+// Archivos .txt de una carpeta y sus subcarpetas (las salas guardan uno por mesa o por día)
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("No se pudo leer la carpeta {}: {}", dir.display(), e);
+            return;
+        },
+    };
+
+    for entry in entries.flatten() {
+        // file_type no sigue los enlaces simbólicos: se ignoran para no entrar en
+        // ciclos ni leer dos veces la misma carpeta
+        let Ok(file_type) = entry.file_type() else { continue };
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_files(&path, files);
+        } else if file_type.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("txt")) {
+            files.push(path);
+        }
+    }
+}
+
+// Bytes del bloque que contienen manos completas. La última mano se deja para la
+// siguiente pasada si la sala podría estar escribiéndola todavía.
+fn complete_prefix_len(bytes: &[u8], idle: bool) -> usize {
+    let ends_with_blank_line = bytes.ends_with(b"\n\n") || bytes.ends_with(b"\n\r\n");
+    if idle || ends_with_blank_line {
+        return bytes.len();
+    }
+
+    let mut last_header = 0;
+    let mut line_start = 0;
+    for (i, byte) in bytes.iter().enumerate() {
+        if *byte == b'\n' {
+            if hand_history::is_hand_header(&String::from_utf8_lossy(&bytes[line_start..i])) {
+                last_header = line_start;
+            }
+            line_start = i + 1;
+        }
+    }
+    if line_start < bytes.len() && hand_history::is_hand_header(&String::from_utf8_lossy(&bytes[line_start..])) {
+        last_header = line_start;
+    }
+    last_header
+}
+
+// Lee e interpreta lo añadido a un archivo desde la última pasada.
+// Devuelve None si no hay nada nuevo completo.
+fn read_new_hands(path: &Path, file_state: &mut FileState) -> Result<Option<hand_history::ParseOutcome>, String> {
+    let metadata = fs::metadata(path)
+        .map_err(|e| format!("Error al leer {}: {}", path.display(), e))?;
+
+    // Archivo reemplazado o truncado: se vuelve a leer desde el principio
+    if metadata.len() < file_state.offset {
+        *file_state = FileState::default();
+    }
+    if metadata.len() == file_state.offset {
+        return Ok(None);
+    }
+
+    let mut file = fs::File::open(path)
+        .map_err(|e| format!("Error al abrir {}: {}", path.display(), e))?;
+    file.seek(SeekFrom::Start(file_state.offset))
+        .map_err(|e| format!("Error al leer {}: {}", path.display(), e))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)
+        .map_err(|e| format!("Error al leer {}: {}", path.display(), e))?;
+
+    let idle = metadata.modified().ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|elapsed| elapsed >= IDLE_COMPLETE);
+    let consumed = complete_prefix_len(&bytes, idle);
+    if consumed == 0 {
+        return Ok(None);
+    }
+
+    let chunk = &bytes[..consumed];
+    let text = String::from_utf8_lossy(chunk);
+    let outcome = if text.trim().is_empty() {
+        Ok(hand_history::ParseOutcome::default())
+    } else {
+        hand_history::parse_text(&text)
+    };
+
+    file_state.offset += consumed as u64;
+    let first_line = file_state.lineas;
+    file_state.lineas += chunk.iter().filter(|b| **b == b'\n').count();
+
+    // Un archivo que no es un historial se salta hasta que vuelva a crecer
+    let mut outcome = outcome.map_err(|e| format!("{}: {}", path.display(), e))?;
+    for error in outcome.errors.iter_mut() {
+        error.line += first_line;
+    }
+    file_state.manos += outcome.hands.len();
+    Ok(Some(outcome))
+}
+
+// Revisa las carpetas e importa las manos nuevas
+pub fn scan(folders: &[String], mut on_progress: impl FnMut(&ImportProgress)) -> Result<ScanSummary, String> {
+    if IMPORT_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("Ya hay una importación en curso".to_string());
+    }
+    set_running(true);
+
+    let result = scan_folders(folders, &mut on_progress);
+
+    IMPORT_RUNNING.store(false, Ordering::SeqCst);
+    if let Ok(mut status) = IMPORT_STATUS.lock() {
+        status.en_curso = false;
+        status.ultimo_escaneo = Some(chrono::Local::now().to_rfc3339());
+        match &result {
+            Ok(summary) => {
+                status.ultimo_error = None;
+                status.archivos = summary.archivos;
+            },
+            Err(e) => status.ultimo_error = Some(e.clone()),
+        }
+        status.manos_guardadas = hand_db::count().unwrap_or(status.manos_guardadas);
+    }
+    result
+}
+
+fn set_running(running: bool) {
+    if let Ok(mut status) = IMPORT_STATUS.lock() {
+        status.en_curso = running;
+    }
+}
+
+fn scan_folders(folders: &[String], on_progress: &mut impl FnMut(&ImportProgress)) -> Result<ScanSummary, String> {
+    let mut files = Vec::new();
+    for folder in folders {
+        collect_files(Path::new(folder), &mut files);
+    }
+    files.sort();
+
+    let mut state = read_state()?;
+    let mut summary = ScanSummary { archivos: files.len(), ..Default::default() };
+    let mut state_changed = false;
+
+    for (index, path) in files.iter().enumerate() {
+        let key = path.to_string_lossy().to_string();
+        let mut file_state = state.archivos.get(&key).cloned().unwrap_or_default();
+
+        let outcome = match read_new_hands(path, &mut file_state) {
+            Ok(Some(outcome)) => outcome,
+            Ok(None) => continue,
+            Err(e) => {
+                eprintln!("Error al importar historial: {}", e);
+                summary.errores += 1;
+                state.archivos.insert(key, file_state);
+                state_changed = true;
+                continue;
+            },
+        };
+
+        // Primero se guardan las manos y después la posición: si algo falla
+        // entre medias, la siguiente pasada relee y descarta los duplicados
+        let (nuevas, duplicadas) = hand_db::insert_hands(&outcome.hands)?;
+        state.archivos.insert(key.clone(), file_state);
+        state_changed = true;
+
+        summary.archivos_modificados += 1;
+        summary.nuevas += nuevas;
+        summary.duplicadas += duplicadas;
+        summary.errores += outcome.errors.len();
+
+        on_progress(&ImportProgress {
+            archivo: key,
+            indice: index + 1,
+            total: files.len(),
+            nuevas,
+            duplicadas,
+            errores: outcome.errors,
+        });
+    }
+
+    // Se olvidan los archivos que ya no están en las carpetas configuradas
+    let before = state.archivos.len();
+    state.archivos.retain(|path, _| files.iter().any(|f| f.to_string_lossy() == path.as_str()));
+    state_changed |= state.archivos.len() != before;
+
+    if state_changed {
+        write_state(&state)?;
+    }
+    Ok(summary)
+}
+
+pub fn status() -> ImportStatus {
+    IMPORT_STATUS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+// Importa las carpetas configuradas emitiendo el progreso al frontend
+pub fn import_now<R: Runtime>(app_handle: &AppHandle<R>) -> Result<ScanSummary, String> {
+    let folders = app_handle.state::<ConfigState>().get().carpetas_historial;
+    let summary = scan(&folders, |progress| {
+        let _ = app_handle.emit_all(HAND_IMPORT_PROGRESS_EVENT, progress);
+    })?;
+    if summary.nuevas > 0 {
+        let _ = app_handle.emit_all(HANDS_IMPORTED_EVENT, &summary);
+    }
+    Ok(summary)
+}
+
+// Revisa periódicamente las carpetas configuradas
+pub fn start_hand_import<R: Runtime>(app_handle: AppHandle<R>) {
+    thread::spawn(move || loop {
+        thread::sleep(SCAN_INTERVAL);
+
+        if app_handle.state::<ConfigState>().get().carpetas_historial.is_empty() || IMPORT_RUNNING.load(Ordering::SeqCst) {
+            continue;
+        }
+        match import_now(&app_handle) {
+            Ok(summary) if summary.nuevas > 0 => {
+                println!("Historiales importados: {} manos nuevas", summary.nuevas);
+            },
+            Ok(_) => {},
+            Err(e) => eprintln!("Error al importar historiales: {}", e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HANDS: &str = include_str!("../tests/fixtures/hand_history/pokerstars/nlhe_6max.txt");
+
+    // Carpeta temporal propia de cada prueba
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hand_import_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn append(path: &Path, text: &str) {
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(path).unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    // Texto de la primera mano (con su línea en blanco final) y del resto
+    fn split_first_hand() -> (&'static str, &'static str) {
+        let second = HANDS.find("PokerStars Hand #245000000002").unwrap();
+        HANDS.split_at(second)
+    }
+
+    #[test]
+    fn keeps_the_last_hand_while_it_may_be_incomplete() {
+        let (first, rest) = split_first_hand();
+        let partial = format!("{}{}", first, &rest[..rest.find("*** HOLE CARDS ***").unwrap()]);
+
+        assert_eq!(complete_prefix_len(partial.as_bytes(), false), first.len());
+        assert_eq!(complete_prefix_len(partial.as_bytes(), true), partial.len());
+        assert_eq!(complete_prefix_len(format!("{}\n\n", partial.trim_end()).as_bytes(), false), partial.trim_end().len() + 2);
+        // Sin ninguna cabecera completa no se consume nada
+        assert_eq!(complete_prefix_len(b"PokerStars Hand #1: ", false), 0);
+    }
+
+    #[test]
+    fn reads_only_new_complete_hands() {
+        let dir = temp_dir("offsets");
+        let path = dir.join("HH.txt");
+        let (first, rest) = split_first_hand();
+        let cut = rest.find("*** HOLE CARDS ***").unwrap();
+
+        append(&path, first);
+        append(&path, &rest[..cut]);
+        let mut state = FileState::default();
+        let outcome = read_new_hands(&path, &mut state).unwrap().unwrap();
+        assert_eq!(outcome.hands.len(), 1);
+        assert_eq!(state.offset, first.len() as u64);
+        assert_eq!(state.lineas, first.lines().count());
+
+        // Nada nuevo: la segunda mano sigue incompleta
+        assert!(read_new_hands(&path, &mut state).unwrap().is_none());
+
+        append(&path, &rest[cut..]);
+        append(&path, "\n\n");
+        let outcome = read_new_hands(&path, &mut state).unwrap().unwrap();
+        assert_eq!(outcome.hands.len(), 2);
+        assert_eq!(outcome.hands[0].id, "245000000002");
+        assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+        assert_eq!(state.manos, 3);
+        assert_eq!(state.offset, fs::metadata(&path).unwrap().len());
+        assert!(read_new_hands(&path, &mut state).unwrap().is_none());
+
+        // Archivo rotado o truncado: se vuelve a leer desde el principio
+        fs::write(&path, first).unwrap();
+        let outcome = read_new_hands(&path, &mut state).unwrap().unwrap();
+        assert_eq!(outcome.hands.len(), 1);
+        assert_eq!(state.offset, first.len() as u64);
+        assert_eq!(state.manos, 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn error_lines_are_absolute() {
+        let dir = temp_dir("lines");
+        let path = dir.join("HH.txt");
+        let (first, rest) = split_first_hand();
+        append(&path, first);
+        let mut state = FileState::default();
+        read_new_hands(&path, &mut state).unwrap();
+
+        // Segunda mano sin la línea de mesa
+        let table = rest.lines().nth(1).unwrap();
+        append(&path, &format!("{}\n\n", rest.replacen(table, "", 1).trim_end()));
+        let outcome = read_new_hands(&path, &mut state).unwrap().unwrap();
+        assert_eq!(outcome.errors.len(), 1);
+        // La cabecera es la línea siguiente a la primera mano y la mesa va detrás
+        assert_eq!(outcome.errors[0].line, first.lines().count() + 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn skips_symlinked_folders() {
+        let dir = temp_dir("symlinks");
+        fs::create_dir_all(dir.join("sala")).unwrap();
+        fs::write(dir.join("sala").join("HH.txt"), HANDS).unwrap();
+        fs::write(dir.join("notas.csv"), "").unwrap();
+        // Enlace a la carpeta padre: sin comprobarlo la búsqueda no terminaría
+        std::os::unix::fs::symlink(&dir, dir.join("sala").join("bucle")).unwrap();
+        std::os::unix::fs::symlink(dir.join("sala"), dir.join("copia")).unwrap();
+
+        let mut files = Vec::new();
+        collect_files(&dir, &mut files);
+        assert_eq!(files, [dir.join("sala").join("HH.txt")]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,404 @@
+// src-tauri/src/hero_report.rs
+// Informes de las manos propias (hero): resumen por sesiones, botes más grandes
+// y leaks detectados comparando las stats con rangos de referencia configurables
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use crate::api::PlayerStats;
+use crate::equity;
+use crate::hand_db;
+use crate::hand_history::{Chips, Hand, Position};
+use crate::local_stats::{self, LocalStats, StatCounter, COUNTER_NAMES, POSITION_COUNTER_NAMES};
+use crate::stats_filter::StatsFilter;
+
+// Pausa entre manos a partir de la cual empieza una sesión nueva
+const SESSION_GAP_MINUTES: i64 = 30;
+// Botes más grandes que se muestran por sesión
+const BIGGEST_POTS: usize = 5;
+// Oportunidades mínimas para valorar una stat
+const MIN_OPORTUNIDADES: u32 = 30;
+
+// Rango de valores (en %) que se considera correcto para una stat
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct RangoReferencia {
+    pub min: f64,
+    pub max: f64,
+}
+
+// Rangos por defecto para cash 6-max. Las claves son las de COUNTER_NAMES o
+// "{stat}_{posición}" para las stats por posición (p. ej. "rfi_btn").
+pub fn default_reference_ranges() -> HashMap<String, RangoReferencia> {
+    [
+        ("vpip", 20.0, 28.0),
+        ("pfr", 16.0, 23.0),
+        ("three_bet", 6.0, 11.0),
+        ("fold_to_3bet", 45.0, 62.0),
+        ("cbet_flop", 50.0, 70.0),
+        ("cbet_turn", 40.0, 60.0),
+        ("fold_to_flop_cbet", 35.0, 50.0),
+        ("fold_to_turn_cbet", 35.0, 50.0),
+        ("wtsd", 24.0, 31.0),
+        ("wsd", 48.0, 56.0),
+        ("wwsf", 44.0, 52.0),
+        ("rfi_ep", 12.0, 20.0),
+        ("rfi_mp", 16.0, 24.0),
+        ("rfi_co", 25.0, 35.0),
+        ("rfi_btn", 40.0, 55.0),
+        ("rfi_sb", 30.0, 50.0),
+        ("fold_to_steal_sb", 55.0, 75.0),
+        ("fold_to_steal_bb", 30.0, 45.0),
+    ]
+    .into_iter()
+    .map(|(key, min, max)| (key.to_string(), RangoReferencia { min, max }))
+    .collect()
+}
+
+// Indica si una clave de rango de referencia corresponde a una stat conocida
+pub fn is_known_stat_key(key: &str) -> bool {
+    COUNTER_NAMES.contains(&key) || split_positional_key(key).is_some()
+}
+
+// "rfi_btn" -> (índice de "rfi" en POSITION_COUNTER_NAMES, BTN)
+fn split_positional_key(key: &str) -> Option<(usize, Position)> {
+    let (stat, position) = key.rsplit_once('_')?;
+    let index = POSITION_COUNTER_NAMES.iter().position(|name| *name == stat)?;
+    Some((index, Position::from_label(position)?))
+}
+
+fn counter_for_key(stats: &LocalStats, key: &str) -> Option<StatCounter> {
+    if let Some(index) = COUNTER_NAMES.iter().position(|name| *name == key) {
+        return Some(stats.counters()[index]);
+    }
+    let (index, position) = split_positional_key(key)?;
+    stats.por_posicion.get(&position).map(|s| s.counters()[index])
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct BigPot {
+    pub sala: String,
+    pub hand_id: String,
+    pub fecha: NaiveDateTime,
+    pub bote_bb: f64,
+    pub neto_bb: f64,
+}
+
+// Ganancia en una moneda; `moneda` es None en dinero ficticio
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct CurrencyNet {
+    pub moneda: Option<String>,
+    pub ganancia: Chips,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct SessionSummary {
+    pub inicio: NaiveDateTime,
+    pub fin: NaiveDateTime,
+    pub manos: u32,
+    // Ganancia en centésimas por moneda y en ciegas grandes de todas las manos
+    pub ganancias: Vec<CurrencyNet>,
+    pub ganancia_bb: f64,
+    pub bb_100: Option<f64>,
+    // Ganancia en ciegas grandes contando los all-in por su equidad en vez de por el resultado
+    pub ganancia_ev_bb: f64,
+    pub botes_mayores: Vec<BigPot>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Leak {
+    pub stat: String,
+    pub valor: f64,
+    pub min: f64,
+    pub max: f64,
+    pub oportunidades: u32,
+    // "alto" o "bajo" respecto al rango
+    pub direccion: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct HeroReport {
+    // Descripción del filtro aplicado ("" = todas las manos)
+    pub filtro: String,
+    pub total: Option<SessionSummary>,
+    pub sesiones: Vec<SessionSummary>,
+    pub stats: PlayerStats,
+    pub leaks: Vec<Leak>,
+    // Informe en texto, para copiarlo o enviarlo al proveedor de análisis
+    pub texto: String,
+    // Análisis del proveedor configurado, si se pidió
+    pub analisis: Option<String>,
+}
+
+// Informe de las manos propias guardadas en la base de datos local
+pub fn hero_report(filter: &StatsFilter, ranges: &HashMap<String, RangoReferencia>) -> Result<HeroReport, String> {
+    let hands = hand_db::hero_hands(filter)?;
+    if hands.is_empty() {
+        return Err("No hay manos propias en la base de datos local".to_string());
+    }
+    Ok(build_report(&hands, ranges, filter.describe()))
+}
+
+// Construye el informe a partir de manos ordenadas por fecha
+pub fn build_report(hands: &[Hand], ranges: &HashMap<String, RangoReferencia>, filtro: String) -> HeroReport {
+    let mut stats = LocalStats::default();
+    let mut names = BTreeSet::new();
+    for hand in hands {
+        let Some(hero) = hand.hero.as_deref() else {
+            continue;
+        };
+        if let Some(hand_stats) = local_stats::hand_stats(hand, hero) {
+            stats.merge(&hand_stats);
+            names.insert(hero);
+        }
+    }
+    stats.player_name = names.into_iter().collect::<Vec<_>>().join(" / ");
+
+    let mut report = HeroReport {
+        filtro,
+        total: (!hands.is_empty()).then(|| summarize(hands)),
+        sesiones: split_sessions(hands).into_iter().map(summarize).collect(),
+        stats: stats.to_player_stats(),
+        leaks: find_leaks(&stats, ranges),
+        texto: String::new(),
+        analisis: None,
+    };
+    report.texto = format_report(&report);
+    report
+}
+
+// Agrupa manos consecutivas separadas por menos de SESSION_GAP_MINUTES
+pub fn split_sessions(hands: &[Hand]) -> Vec<&[Hand]> {
+    let mut sessions = Vec::new();
+    let mut start = 0;
+    for i in 1..hands.len() {
+        if hands[i].date - hands[i - 1].date > Duration::minutes(SESSION_GAP_MINUTES) {
+            sessions.push(&hands[start..i]);
+            start = i;
+        }
+    }
+    if start < hands.len() {
+        sessions.push(&hands[start..]);
+    }
+    sessions
+}
+
+// Código de la moneda para agrupar: "$" y "USD" son la misma
+fn currency_key(currency: Option<&str>) -> Option<String> {
+    currency.map(|currency| match currency {
+        "$" => "USD",
+        "€" => "EUR",
+        "£" => "GBP",
+        code => code,
+    }.to_string())
+}
+
+fn summarize(hands: &[Hand]) -> SessionSummary {
+    let mut summary = SessionSummary {
+        inicio: hands[0].date,
+        fin: hands[hands.len() - 1].date,
+        manos: 0,
+        ganancias: Vec::new(),
+        ganancia_bb: 0.0,
+        bb_100: None,
+        ganancia_ev_bb: 0.0,
+        botes_mayores: Vec::new(),
+    };
+    let mut pots = Vec::new();
+    let mut by_currency: BTreeMap<Option<String>, Chips> = BTreeMap::new();
+
+    for hand in hands {
+        let Some(hero) = hand.hero.as_deref() else {
+            continue;
+        };
+        if hand.big_blind <= 0 {
+            continue;
+        }
+        let big_blind = hand.big_blind as f64;
+        let net = hand.net(hero);
+        summary.manos += 1;
+        *by_currency.entry(currency_key(hand.currency.as_deref())).or_default() += net;
+        summary.ganancia_bb += net as f64 / big_blind;
+        let ev_net = equity::all_in_adjusted_net(hand, hero).unwrap_or(net as f64);
+        summary.ganancia_ev_bb += ev_net / big_blind;
+        pots.push(BigPot {
+            sala: hand.site.code().to_string(),
+            hand_id: hand.id.clone(),
+            fecha: hand.date,
+            bote_bb: hand.total_pot as f64 / big_blind,
+            neto_bb: net as f64 / big_blind,
+        });
+    }
+
+    if summary.manos > 0 {
+        summary.bb_100 = Some(summary.ganancia_bb * 100.0 / summary.manos as f64);
+    }
+    summary.ganancias = by_currency.into_iter()
+        .map(|(moneda, ganancia)| CurrencyNet { moneda, ganancia })
+        .collect();
+    pots.sort_by(|a, b| b.bote_bb.total_cmp(&a.bote_bb));
+    pots.truncate(BIGGEST_POTS);
+    summary.botes_mayores = pots;
+    summary
+}
+
+// Stats fuera de su rango de referencia, con suficientes oportunidades
+fn find_leaks(stats: &LocalStats, ranges: &HashMap<String, RangoReferencia>) -> Vec<Leak> {
+    let mut keys: Vec<&String> = ranges.keys().collect();
+    keys.sort();
+
+    let mut leaks = Vec::new();
+    for key in keys {
+        let range = ranges[key];
+        let Some(counter) = counter_for_key(stats, key) else {
+            continue;
+        };
+        let Some(valor) = counter.pct().filter(|_| counter.oportunidades >= MIN_OPORTUNIDADES) else {
+            continue;
+        };
+        let direccion = if valor < range.min {
+            "bajo"
+        } else if valor > range.max {
+            "alto"
+        } else {
+            continue;
+        };
+        leaks.push(Leak {
+            stat: key.clone(),
+            valor,
+            min: range.min,
+            max: range.max,
+            oportunidades: counter.oportunidades,
+            direccion: direccion.to_string(),
+        });
+    }
+    leaks
+}
+
+fn format_bb(value: Option<f64>) -> String {
+    value.map(|v| format!("{:.2}", v)).unwrap_or_else(|| "-".to_string())
+}
+
+// "12.50 USD, -3.00 EUR, 150 fichas"
+fn format_net(ganancias: &[CurrencyNet]) -> String {
+    ganancias.iter()
+        .map(|net| match &net.moneda {
+            Some(moneda) => format!("{:.2} {}", net.ganancia as f64 / 100.0, moneda),
+            None => format!("{} fichas", net.ganancia / 100),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_report(report: &HeroReport) -> String {
+    let mut lines = vec![format!("Informe de {}", report.stats.player_name)];
+    if !report.filtro.is_empty() {
+        lines.push(format!("Filtro: {}", report.filtro));
+    }
+
+    if let Some(total) = &report.total {
+        lines.push(format!(
+            "Total: {} manos, {:.2} bb ({}), {} bb/100, EV all-in {:.2} bb ({} a {})",
+            total.manos, total.ganancia_bb, format_net(&total.ganancias), format_bb(total.bb_100), total.ganancia_ev_bb,
+            total.inicio.format("%Y-%m-%d %H:%M"), total.fin.format("%Y-%m-%d %H:%M")
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push(format!("Sesiones ({}):", report.sesiones.len()));
+    for session in report.sesiones.iter() {
+        lines.push(format!(
+            "- {} ({} manos): {:.2} bb, {} bb/100",
+            session.inicio.format("%Y-%m-%d %H:%M"), session.manos, session.ganancia_bb, format_bb(session.bb_100)
+        ));
+        if let Some(pot) = session.botes_mayores.first() {
+            lines.push(format!("  Bote mayor: {} #{} de {:.1} bb ({:+.1} bb)", pot.sala, pot.hand_id, pot.bote_bb, pot.neto_bb));
+        }
+    }
+
+    let stats = &report.stats;
+    lines.push(String::new());
+    lines.push(format!(
+        "Stats: VPIP {} PFR {} 3B {} F3B {} CB flop {} CB turn {} FCB flop {} FCB turn {} WTSD {} WSD {} WWSF {}",
+        stats.vpip, stats.pfr, stats.three_bet, stats.fold_to_3bet_pct, stats.cbet_flop, stats.cbet_turn,
+        stats.fold_to_flop_cbet_pct, stats.fold_to_turn_cbet_pct, stats.wtsd, stats.wsd, stats.wwsf
+    ));
+
+    lines.push(String::new());
+    if report.leaks.is_empty() {
+        lines.push("Sin leaks respecto a los rangos de referencia".to_string());
+    } else {
+        lines.push("Leaks:".to_string());
+        for leak in report.leaks.iter() {
+            lines.push(format!(
+                "- {}: {:.1}% ({}, referencia {:.0}-{:.0}%, {} oportunidades)",
+                leak.stat, leak.valor, leak.direccion, leak.min, leak.max, leak.oportunidades
+            ));
+        }
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand_history::fixtures::pokerstars_hands;
+
+    #[test]
+    fn splits_sessions_after_a_long_pause() {
+        let mut hands = pokerstars_hands();
+        let last = hands.len() - 1;
+        hands[last].date += Duration::hours(2);
+
+        let report = build_report(&hands, &default_reference_ranges(), String::new());
+        let total = report.total.as_ref().unwrap();
+        assert_eq!(total.manos, 3);
+        assert_eq!(report.sesiones.len(), 2);
+        assert_eq!(report.sesiones[0].manos + report.sesiones[1].manos, 3);
+        assert!((total.ganancia_bb - report.sesiones.iter().map(|s| s.ganancia_bb).sum::<f64>()).abs() < 1e-9);
+        assert_eq!(report.stats.player_name, "HeroPlayer");
+        assert!(total.botes_mayores.windows(2).all(|w| w[0].bote_bb >= w[1].bote_bb));
+    }
+
+    #[test]
+    fn groups_net_by_currency() {
+        let mut hands = pokerstars_hands();
+        let hero = hands[0].hero.clone().unwrap();
+        let nets: Vec<Chips> = hands.iter().map(|h| h.net(&hero)).collect();
+        hands[0].currency = Some("$".to_string());
+        hands[1].currency = Some("€".to_string());
+        hands[2].currency = None;
+
+        let total = summarize(&hands);
+        assert_eq!(total.ganancias, [
+            CurrencyNet { moneda: None, ganancia: nets[2] },
+            CurrencyNet { moneda: Some("EUR".to_string()), ganancia: nets[1] },
+            CurrencyNet { moneda: Some("USD".to_string()), ganancia: nets[0] },
+        ]);
+        assert!(format_net(&total.ganancias).contains(" EUR"));
+    }
+
+    #[test]
+    fn flags_stats_outside_the_reference_range() {
+        let hand = pokerstars_hands().remove(0);
+        let hero = hand.hero.clone().unwrap();
+        let mut stats = local_stats::hand_stats(&hand, &hero).unwrap();
+        // Muestra suficiente repitiendo la misma mano
+        for _ in 1..MIN_OPORTUNIDADES {
+            stats.merge(&local_stats::hand_stats(&hand, &hero).unwrap());
+        }
+
+        let vpip = stats.vpip.pct().unwrap();
+        let ranges: HashMap<String, RangoReferencia> = [
+            ("vpip".to_string(), RangoReferencia { min: vpip + 1.0, max: vpip + 10.0 }),
+            ("pfr".to_string(), RangoReferencia { min: 0.0, max: 100.0 }),
+            ("rfi_btn".to_string(), RangoReferencia { min: 0.0, max: 0.0 }),
+        ].into_iter().collect();
+
+        let leaks = find_leaks(&stats, &ranges);
+        assert_eq!(leaks.len(), 1);
+        assert_eq!(leaks[0].stat, "vpip");
+        assert_eq!(leaks[0].direccion, "bajo");
+        assert!(is_known_stat_key("fold_to_steal_bb"));
+        assert!(!is_known_stat_key("rfi_utg"));
+    }
+}
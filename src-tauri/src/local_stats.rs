@@ -0,0 +1,853 @@
+// src-tauri/src/local_stats.rs
+// Cálculo local de las stats del HUD a partir de historiales de manos,
+// con las mismas definiciones que PlayerStats del servidor
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::api::{PlayerStats, PositionalStats};
+use crate::hand_history::{self, ActionKind, Chips, Hand, Position, Street};
+use crate::stats_filter::StatsFilter;
+
+// Veces que se hizo una acción frente a las veces que se pudo hacer
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatCounter {
+    pub veces: u32,
+    pub oportunidades: u32,
+}
+
+impl StatCounter {
+    fn record(&mut self, result: Option<bool>) {
+        if let Some(done) = result {
+            self.oportunidades += 1;
+            if done {
+                self.veces += 1;
+            }
+        }
+    }
+
+    pub fn merge(&mut self, other: StatCounter) {
+        self.veces += other.veces;
+        self.oportunidades += other.oportunidades;
+    }
+
+    pub fn pct(&self) -> Option<f64> {
+        if self.oportunidades == 0 {
+            return None;
+        }
+        Some(self.veces as f64 * 100.0 / self.oportunidades as f64)
+    }
+
+    // Mismo formato que las stats del servidor; "-" si nunca hubo oportunidad
+    fn format(&self) -> String {
+        self.pct().map(|pct| format!("{:.1}", pct)).unwrap_or_else(|| "-".to_string())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct LocalStats {
+    pub player_name: String,
+    pub total_manos: u32,
+    pub vpip: StatCounter,
+    pub pfr: StatCounter,
+    pub three_bet: StatCounter,
+    pub fold_to_3bet: StatCounter,
+    pub cbet_flop: StatCounter,
+    pub cbet_turn: StatCounter,
+    pub fold_to_flop_cbet: StatCounter,
+    pub fold_to_turn_cbet: StatCounter,
+    pub wtsd: StatCounter,
+    pub wsd: StatCounter,
+    pub wwsf: StatCounter,
+    // Ganancia neta en centésimas de dólar (solo manos en USD) y en ciegas grandes
+    pub ganancia: Chips,
+    pub ganancia_bb: f64,
+    // Stats preflop por posición
+    pub por_posicion: BTreeMap<Position, PositionStats>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct PositionStats {
+    pub total_manos: u32,
+    pub vpip: StatCounter,
+    pub pfr: StatCounter,
+    // Raise first in: sube cuando todos los anteriores se han retirado
+    pub rfi: StatCounter,
+    pub three_bet: StatCounter,
+    pub fold_to_3bet: StatCounter,
+    // RFI desde CO, BTN o SB
+    pub steal: StatCounter,
+    // Se retira desde las ciegas ante un robo
+    pub fold_to_steal: StatCounter,
+}
+
+impl PositionStats {
+    // Contadores por nombre, en el orden de POSITION_COUNTER_NAMES
+    pub fn counters(&self) -> [StatCounter; 7] {
+        [self.vpip, self.pfr, self.rfi, self.three_bet, self.fold_to_3bet, self.steal, self.fold_to_steal]
+    }
+
+    pub fn counters_mut(&mut self) -> [&mut StatCounter; 7] {
+        [
+            &mut self.vpip, &mut self.pfr, &mut self.rfi, &mut self.three_bet,
+            &mut self.fold_to_3bet, &mut self.steal, &mut self.fold_to_steal,
+        ]
+    }
+
+    pub fn to_positional_stats(&self) -> PositionalStats {
+        PositionalStats {
+            total_manos: self.total_manos.to_string(),
+            vpip: self.vpip.format(),
+            pfr: self.pfr.format(),
+            rfi: self.rfi.format(),
+            three_bet: self.three_bet.format(),
+            fold_to_3bet_pct: self.fold_to_3bet.format(),
+            steal_pct: self.steal.format(),
+            fold_to_steal_pct: self.fold_to_steal.format(),
+        }
+    }
+
+    fn add_hand(&mut self, flags: &HandFlags) {
+        self.total_manos += 1;
+        self.vpip.record(flags.vpip);
+        self.pfr.record(flags.pfr);
+        self.rfi.record(flags.rfi);
+        self.three_bet.record(flags.three_bet);
+        self.fold_to_3bet.record(flags.fold_to_3bet);
+        self.steal.record(flags.steal);
+        self.fold_to_steal.record(flags.fold_to_steal);
+    }
+}
+
+impl LocalStats {
+    pub fn bb_100(&self) -> Option<f64> {
+        if self.total_manos == 0 {
+            return None;
+        }
+        Some(self.ganancia_bb * 100.0 / self.total_manos as f64)
+    }
+
+    // Convierte a la estructura del servidor para mostrarla igual que las stats remotas
+    pub fn to_player_stats(&self) -> PlayerStats {
+        PlayerStats {
+            player_name: self.player_name.clone(),
+            vpip: self.vpip.format(),
+            pfr: self.pfr.format(),
+            three_bet: self.three_bet.format(),
+            fold_to_3bet_pct: self.fold_to_3bet.format(),
+            wtsd: self.wtsd.format(),
+            wsd: self.wsd.format(),
+            cbet_flop: self.cbet_flop.format(),
+            cbet_turn: self.cbet_turn.format(),
+            fold_to_flop_cbet_pct: self.fold_to_flop_cbet.format(),
+            fold_to_turn_cbet_pct: self.fold_to_turn_cbet.format(),
+            limp_pct: None,
+            limp_raise_pct: None,
+            four_bet_preflop_pct: None,
+            fold_to_4bet_pct: None,
+            probe_bet_turn_pct: None,
+            bet_river_pct: None,
+            fold_to_river_bet_pct: None,
+            overbet_turn_pct: None,
+            overbet_river_pct: None,
+            wsdwbr_pct: None,
+            wwsf: self.wwsf.format(),
+            total_manos: self.total_manos.to_string(),
+            bb_100: self.bb_100().map(|bb| format!("{:.2}", bb)).unwrap_or_else(|| "-".to_string()),
+            win_usd: format!("{:.2}", self.ganancia as f64 / 100.0),
+            hand_ids: None,
+            by_position: (!self.por_posicion.is_empty()).then(|| {
+                self.por_posicion.iter()
+                    .map(|(position, stats)| (*position, stats.to_positional_stats()))
+                    .collect()
+            }),
+        }
+    }
+
+    // Contadores por nombre, en el orden de COUNTER_NAMES
+    pub fn counters(&self) -> [StatCounter; 11] {
+        [
+            self.vpip, self.pfr, self.three_bet, self.fold_to_3bet,
+            self.cbet_flop, self.cbet_turn, self.fold_to_flop_cbet, self.fold_to_turn_cbet,
+            self.wtsd, self.wsd, self.wwsf,
+        ]
+    }
+
+    pub fn counters_mut(&mut self) -> [&mut StatCounter; 11] {
+        [
+            &mut self.vpip, &mut self.pfr, &mut self.three_bet, &mut self.fold_to_3bet,
+            &mut self.cbet_flop, &mut self.cbet_turn, &mut self.fold_to_flop_cbet, &mut self.fold_to_turn_cbet,
+            &mut self.wtsd, &mut self.wsd, &mut self.wwsf,
+        ]
+    }
+
+    // Suma los contadores de otro conjunto de manos
+    pub fn merge(&mut self, other: &LocalStats) {
+        self.total_manos += other.total_manos;
+        for (counter, other) in self.counters_mut().into_iter().zip(other.counters()) {
+            counter.merge(other);
+        }
+        self.ganancia += other.ganancia;
+        self.ganancia_bb += other.ganancia_bb;
+        for (position, other) in other.por_posicion.iter() {
+            let stats = self.por_posicion.entry(*position).or_default();
+            stats.total_manos += other.total_manos;
+            for (counter, other) in stats.counters_mut().into_iter().zip(other.counters()) {
+                counter.merge(other);
+            }
+        }
+    }
+
+    fn add_hand(&mut self, hand: &Hand, flags: &HandFlags) {
+        self.total_manos += 1;
+        self.vpip.record(flags.vpip);
+        self.pfr.record(flags.pfr);
+        self.three_bet.record(flags.three_bet);
+        self.fold_to_3bet.record(flags.fold_to_3bet);
+        self.cbet_flop.record(flags.cbet_flop);
+        self.cbet_turn.record(flags.cbet_turn);
+        self.fold_to_flop_cbet.record(flags.fold_to_flop_cbet);
+        self.fold_to_turn_cbet.record(flags.fold_to_turn_cbet);
+        self.wtsd.record(flags.wtsd);
+        self.wsd.record(flags.wsd);
+        self.wwsf.record(flags.wwsf);
+
+        if let Some(position) = flags.position {
+            self.por_posicion.entry(position).or_default().add_hand(flags);
+        }
+
+        let net = hand.net(&self.player_name);
+        // Se muestra como "win_usd": no se mezclan otras monedas ni fichas de juego
+        if matches!(hand.currency.as_deref(), Some("$") | Some("USD")) {
+            self.ganancia += net;
+        }
+        if hand.big_blind > 0 {
+            self.ganancia_bb += net as f64 / hand.big_blind as f64;
+        }
+    }
+}
+
+// Nombres de los contadores de LocalStats::counters
+pub const COUNTER_NAMES: [&str; 11] = [
+    "vpip", "pfr", "three_bet", "fold_to_3bet",
+    "cbet_flop", "cbet_turn", "fold_to_flop_cbet", "fold_to_turn_cbet",
+    "wtsd", "wsd", "wwsf",
+];
+
+// Nombres de los contadores de PositionStats::counters
+pub const POSITION_COUNTER_NAMES: [&str; 7] = [
+    "vpip", "pfr", "rfi", "three_bet", "fold_to_3bet", "steal", "fold_to_steal",
+];
+
+// Resultado de una mano para cada stat: None si no hubo oportunidad
+#[derive(Debug, Default)]
+struct HandFlags {
+    vpip: Option<bool>,
+    pfr: Option<bool>,
+    three_bet: Option<bool>,
+    fold_to_3bet: Option<bool>,
+    cbet_flop: Option<bool>,
+    cbet_turn: Option<bool>,
+    fold_to_flop_cbet: Option<bool>,
+    fold_to_turn_cbet: Option<bool>,
+    wtsd: Option<bool>,
+    wsd: Option<bool>,
+    wwsf: Option<bool>,
+    position: Option<Position>,
+    rfi: Option<bool>,
+    steal: Option<bool>,
+    fold_to_steal: Option<bool>,
+}
+
+// Calcula las stats de un jugador en un conjunto de manos
+pub fn compute(hands: &[Hand], player: &str) -> LocalStats {
+    let mut stats = LocalStats {
+        player_name: player.to_string(),
+        ..Default::default()
+    };
+
+    for hand in hands {
+        if let Some(flags) = analyze_hand(hand, player) {
+            stats.add_hand(hand, &flags);
+        }
+    }
+    stats
+}
+
+// Stats de una sola mano, para acumularlas en la base de datos o con LocalStats::merge.
+// None si el jugador no jugó la mano.
+pub fn hand_stats(hand: &Hand, player: &str) -> Option<LocalStats> {
+    let flags = analyze_hand(hand, player)?;
+    let mut stats = LocalStats {
+        player_name: player.to_string(),
+        ..Default::default()
+    };
+    stats.add_hand(hand, &flags);
+    Some(stats)
+}
+
+// Lee los historiales y calcula las stats del jugador en las manos que cumplen el filtro.
+// Las manos que no se pueden leer se ignoran.
+pub fn compute_from_files(paths: &[String], player: &str, filter: &StatsFilter) -> Result<LocalStats, String> {
+    let mut hands = Vec::new();
+    for path in paths {
+        let outcome = hand_history::parse_file(path)?;
+        if !outcome.errors.is_empty() {
+            println!("{}: {} manos no se pudieron leer", path, outcome.errors.len());
+        }
+        hands.extend(outcome.hands.into_iter().filter(|hand| filter.matches(hand, player)));
+    }
+    Ok(compute(&hands, player))
+}
+
+// Primera acción del jugador en la calle si nadie ha apostado antes
+fn first_action_unopened(hand: &Hand, street: Street, player: &str) -> Option<ActionKind> {
+    for action in hand.actions.iter().filter(|a| a.street == street) {
+        if action.player == player {
+            return Some(action.kind);
+        }
+        if action.kind.is_aggressive() {
+            return None;
+        }
+    }
+    None
+}
+
+// Respuesta del jugador a la primera apuesta de `bettor`, si nadie sube antes
+fn response_to_bet(hand: &Hand, street: Street, bettor: &str, player: &str) -> Option<ActionKind> {
+    let mut facing_bet = false;
+    for action in hand.actions.iter().filter(|a| a.street == street) {
+        if facing_bet {
+            if action.player == player {
+                return Some(action.kind);
+            }
+            if action.kind.is_aggressive() {
+                return None;
+            }
+        } else if action.player == bettor && action.kind == ActionKind::Bet {
+            facing_bet = true;
+        }
+    }
+    None
+}
+
+fn is_cbet(hand: &Hand, street: Street, aggressor: &str) -> bool {
+    first_action_unopened(hand, street, aggressor) == Some(ActionKind::Bet)
+}
+
+fn analyze_hand(hand: &Hand, player: &str) -> Option<HandFlags> {
+    // Solo cuentan las manos en las que el jugador recibió cartas
+    hand.seats.iter().find(|s| s.player == player && !s.sitting_out)?;
+    let mut flags = HandFlags {
+        position: hand.position(player),
+        ..Default::default()
+    };
+
+    // Preflop: las ciegas y antes no son acciones voluntarias
+    let mut raises = 0;
+    let mut first_raiser: Option<&str> = None;
+    let mut aggressor: Option<&str> = None;
+    let mut decided = false;
+    let mut vpip = false;
+    let mut pfr = false;
+    // Alguien ha pagado o subido antes; y si la primera entrada fue un robo sin respuesta
+    let mut entered = false;
+    let mut steal_unanswered = false;
+
+    for action in hand.actions.iter().filter(|a| a.street == Street::Preflop && !a.kind.is_forced()) {
+        if action.player == player {
+            // RFI y fold to steal: primera decisión del jugador
+            if !decided {
+                if !entered {
+                    flags.rfi = Some(action.kind.is_aggressive());
+                    flags.steal = flags.rfi.filter(|_| flags.position.is_some_and(|p| p.is_steal_position()));
+                } else if steal_unanswered && flags.position.is_some_and(|p| p.is_blind()) {
+                    flags.fold_to_steal = Some(action.kind == ActionKind::Fold);
+                }
+            }
+            decided = true;
+            vpip |= matches!(action.kind, ActionKind::Call | ActionKind::Bet | ActionKind::Raise);
+            pfr |= action.kind.is_aggressive();
+
+            // 3-bet: primera vez que se enfrenta a una sola subida
+            if raises == 1 && first_raiser != Some(player) && flags.three_bet.is_none() {
+                flags.three_bet = Some(action.kind.is_aggressive());
+            }
+            // Fold to 3-bet: abrió subiendo y le resuben una vez
+            if raises == 2 && first_raiser == Some(player) && flags.fold_to_3bet.is_none() {
+                flags.fold_to_3bet = Some(action.kind == ActionKind::Fold);
+            }
+        }
+        if action.kind != ActionKind::Fold {
+            steal_unanswered = !entered && action.kind.is_aggressive()
+                && hand.position(&action.player).is_some_and(|p| p.is_steal_position());
+            entered = true;
+        }
+        if action.kind.is_aggressive() {
+            raises += 1;
+            if raises == 1 {
+                first_raiser = Some(&action.player);
+            }
+            aggressor = Some(&action.player);
+        }
+    }
+
+    // En un walk la ciega grande no llega a decidir
+    if decided {
+        flags.vpip = Some(vpip);
+        flags.pfr = Some(pfr);
+    }
+
+    // Continuation bets del agresor preflop y respuestas del resto
+    if let Some(aggressor) = aggressor {
+        if aggressor == player {
+            flags.cbet_flop = first_action_unopened(hand, Street::Flop, player).map(|kind| kind == ActionKind::Bet);
+            if flags.cbet_flop == Some(true) {
+                flags.cbet_turn = first_action_unopened(hand, Street::Turn, player).map(|kind| kind == ActionKind::Bet);
+            }
+        } else if is_cbet(hand, Street::Flop, aggressor) {
+            flags.fold_to_flop_cbet = response_to_bet(hand, Street::Flop, aggressor, player)
+                .map(|kind| kind == ActionKind::Fold);
+            if is_cbet(hand, Street::Turn, aggressor) {
+                flags.fold_to_turn_cbet = response_to_bet(hand, Street::Turn, aggressor, player)
+                    .map(|kind| kind == ActionKind::Fold);
+            }
+        }
+    }
+
+    // Showdown: quedan al menos dos jugadores sin tirar las cartas
+    let folded_preflop = hand.actions.iter()
+        .any(|a| a.player == player && a.street == Street::Preflop && a.kind == ActionKind::Fold);
+
+    if !folded_preflop && hand.board.len() >= 3 {
+        let showdown = hand.went_to_showdown(player);
+        let won = hand.won(player) > 0;
+
+        flags.wtsd = Some(showdown);
+        flags.wwsf = Some(won);
+        if showdown {
+            flags.wsd = Some(won);
+        }
+    }
+
+    Some(flags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::hand_history::*;
+
+    const HERO: &str = "Hero";
+
+    // Mano de 10/20 (en centésimas) con ciegas puestas por los dos primeros jugadores
+    struct HandBuilder {
+        hand: Hand,
+    }
+
+    fn hand(players: &[&str]) -> HandBuilder {
+        let seats = players.iter().enumerate()
+            .map(|(i, name)| Seat { number: i as u8 + 1, player: name.to_string(), stack: 10_000, sitting_out: false, anonymized: false })
+            .collect();
+        let mut builder = HandBuilder {
+            hand: Hand {
+                site: Site::PokerStars,
+                id: "1".to_string(),
+                table: "Test".to_string(),
+                date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(12, 0, 0).unwrap(),
+                time_zone: None,
+                variant: Variant::Holdem,
+                limit: BettingLimit::NoLimit,
+                currency: Some("USD".to_string()),
+                small_blind: 10,
+                big_blind: 20,
+                ante: 0,
+                max_players: 6,
+                fast_fold: false,
+                button_seat: players.len() as u8,
+                seats,
+                hero: Some(HERO.to_string()),
+                hero_cards: Vec::new(),
+                actions: Vec::new(),
+                board: Vec::new(),
+                showdown: Vec::new(),
+                returned: Vec::new(),
+                collected: Vec::new(),
+                total_pot: 0,
+                side_pots: Vec::new(),
+                rake: 0,
+            },
+        };
+        builder = builder.act(Street::Preflop, players[0], ActionKind::SmallBlind, 10);
+        builder.act(Street::Preflop, players[1], ActionKind::BigBlind, 20)
+    }
+
+    impl HandBuilder {
+        fn act(mut self, street: Street, player: &str, kind: ActionKind, amount: Chips) -> Self {
+            let visible = match street {
+                Street::Preflop => 0,
+                Street::Flop => 3,
+                Street::Turn => 4,
+                Street::River => 5,
+            };
+            if self.hand.board.len() < visible {
+                self.hand.board = Card::parse_list("2c 7d Js Qh 3s").unwrap()[..visible].to_vec();
+            }
+            self.hand.actions.push(Action { street, player: player.to_string(), kind, amount, to: None, all_in: false });
+            self
+        }
+
+        fn pre(self, player: &str, kind: ActionKind, amount: Chips) -> Self {
+            self.act(Street::Preflop, player, kind, amount)
+        }
+
+        fn flop(self, player: &str, kind: ActionKind, amount: Chips) -> Self {
+            self.act(Street::Flop, player, kind, amount)
+        }
+
+        fn turn(self, player: &str, kind: ActionKind, amount: Chips) -> Self {
+            self.act(Street::Turn, player, kind, amount)
+        }
+
+        fn river(self, player: &str, kind: ActionKind, amount: Chips) -> Self {
+            self.act(Street::River, player, kind, amount)
+        }
+
+        fn wins(mut self, player: &str, amount: Chips) -> Hand {
+            self.hand.collected.push(Collection { player: player.to_string(), amount, pot: None });
+            self.hand
+        }
+    }
+
+    use ActionKind::*;
+
+    fn counter(veces: u32, oportunidades: u32) -> StatCounter {
+        StatCounter { veces, oportunidades }
+    }
+
+    // Hero abre y gana sin oposición
+    fn open_and_take_blinds() -> Hand {
+        hand(&["SB", "BB", HERO])
+            .pre(HERO, Raise, 60)
+            .pre("SB", Fold, 0)
+            .pre("BB", Fold, 0)
+            .wins(HERO, 90)
+    }
+
+    // Hero paga desde la ciega grande y se retira al flop
+    fn call_and_fold_flop() -> Hand {
+        hand(&["SB", HERO, "BTN"])
+            .pre("BTN", Raise, 60)
+            .pre("SB", Fold, 0)
+            .pre(HERO, Call, 40)
+            .flop(HERO, Check, 0)
+            .flop("BTN", Bet, 60)
+            .flop(HERO, Fold, 0)
+            .wins("BTN", 190)
+    }
+
+    #[test]
+    fn vpip_and_pfr_count_only_voluntary_actions() {
+        let walk = hand(&["SB", HERO]).pre("SB", Fold, 0).wins(HERO, 30);
+        let limp = hand(&["SB", "BB", HERO])
+            .pre(HERO, Call, 20)
+            .pre("SB", Fold, 0)
+            .pre("BB", Check, 0)
+            .flop("BB", Check, 0)
+            .flop(HERO, Check, 0)
+            .turn("BB", Bet, 40)
+            .turn(HERO, Fold, 0)
+            .wins("BB", 90);
+        let fold = hand(&["SB", "BB", HERO]).pre(HERO, Fold, 0).pre("SB", Fold, 0).wins("BB", 30);
+
+        let stats = compute(&[walk, limp, fold, open_and_take_blinds()], HERO);
+        assert_eq!(stats.total_manos, 4);
+        // El walk no es una oportunidad: la ciega grande no decide
+        assert_eq!(stats.vpip, counter(2, 3));
+        assert_eq!(stats.pfr, counter(1, 3));
+    }
+
+    #[test]
+    fn three_bet_needs_a_single_raise_before() {
+        let three_bet = hand(&["SB", "BB", "CO", HERO])
+            .pre("CO", Raise, 60)
+            .pre(HERO, Raise, 180)
+            .pre("SB", Fold, 0)
+            .pre("BB", Fold, 0)
+            .pre("CO", Fold, 0)
+            .wins(HERO, 270);
+        // Frente a un 3-bet ya no es oportunidad de 3-bet
+        let faced_three_bet = hand(&["SB", "BB", "CO", "BTN", HERO])
+            .pre("CO", Raise, 60)
+            .pre("BTN", Raise, 180)
+            .pre(HERO, Fold, 0)
+            .pre("SB", Fold, 0)
+            .pre("BB", Fold, 0)
+            .pre("CO", Fold, 0)
+            .wins("BTN", 270);
+
+        let stats = compute(&[three_bet, call_and_fold_flop(), faced_three_bet, open_and_take_blinds()], HERO);
+        assert_eq!(stats.three_bet, counter(1, 2));
+    }
+
+    #[test]
+    fn fold_to_three_bet_only_for_the_opener() {
+        let folds = hand(&["SB", "BB", HERO])
+            .pre(HERO, Raise, 60)
+            .pre("SB", Raise, 200)
+            .pre("BB", Fold, 0)
+            .pre(HERO, Fold, 0)
+            .wins("SB", 280);
+        let calls = hand(&["SB", "BB", HERO])
+            .pre(HERO, Raise, 60)
+            .pre("SB", Fold, 0)
+            .pre("BB", Raise, 200)
+            .pre(HERO, Call, 140)
+            .flop("BB", Bet, 200)
+            .flop(HERO, Fold, 0)
+            .wins("BB", 610);
+        // Hero no abrió: pagar un 3-bet ajeno no cuenta
+        let cold = hand(&["SB", "BB", "CO", HERO])
+            .pre("CO", Raise, 60)
+            .pre(HERO, Call, 60)
+            .pre("SB", Raise, 240)
+            .pre("BB", Fold, 0)
+            .pre("CO", Fold, 0)
+            .pre(HERO, Fold, 0)
+            .wins("SB", 380);
+
+        let stats = compute(&[folds, calls, cold], HERO);
+        assert_eq!(stats.fold_to_3bet, counter(1, 2));
+    }
+
+    #[test]
+    fn cbets_require_preflop_initiative_and_an_unopened_pot() {
+        let cbet_and_barrel = hand(&["SB", "BB", HERO])
+            .pre(HERO, Raise, 60)
+            .pre("SB", Fold, 0)
+            .pre("BB", Call, 40)
+            .flop("BB", Check, 0)
+            .flop(HERO, Bet, 60)
+            .flop("BB", Call, 60)
+            .turn("BB", Check, 0)
+            .turn(HERO, Bet, 150)
+            .turn("BB", Fold, 0)
+            .wins(HERO, 400);
+        let check_back = hand(&["SB", "BB", HERO])
+            .pre(HERO, Raise, 60)
+            .pre("SB", Fold, 0)
+            .pre("BB", Call, 40)
+            .flop("BB", Check, 0)
+            .flop(HERO, Check, 0)
+            .turn("BB", Bet, 100)
+            .turn(HERO, Fold, 0)
+            .wins("BB", 130);
+        // La ciega grande apuesta primero (donk): no hay oportunidad de c-bet
+        let donked = hand(&["SB", "BB", HERO])
+            .pre(HERO, Raise, 60)
+            .pre("SB", Fold, 0)
+            .pre("BB", Call, 40)
+            .flop("BB", Bet, 80)
+            .flop(HERO, Fold, 0)
+            .wins("BB", 210);
+        let cbet_then_give_up = hand(&["SB", "BB", HERO])
+            .pre(HERO, Raise, 60)
+            .pre("SB", Fold, 0)
+            .pre("BB", Call, 40)
+            .flop("BB", Check, 0)
+            .flop(HERO, Bet, 60)
+            .flop("BB", Call, 60)
+            .turn("BB", Check, 0)
+            .turn(HERO, Check, 0)
+            .river("BB", Check, 0)
+            .river(HERO, Check, 0)
+            .wins(HERO, 250);
+
+        let stats = compute(&[cbet_and_barrel, check_back, donked, cbet_then_give_up, call_and_fold_flop()], HERO);
+        assert_eq!(stats.cbet_flop, counter(2, 3));
+        assert_eq!(stats.cbet_turn, counter(1, 2));
+    }
+
+    #[test]
+    fn fold_to_cbet_counts_responses_to_the_preflop_raiser() {
+        let call_flop_fold_turn = hand(&["SB", HERO, "BTN"])
+            .pre("BTN", Raise, 60)
+            .pre("SB", Fold, 0)
+            .pre(HERO, Call, 40)
+            .flop(HERO, Check, 0)
+            .flop("BTN", Bet, 60)
+            .flop(HERO, Call, 60)
+            .turn(HERO, Check, 0)
+            .turn("BTN", Bet, 150)
+            .turn(HERO, Fold, 0)
+            .wins("BTN", 400);
+        // Otro jugador sube la c-bet antes de que Hero actúe: no cuenta
+        let raised_before = hand(&["SB", "BB", "BTN", HERO])
+            .pre("BTN", Raise, 60)
+            .pre(HERO, Call, 60)
+            .pre("SB", Fold, 0)
+            .pre("BB", Call, 40)
+            .flop("BB", Check, 0)
+            .flop(HERO, Check, 0)
+            .flop("BTN", Bet, 100)
+            .flop("BB", Raise, 300)
+            .flop(HERO, Fold, 0)
+            .flop("BTN", Fold, 0)
+            .wins("BB", 490);
+        // Apuesta de alguien que no subió preflop: no es una c-bet
+        let not_a_cbet = hand(&["SB", HERO, "BTN"])
+            .pre("BTN", Raise, 60)
+            .pre("SB", Call, 50)
+            .pre(HERO, Call, 40)
+            .flop("SB", Bet, 100)
+            .flop(HERO, Fold, 0)
+            .flop("BTN", Fold, 0)
+            .wins("SB", 280);
+
+        let stats = compute(&[call_flop_fold_turn, raised_before, not_a_cbet, call_and_fold_flop()], HERO);
+        assert_eq!(stats.fold_to_flop_cbet, counter(1, 2));
+        assert_eq!(stats.fold_to_turn_cbet, counter(1, 1));
+    }
+
+    #[test]
+    fn showdown_stats_use_hands_that_saw_the_flop() {
+        let won_showdown = hand(&["SB", "BB", HERO])
+            .pre(HERO, Call, 20)
+            .pre("SB", Fold, 0)
+            .pre("BB", Check, 0)
+            .flop("BB", Check, 0)
+            .flop(HERO, Check, 0)
+            .turn("BB", Check, 0)
+            .turn(HERO, Check, 0)
+            .river("BB", Check, 0)
+            .river(HERO, Check, 0)
+            .wins(HERO, 50);
+        let lost_showdown = hand(&["SB", "BB", HERO])
+            .pre(HERO, Call, 20)
+            .pre("SB", Call, 10)
+            .pre("BB", Check, 0)
+            .flop("SB", Check, 0)
+            .flop("BB", Check, 0)
+            .flop(HERO, Check, 0)
+            .turn("SB", Check, 0)
+            .turn("BB", Check, 0)
+            .turn(HERO, Check, 0)
+            .river("SB", Bet, 60)
+            .river("BB", Fold, 0)
+            .river(HERO, Call, 60)
+            .wins("SB", 180);
+        let won_without_showdown = hand(&["SB", "BB", HERO])
+            .pre(HERO, Raise, 60)
+            .pre("SB", Fold, 0)
+            .pre("BB", Call, 40)
+            .flop("BB", Check, 0)
+            .flop(HERO, Bet, 60)
+            .flop("BB", Fold, 0)
+            .wins(HERO, 190);
+
+        let hands = [won_showdown, lost_showdown, won_without_showdown, call_and_fold_flop(), open_and_take_blinds()];
+        let stats = compute(&hands, HERO);
+        assert_eq!(stats.wtsd, counter(2, 4));
+        assert_eq!(stats.wsd, counter(1, 2));
+        assert_eq!(stats.wwsf, counter(2, 4));
+    }
+
+    #[test]
+    fn bb_per_100_uses_net_result() {
+        // +30 (neto tras poner 60 y ganar 90) y -60 en ciegas de 20
+        let stats = compute(&[open_and_take_blinds(), call_and_fold_flop()], HERO);
+        assert_eq!(stats.ganancia, 30 - 60);
+        assert!((stats.ganancia_bb - (-1.5)).abs() < 1e-9);
+        assert!((stats.bb_100().unwrap() - (-75.0)).abs() < 1e-9);
+
+        let summary = stats.to_player_stats();
+        assert_eq!(summary.total_manos, "2");
+        assert_eq!(summary.bb_100, "-75.00");
+        assert_eq!(summary.win_usd, "-0.30");
+        assert_eq!(summary.vpip, "100.0");
+    }
+
+    #[test]
+    fn ignores_hands_where_the_player_was_not_dealt_in() {
+        let mut away = open_and_take_blinds();
+        away.seats[2].sitting_out = true;
+        let stats = compute(&[away, hand(&["SB", "BB", "CO"]).pre("CO", Fold, 0).wins("BB", 30)], HERO);
+        assert_eq!(stats.total_manos, 0);
+        assert_eq!(stats.vpip.pct(), None);
+        assert_eq!(stats.to_player_stats().vpip, "-");
+    }
+
+    #[test]
+    fn computes_from_parsed_fixture() {
+        let stats = compute(&fixtures::pokerstars_hands(), "HeroPlayer");
+
+        assert_eq!(stats.total_manos, 3);
+        assert_eq!(stats.vpip, counter(2, 3));
+        assert_eq!(stats.pfr, counter(2, 3));
+        assert_eq!(stats.three_bet, counter(1, 1));
+        assert_eq!(stats.cbet_flop, counter(1, 1));
+        assert_eq!(stats.wtsd, counter(1, 1));
+        assert_eq!(stats.wsd, counter(0, 1));
+        assert_eq!(stats.ganancia, -150 + 35);
+    }
+
+    #[test]
+    fn positions_follow_the_button() {
+        let six = hand(&["p1", "p2", "p3", "p4", "p5", "p6"]).wins("p2", 30);
+        let labels: Vec<_> = ["p1", "p2", "p3", "p4", "p5", "p6"].iter()
+            .map(|p| six.position(p).map(|pos| pos.label()))
+            .collect();
+        assert_eq!(labels, [Some("SB"), Some("BB"), Some("EP"), Some("MP"), Some("CO"), Some("BTN")]);
+
+        let nine = hand(&["p1", "p2", "p3", "p4", "p5", "p6", "p7", "p8", "p9"]).wins("p2", 30);
+        assert_eq!(nine.position("p5"), Some(Position::Ep));
+        assert_eq!(nine.position("p6"), Some(Position::Mp));
+        assert_eq!(nine.position("p8"), Some(Position::Co));
+
+        let heads_up = hand(&["p1", "p2"]).wins("p2", 30);
+        assert_eq!(heads_up.position("p2"), Some(Position::Sb));
+        assert_eq!(heads_up.position("p1"), Some(Position::Bb));
+    }
+
+    #[test]
+    fn filter_keeps_matching_hands_only() {
+        let hands = [open_and_take_blinds(), call_and_fold_flop()];
+        let by_position = StatsFilter { posicion: Some(Position::Btn), ..Default::default() };
+        let stats = compute(&hands.iter().filter(|h| by_position.matches(h, HERO)).cloned().collect::<Vec<_>>(), HERO);
+        assert_eq!(stats.total_manos, 1);
+        assert_eq!(stats.pfr, counter(1, 1));
+
+        let higher_stakes = StatsFilter { ciega_min: Some(0.5), ..Default::default() };
+        assert!(!higher_stakes.matches(&hands[0], HERO));
+        let in_january = StatsFilter {
+            desde: NaiveDate::from_ymd_opt(2024, 1, 1),
+            hasta: NaiveDate::from_ymd_opt(2024, 1, 31),
+            max_jugadores: Some(6),
+            ..Default::default()
+        };
+        assert!(in_january.matches(&hands[1], HERO));
+        assert_eq!(in_january.describe(), "6-max, 2024-01-01 a 2024-01-31");
+    }
+
+    #[test]
+    fn positional_rfi_steal_and_fold_to_steal() {
+        // Hero abre desde el botón y la ciega grande se retira ante el robo
+        let steal = open_and_take_blinds();
+        let hero = compute(std::slice::from_ref(&steal), HERO);
+        let btn = &hero.por_posicion[&Position::Btn];
+        assert_eq!(btn.rfi, counter(1, 1));
+        assert_eq!(btn.steal, counter(1, 1));
+        assert_eq!(compute(std::slice::from_ref(&steal), "BB").por_posicion[&Position::Bb].fold_to_steal, counter(1, 1));
+
+        // Con un limp antes no hay RFI ni robo; la ciega grande paga el robo
+        let limped = hand(&["SB", "BB", "EP", HERO]).pre("EP", Call, 20).pre(HERO, Raise, 80).wins(HERO, 30);
+        let defended = hand(&["SB", HERO, "BTN"]).pre("BTN", Raise, 60).pre("SB", Fold, 0).pre(HERO, Call, 40).wins(HERO, 130);
+        let stats = compute(&[limped, defended], HERO);
+        assert_eq!(stats.por_posicion[&Position::Btn].rfi, counter(0, 0));
+        assert_eq!(stats.por_posicion[&Position::Btn].pfr, counter(1, 1));
+        assert_eq!(stats.por_posicion[&Position::Bb].fold_to_steal, counter(0, 1));
+
+        let summary = stats.to_player_stats().by_position.unwrap();
+        assert_eq!(summary[&Position::Bb].fold_to_steal_pct, "0.0");
+        assert_eq!(summary[&Position::Btn].steal_pct, "-");
+    }
+}
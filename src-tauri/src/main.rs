@@ -7,11 +7,32 @@ use tauri::ClipboardManager; // Importar el trait ClipboardManager para acceder
 mod window_manager; 
 mod auth; 
 mod settings;
+mod config_migrations;
+mod config_validation;
+mod profiles;
+mod config_bundle;
+mod config_backups;
+mod config_state;
 mod api;
 mod error;
 mod ocr_bridge;
 mod python_setup;
 mod right_click_detector;  // Nuevo módulo
+mod secrets;
+mod entitlements;
+mod notes;
+mod notes_sync;
+mod notes_import;
+mod hand_history;
+mod local_stats;
+mod hand_db;
+mod hand_import;
+mod stats_source;
+mod stats_filter;
+mod hero_report;
+mod equity;
+mod graphs;
+mod replayer;
 
 use std::collections::HashMap;
 use std::sync::Mutex;
@@ -19,6 +40,8 @@ use once_cell::sync::Lazy;
 use image::{RgbaImage, ImageOutputFormat};
 use base64;
 use right_click_detector::RightClickDetector;
+use config_state::ConfigState;
+use error::AppError;
 
 // Variable global para almacenar el detector
 static RIGHT_CLICK_DETECTOR: Lazy<Mutex<Option<RightClickDetector>>> = 
@@ -32,8 +55,21 @@ fn find_poker_tables() -> Vec<(u32, String)> {
 
 // Comando para analizar una mesa específica  
 #[tauri::command]
-async fn analyze_table(hwnd: u32, config: settings::AppConfig, manual_nick: Option<String>, force_new_capture: bool) -> Result<String, String> {
-    window_manager::analyze_table(hwnd, config, manual_nick, force_new_capture).await
+async fn analyze_table(app_handle: tauri::AppHandle, state: tauri::State<'_, ConfigState>, hwnd: u32, config: Option<settings::AppConfig>, manual_nick: Option<String>, force_new_capture: bool, automatic: Option<bool>) -> Result<String, AppError> {
+    // Las mesas analizadas en modo automático cuentan para el límite de la suscripción
+    if automatic.unwrap_or(false) {
+        entitlements::register_auto_table(hwnd)?;
+    }
+    
+    // Sin configuración explícita se usa la guardada en memoria
+    let config = config.unwrap_or_else(|| state.get());
+    let result = window_manager::analyze_table(hwnd, config, manual_nick, force_new_capture).await;
+    if let Err(e) = &result {
+        if auth::is_session_expired_error(e) {
+            auth::handle_session_expired(&app_handle);
+        }
+    }
+    Ok(result?)
 }
 
 // Comando para obtener la mesa bajo el cursor 
@@ -44,23 +80,383 @@ fn get_window_under_cursor() -> Option<(u32, String)> {
 
 // Comando para guardar configuración 
 #[tauri::command] 
-fn save_config(config: settings::AppConfig) -> Result<(), String> {
-    settings::save_config(config) 
+fn save_config(app_handle: tauri::AppHandle, state: tauri::State<'_, ConfigState>, config: settings::AppConfig) -> Result<config_validation::ValidationReport, String> {
+    state.update_validated(&app_handle, config)
 }  
 
+// Comando para validar la configuración sin guardarla
+#[tauri::command]
+fn validate_config(config: settings::AppConfig) -> config_validation::ValidationReport {
+    config_validation::validate(&config)
+}
+
+// Comando para exportar perfiles y disposición de stats (sin secretos) a un archivo
+#[tauri::command]
+fn export_settings_bundle(state: tauri::State<'_, ConfigState>, path: String) -> Result<(), String> {
+    let config = state.get();
+    config_bundle::export_bundle(&config, &path)
+}
+
+// Comando para ver qué cambiaría al importar un paquete de ajustes
+#[tauri::command]
+fn preview_settings_bundle(state: tauri::State<'_, ConfigState>, path: String) -> Result<config_bundle::BundlePreview, String> {
+    let config = state.get();
+    config_bundle::preview_bundle(&config, &path)
+}
+
+// Comando para importar un paquete de ajustes (opcionalmente solo algunos campos)
+#[tauri::command]
+fn import_settings_bundle(app_handle: tauri::AppHandle, state: tauri::State<'_, ConfigState>, path: String, campos: Option<Vec<String>>) -> Result<config_validation::ValidationReport, String> {
+    let config = state.get();
+    let merged = config_bundle::import_bundle(&config, &path, campos)?;
+    state.update_validated(&app_handle, merged)
+}
+
+// Comando para listar las copias de seguridad de la configuración
+#[tauri::command]
+fn list_config_backups() -> Result<Vec<config_backups::BackupInfo>, String> {
+    config_backups::list_backups()
+}
+
+// Comando para restaurar una copia de seguridad de la configuración
+#[tauri::command]
+fn restore_config_backup(app_handle: tauri::AppHandle, state: tauri::State<'_, ConfigState>, nombre: String) -> Result<settings::AppConfig, String> {
+    let config = config_backups::read_backup(&nombre)?;
+    // Al guardar se crea una copia de la configuración actual, así que se puede deshacer
+    state.update(&app_handle, config.clone())?;
+    Ok(config)
+}
+
+// Comando para obtener el perfil que se aplicará a una mesa
+#[tauri::command]
+fn get_profile_for_table(state: tauri::State<'_, ConfigState>, hwnd: u32) -> serde_json::Value {
+    let config = state.get();
+    let title = window_manager::get_window_title(hwnd).unwrap_or_default();
+    let (effective, profile_name) = profiles::resolve_for_title(&config, &title);
+    serde_json::json!({
+        "sitio": profiles::detect_site(&title),
+        "perfil": profile_name,
+        "config": effective
+    })
+}
+
+// Comando para obtener las salas que se detectan en los títulos de ventana
+#[tauri::command]
+fn get_known_sites() -> Vec<&'static str> {
+    profiles::known_sites()
+}
+
+// Comando para obtener los problemas detectados al cargar la configuración
+#[tauri::command]
+fn get_config_validation() -> config_validation::ValidationReport {
+    config_validation::last_load_report()
+}
+
 // Comando para cargar configuración 
 #[tauri::command] 
-fn load_config() -> settings::AppConfig {
-    settings::load_config() 
+fn load_config(state: tauri::State<'_, ConfigState>) -> settings::AppConfig {
+    state.get()
 }  
 
 // Comando para iniciar sesión 
 #[tauri::command] 
-async fn login(email: String, password: String) -> Result<auth::AuthResponse, String> {
-    let config = settings::load_config();
-    auth::login(email, password, &config.server_url).await 
+async fn login(state: tauri::State<'_, ConfigState>, email: String, password: String) -> Result<auth::Session, String> {
+    let config = state.get();
+    let response = auth::login(email, password, &config.server_url).await?;
+    
+    // Guardar la sesión en el backend para que los comandos usen el token
+    auth::set_current_user(Some(response.usuario.clone()));
+    secrets::set_secret(secrets::SecretKind::Token, &response.token)?;
+    
+    Ok(auth::Session {
+        mensaje: response.mensaje,
+        usuario: response.usuario,
+    })
 }  
 
+// Comando para cerrar sesión
+#[tauri::command]
+async fn logout(state: tauri::State<'_, ConfigState>) -> Result<(), String> {
+    let config = state.get();
+    let token = secrets::get_secret(secrets::SecretKind::Token)?;
+    auth::logout(&token, &config.server_url).await?;
+    
+    secrets::clear_secret(secrets::SecretKind::Token)
+}
+
+// Comando para obtener el usuario de la sesión activa
+#[tauri::command]
+async fn get_current_user(state: tauri::State<'_, ConfigState>) -> Result<Option<auth::User>, String> {
+    let config = state.get();
+    let token = secrets::get_secret(secrets::SecretKind::Token)?;
+    auth::get_current_user(&token, &config.server_url).await
+}
+
+// Comando para verificar si el token guardado sigue siendo válido
+#[tauri::command]
+async fn verify_session(state: tauri::State<'_, ConfigState>) -> Result<bool, String> {
+    let config = state.get();
+    let token = secrets::get_secret(secrets::SecretKind::Token)?;
+    auth::verify_token(&token, &config.server_url).await
+}
+
+// Comando para obtener las capacidades de la suscripción activa
+#[tauri::command]
+fn get_entitlements() -> serde_json::Value {
+    serde_json::json!({
+        "entitlements": entitlements::current(),
+        "remaining_analyses_today": entitlements::remaining_analyses()
+    })
+}
+
+// Comando para obtener el estado del almacén de secretos (sin valores)
+#[tauri::command]
+fn get_secrets_status() -> secrets::SecretsStatus {
+    secrets::status()
+}
+
+// Comando para desbloquear el almacén de secretos con contraseña
+#[tauri::command]
+fn unlock_secrets(passphrase: String) -> Result<(), String> {
+    secrets::unlock_with_passphrase(&passphrase)
+}
+
+// Comando para descartar un almacén de secretos que no se puede abrir.
+// Los secretos guardados se pierden, así que la interfaz debe pedir confirmación.
+#[tauri::command]
+fn reset_secrets_store(confirmar: bool) -> Result<(), String> {
+    if !confirmar {
+        return Err("Hay que confirmar que se descartan los secretos guardados".to_string());
+    }
+    secrets::reset_store()
+}
+
+// Comando para guardar un secreto
+#[tauri::command]
+fn set_secret(kind: secrets::SecretKind, value: String) -> Result<(), String> {
+    secrets::set_secret(kind, &value)
+}
+
+// Comando para eliminar un secreto
+#[tauri::command]
+fn clear_secret(kind: secrets::SecretKind) -> Result<(), String> {
+    secrets::clear_secret(kind)
+}
+
+// Comando para comprobar que un secreto es válido
+#[tauri::command]
+async fn test_secret(state: tauri::State<'_, ConfigState>, kind: secrets::SecretKind) -> Result<bool, String> {
+    let config = state.get();
+    secrets::test_secret(kind, &config.server_url).await
+}
+
+// Comando para obtener las notas y etiquetas de un jugador
+#[tauri::command]
+fn get_player_notes(sala: String, nick: String) -> Result<Option<notes::PlayerRecord>, String> {
+    notes::get_player(&sala, &nick)
+}
+
+// Comando para añadir una nota a un jugador
+#[tauri::command]
+fn add_player_note(sala: String, nick: String, texto: String) -> Result<notes::PlayerRecord, String> {
+    notes::add_note(&sala, &nick, &texto)
+}
+
+// Comando para modificar una nota
+#[tauri::command]
+fn update_player_note(sala: String, nick: String, id: String, texto: String) -> Result<notes::PlayerRecord, String> {
+    notes::update_note(&sala, &nick, &id, &texto)
+}
+
+// Comando para eliminar una nota
+#[tauri::command]
+fn delete_player_note(sala: String, nick: String, id: String) -> Result<Option<notes::PlayerRecord>, String> {
+    notes::delete_note(&sala, &nick, &id)
+}
+
+// Comando para asignar etiquetas a un jugador
+#[tauri::command]
+fn set_player_tags(sala: String, nick: String, etiquetas: Vec<String>) -> Result<Option<notes::PlayerRecord>, String> {
+    notes::set_tags(&sala, &nick, etiquetas)
+}
+
+// Comando para buscar jugadores por nick, texto de las notas, sala o etiqueta
+#[tauri::command]
+fn search_player_notes(query: String, sala: Option<String>, etiqueta: Option<String>) -> Result<Vec<notes::PlayerRecord>, String> {
+    notes::search(&query, sala.as_deref(), etiqueta.as_deref())
+}
+
+// Comando para obtener las etiquetas disponibles
+#[tauri::command]
+fn get_note_tags() -> Result<Vec<notes::TagDef>, String> {
+    notes::list_tags()
+}
+
+// Comando para crear una etiqueta o cambiar su color
+#[tauri::command]
+fn save_note_tag(tag: notes::TagDef) -> Result<Vec<notes::TagDef>, String> {
+    notes::save_tag(tag)
+}
+
+// Comando para eliminar una etiqueta
+#[tauri::command]
+fn delete_note_tag(nombre: String) -> Result<Vec<notes::TagDef>, String> {
+    notes::delete_tag(&nombre)
+}
+
+// Comando para importar notas de HoldemManager 3 (XML) o PokerTracker 4 (CSV).
+// Con `simulacion` solo devuelve el informe sin modificar las notas.
+#[tauri::command]
+fn import_player_notes(path: String, options: notes_import::ImportOptions) -> Result<notes_import::ImportReport, String> {
+    notes_import::import_notes(&path, options)
+}
+
+// Comando para sincronizar las notas con el equipo
+#[tauri::command]
+async fn sync_notes(app_handle: tauri::AppHandle, state: tauri::State<'_, ConfigState>) -> Result<notes_sync::SyncSummary, String> {
+    let config = state.get();
+    let token = secrets::get_secret(secrets::SecretKind::Token)?;
+    let result = notes_sync::sync(&token, &config.server_url).await;
+    if let Err(e) = &result {
+        if auth::is_session_expired_error(e) {
+            auth::handle_session_expired(&app_handle);
+        }
+    }
+    result
+}
+
+// Comando para obtener el estado de la sincronización de notas
+#[tauri::command]
+fn get_notes_sync_status() -> notes_sync::SyncStatus {
+    notes_sync::status()
+}
+
+// Comando para obtener las notas en conflicto con el servidor
+#[tauri::command]
+fn get_note_conflicts() -> Result<Vec<notes::NoteConflict>, String> {
+    notes_sync::list_conflicts()
+}
+
+// Comando para resolver un conflicto conservando la nota local o la del servidor
+#[tauri::command]
+fn resolve_note_conflict(id: String, conservar_local: bool) -> Result<(), String> {
+    notes_sync::resolve_conflict(&id, conservar_local)
+}
+
+// Comando para leer un archivo de historial de manos.
+// Devuelve las manos válidas y los errores de las que no se pudieron interpretar.
+#[tauri::command]
+fn parse_hand_history_file(path: String) -> Result<hand_history::ParseOutcome, String> {
+    hand_history::parse_file(&path)
+}
+
+// Comando para importar ahora las carpetas de historiales configuradas
+#[tauri::command]
+async fn import_hand_histories(app_handle: tauri::AppHandle) -> Result<hand_import::ScanSummary, String> {
+    tauri::async_runtime::spawn_blocking(move || hand_import::import_now(&app_handle))
+        .await
+        .map_err(|e| format!("Error al importar historiales: {}", e))?
+}
+
+// Comando para obtener las stats de un jugador de la base de datos local,
+// con el mismo formato que get_player_stats
+#[tauri::command]
+fn get_local_db_player_stats(state: tauri::State<'_, ConfigState>, nick: String, sala: String, filter: Option<stats_filter::StatsFilter>) -> Result<api::PlayerStats, String> {
+    let site = profiles::local_site(&state.get(), &sala, None);
+    hand_db::get_player_stats(&site, &nick, &filter.unwrap_or_default())
+}
+
+// Comando para generar el informe de las manos propias importadas.
+// Con `analizar` el informe se envía además al proveedor de análisis.
+#[tauri::command]
+async fn get_hero_report(state: tauri::State<'_, ConfigState>, filter: Option<stats_filter::StatsFilter>, analizar: Option<bool>) -> Result<hero_report::HeroReport, AppError> {
+    let config = state.get();
+    let filter = filter.unwrap_or_default();
+    let ranges = config.rangos_referencia.clone();
+    let mut report = tauri::async_runtime::spawn_blocking(move || hero_report::hero_report(&filter, &ranges))
+        .await
+        .map_err(|e| format!("Error al generar el informe: {}", e))??;
+
+    if analizar.unwrap_or(false) {
+        let api_key = secrets::get_secret(secrets::SecretKind::OpenaiApiKey)?;
+        api::check_api_key(&api_key)?;
+        // El análisis se reserva antes de llamar al proveedor y se devuelve si la llamada falla
+        entitlements::consume_analysis()?;
+        let analisis = api::analyze_hero_report(report.texto.clone(), api_key).await;
+        if analisis.is_err() {
+            entitlements::refund_analysis();
+        }
+        report.analisis = Some(analisis?);
+    }
+    Ok(report)
+}
+
+// Comando para obtener las series de la gráfica de ganancias de las manos propias
+#[tauri::command]
+async fn get_winnings_graph(filter: Option<stats_filter::StatsFilter>, eje: Option<graphs::GraphAxis>, max_puntos: Option<usize>) -> Result<graphs::WinningsGraph, String> {
+    let filter = filter.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || graphs::winnings_graph(&filter, eje.unwrap_or_default(), max_puntos))
+        .await
+        .map_err(|e| format!("Error al generar la gráfica: {}", e))?
+}
+
+// Comando para obtener una mano guardada preparada para el reproductor
+#[tauri::command]
+fn get_hand_replay(state: tauri::State<'_, ConfigState>, sala: String, hand_id: String) -> Result<replayer::HandReplay, String> {
+    let site = profiles::local_site(&state.get(), &sala, None);
+    replayer::hand_replay(&site, &hand_id)
+}
+
+// Comando para listar las manos guardadas de un jugador (p. ej. las que respaldan una nota)
+#[tauri::command]
+fn list_player_hands(state: tauri::State<'_, ConfigState>, sala: String, nick: String, filter: Option<replayer::HandListFilter>) -> Result<Vec<replayer::HandSummary>, String> {
+    let site = profiles::local_site(&state.get(), &sala, None);
+    replayer::list_player_hands(&site, &nick, &filter.unwrap_or_default())
+}
+
+// Comando para exportar manos guardadas como texto con el formato de PokerStars
+#[tauri::command]
+fn export_hands(state: tauri::State<'_, ConfigState>, sala: String, hand_ids: Vec<String>, options: Option<hand_history::export::ExportOptions>) -> Result<String, AppError> {
+    entitlements::require_export()?;
+    let site = profiles::local_site(&state.get(), &sala, None);
+    let hands = hand_ids.iter()
+        .map(|hand_id| hand_db::get_hand(&site, hand_id)?
+            .ok_or_else(|| format!("No se encontró la mano {} de {}", hand_id, sala)))
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(hand_history::export::hands_to_pokerstars(&hands, &options.unwrap_or_default()))
+}
+
+// Comando para calcular la equidad de varias manos con un board parcial y cartas muertas
+#[tauri::command]
+async fn calculate_equity(manos: Vec<String>, board: Option<String>, muertas: Option<String>, iteraciones: Option<u32>) -> Result<equity::EquityResult, String> {
+    let hands = manos.iter().map(|mano| equity::parse_cards(mano)).collect::<Result<Vec<_>, String>>()?;
+    let board = equity::parse_cards(board.as_deref().unwrap_or(""))?;
+    let dead = equity::parse_cards(muertas.as_deref().unwrap_or(""))?;
+    tauri::async_runtime::spawn_blocking(move || equity::calculate(&hands, &board, &dead, iteraciones))
+        .await
+        .map_err(|e| format!("Error al calcular la equidad: {}", e))?
+}
+
+// Comando para obtener el estado de la importación de historiales
+#[tauri::command]
+fn get_hand_import_status() -> hand_import::ImportStatus {
+    hand_import::status()
+}
+
+// Comando para calcular las stats de un jugador a partir de historiales locales,
+// con el número de oportunidades de cada stat
+#[tauri::command]
+fn compute_local_stats(paths: Vec<String>, nick: String, filter: Option<stats_filter::StatsFilter>) -> Result<local_stats::LocalStats, String> {
+    local_stats::compute_from_files(&paths, &nick, &filter.unwrap_or_default())
+}
+
+// Igual que compute_local_stats pero con el formato de get_player_stats,
+// para mostrarlas sin servidor o compararlas con las remotas
+#[tauri::command]
+fn get_local_player_stats(paths: Vec<String>, nick: String, filter: Option<stats_filter::StatsFilter>) -> Result<api::PlayerStats, String> {
+    local_stats::compute_from_files(&paths, &nick, &filter.unwrap_or_default()).map(|stats| stats.to_player_stats())
+}
+
 // Comando para obtener la versión de la aplicación 
 #[tauri::command] 
 fn get_app_version() -> String {
@@ -75,16 +471,32 @@ fn clear_nick_cache() -> bool {
 
 // Comando para obtener estadísticas de jugador
 #[tauri::command] 
-async fn get_player_stats(nick: String, sala: String) -> Result<api::PlayerStats, String> {
-    let config = settings::load_config();
-    api::get_player_stats(nick, sala, config.token, config.server_url).await
+async fn get_player_stats(app_handle: tauri::AppHandle, state: tauri::State<'_, ConfigState>, nick: String, sala: String, filter: Option<stats_filter::StatsFilter>) -> Result<api::PlayerStats, String> {
+    let config = state.get();
+    // Sin filtro explícito se usa el de la configuración
+    let filter = filter.unwrap_or_else(|| config.filtro_stats.clone());
+    let site = profiles::local_site(&config, &sala, None);
+    let result = stats_source::StatsSource::from_config(&config)?.get_player_stats(&nick, &sala, &site, &filter).await;
+    if let Err(e) = &result {
+        if auth::is_session_expired_error(e) {
+            auth::handle_session_expired(&app_handle);
+        }
+    }
+    result
 }
 
 // Comando para analizar estadísticas
 #[tauri::command]
-async fn analyze_stats(data: api::PlayerStats) -> Result<String, String> {
-    let config = settings::load_config();
-    api::analyze_stats(data, config.openai_api_key).await
+async fn analyze_stats(state: tauri::State<'_, ConfigState>, data: api::PlayerStats) -> Result<String, AppError> {
+    let api_key = secrets::get_secret(secrets::SecretKind::OpenaiApiKey)?;
+    api::check_api_key(&api_key)?;
+    entitlements::consume_analysis()?;
+    let config = state.get();
+    let analisis = api::analyze_stats(data, api_key, config.plantilla_analisis).await;
+    if analisis.is_err() {
+        entitlements::refund_analysis();
+    }
+    Ok(analisis?)
 }
 
 // Comando para copiar al portapapeles - versión corregida con ClipboardManager
@@ -311,7 +723,52 @@ fn main() {
             get_window_under_cursor,
             save_config,
             load_config,
+            validate_config,
+            get_config_validation,
+            get_profile_for_table,
+            export_settings_bundle,
+            preview_settings_bundle,
+            import_settings_bundle,
+            list_config_backups,
+            restore_config_backup,
+            get_known_sites,
             login,
+            logout,
+            get_current_user,
+            verify_session,
+            get_entitlements,
+            get_secrets_status,
+            unlock_secrets,
+            reset_secrets_store,
+            set_secret,
+            clear_secret,
+            test_secret,
+            get_player_notes,
+            add_player_note,
+            update_player_note,
+            delete_player_note,
+            set_player_tags,
+            search_player_notes,
+            get_note_tags,
+            save_note_tag,
+            delete_note_tag,
+            import_player_notes,
+            sync_notes,
+            get_notes_sync_status,
+            get_note_conflicts,
+            resolve_note_conflict,
+            parse_hand_history_file,
+            compute_local_stats,
+            get_local_player_stats,
+            import_hand_histories,
+            get_hand_import_status,
+            get_local_db_player_stats,
+            get_hero_report,
+            calculate_equity,
+            get_winnings_graph,
+            get_hand_replay,
+            list_player_hands,
+            export_hands,
             get_app_version,
             clear_nick_cache,
             get_player_stats,
@@ -329,6 +786,19 @@ fn main() {
         .setup(|app| { // Agregado guión bajo para indicar que no se utiliza
             // Inicializar componentes en el arranque
             let config = settings::load_config();
+            app.manage(ConfigState::new(config.clone()));
+            
+            // Recargar la configuración si se modifica config.json fuera de la app
+            config_state::start_watcher(app.handle());
+            
+            // Renovar el token de sesión antes de que expire
+            auth::start_token_refresh(app.handle());
+            
+            // Sincronizar las notas del equipo en segundo plano
+            notes_sync::start_notes_sync(app.handle());
+            
+            // Importar las manos nuevas de las carpetas de historiales
+            hand_import::start_hand_import(app.handle());
             
             // Crear directorios Python si no existen
             let _ = python_setup::ensure_python_env();
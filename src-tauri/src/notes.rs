@@ -0,0 +1,497 @@
+// src-tauri/src/notes.rs
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use once_cell::sync::Lazy;
+use rand::RngCore;
+
+const NOTES_VERSION: u32 = 1;
+
+// Etiquetas disponibles la primera vez que se abre el almacén
+const DEFAULT_TAGS: &[(&str, &str)] = &[
+    ("fish", "#38a169"),
+    ("reg", "#3182ce"),
+    ("tilter", "#e53e3e"),
+    ("nit", "#718096"),
+    ("maniac", "#d69e2e"),
+];
+
+// Color de las etiquetas creadas automáticamente (sincronización, importación)
+pub(crate) const NEW_TAG_COLOR: &str = "#a0aec0";
+
+// Etiqueta con su color para mostrarla en la interfaz
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagDef {
+    pub nombre: String,
+    pub color: String,
+}
+
+// Nota sobre un jugador
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlayerNote {
+    pub id: String,
+    pub texto: String,
+    pub creado: String,
+    pub actualizado: String,
+    #[serde(default)]
+    pub autor: Option<String>,
+    // Versión del servidor en la que se basa la nota (0 si nunca se ha sincronizado)
+    #[serde(default)]
+    pub version: u64,
+    // Cambio local pendiente de enviar al servidor
+    #[serde(default)]
+    pub pendiente: bool,
+    // Nota eliminada localmente que aún hay que borrar en el servidor
+    #[serde(default)]
+    pub eliminado: bool,
+}
+
+// Notas y etiquetas de un jugador en una sala
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct PlayerRecord {
+    pub sala: String,
+    pub nick: String,
+    pub etiquetas: Vec<String>,
+    pub notas: Vec<PlayerNote>,
+    // Estado de sincronización de las etiquetas (se sincronizan como un bloque)
+    pub etiquetas_actualizado: String,
+    pub etiquetas_version: u64,
+    pub etiquetas_pendiente: bool,
+}
+
+impl PlayerRecord {
+    // Copia sin las notas eliminadas pendientes de sincronizar
+    fn visible(&self) -> PlayerRecord {
+        let mut player = self.clone();
+        player.notas.retain(|n| !n.eliminado);
+        player
+    }
+
+    pub(crate) fn has_visible_content(&self) -> bool {
+        !self.etiquetas.is_empty() || self.notas.iter().any(|n| !n.eliminado)
+    }
+
+    // El registro se puede borrar cuando no queda nada que mostrar ni que sincronizar
+    pub(crate) fn is_removable(&self) -> bool {
+        self.notas.is_empty() && self.etiquetas.is_empty() && !self.etiquetas_pendiente
+    }
+}
+
+// Conflicto entre una nota local y la versión del servidor.
+// La nota local no se envía hasta que el usuario elige cuál conservar.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteConflict {
+    pub id: String,
+    pub sala: String,
+    pub nick: String,
+    pub local: PlayerNote,
+    pub remoto: PlayerNote,
+}
+
+// Contenido de player_notes.json
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct NotesStore {
+    pub version: u32,
+    pub etiquetas: Vec<TagDef>,
+    // Clave: ver player_key
+    pub jugadores: HashMap<String, PlayerRecord>,
+    // Posición devuelta por el servidor en la última sincronización
+    pub cursor_sync: Option<String>,
+    pub conflictos: Vec<NoteConflict>,
+}
+
+impl Default for NotesStore {
+    fn default() -> Self {
+        NotesStore {
+            version: NOTES_VERSION,
+            etiquetas: DEFAULT_TAGS.iter()
+                .map(|(nombre, color)| TagDef { nombre: nombre.to_string(), color: color.to_string() })
+                .collect(),
+            jugadores: HashMap::new(),
+            cursor_sync: None,
+            conflictos: Vec::new(),
+        }
+    }
+}
+
+// Almacén cargado en memoria (se lee del disco la primera vez que se usa)
+static NOTES: Lazy<Mutex<Option<NotesStore>>> = Lazy::new(|| Mutex::new(None));
+
+// Clave de un jugador: sala y nick sin distinguir mayúsculas
+pub fn player_key(sala: &str, nick: &str) -> String {
+    format!("{}:{}", sala.trim().to_lowercase(), nick.trim().to_lowercase())
+}
+
+fn notes_path() -> Result<PathBuf, String> {
+    let app_data_dir = tauri::api::path::app_data_dir(&tauri::Config::default())
+        .ok_or("No se pudo determinar el directorio de datos")?;
+    Ok(app_data_dir.join("player_notes.json"))
+}
+
+fn read_store() -> Result<NotesStore, String> {
+    let path = notes_path()?;
+    if !path.exists() {
+        return Ok(NotesStore::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Error al leer notas de jugadores: {}", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Error al interpretar notas de jugadores: {}", e))
+}
+
+// Escribe el almacén en un archivo temporal y lo renombra, igual que config.json
+fn write_store(store: &NotesStore) -> Result<(), String> {
+    let path = notes_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Error al crear directorio de datos: {}", e))?;
+    }
+
+    let serialized = serde_json::to_string_pretty(store)
+        .map_err(|e| format!("Error al serializar notas de jugadores: {}", e))?;
+
+    let tmp_path = path.with_extension("json.tmp");
+    {
+        let mut file = fs::File::create(&tmp_path)
+            .map_err(|e| format!("Error al escribir notas de jugadores: {}", e))?;
+        file.write_all(serialized.as_bytes())
+            .and_then(|_| file.sync_all())
+            .map_err(|e| format!("Error al escribir notas de jugadores: {}", e))?;
+    }
+    fs::rename(&tmp_path, &path)
+        .map_err(|e| format!("Error al reemplazar notas de jugadores: {}", e))
+}
+
+// Ejecuta una función con el almacén cargado, sin modificarlo
+pub(crate) fn with_store<T>(f: impl FnOnce(&NotesStore) -> T) -> Result<T, String> {
+    let mut guard = NOTES.lock().map_err(|_| "No se pudo acceder a las notas")?;
+    if guard.is_none() {
+        *guard = Some(read_store()?);
+    }
+    Ok(f(guard.as_ref().unwrap()))
+}
+
+// Ejecuta una modificación sobre el almacén y lo guarda en disco
+pub(crate) fn update_store<T>(f: impl FnOnce(&mut NotesStore) -> Result<T, String>) -> Result<T, String> {
+    let mut guard = NOTES.lock().map_err(|_| "No se pudo acceder a las notas")?;
+    if guard.is_none() {
+        *guard = Some(read_store()?);
+    }
+
+    // Se trabaja sobre una copia para no dejar cambios en memoria si falla el guardado
+    let mut store = guard.as_ref().unwrap().clone();
+    let result = f(&mut store)?;
+    write_store(&store)?;
+    *guard = Some(store);
+    Ok(result)
+}
+
+pub(crate) fn new_note_id() -> String {
+    let mut bytes = [0u8; 8];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Fechas de notas y etiquetas en UTC (RFC 3339)
+pub(crate) fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+// Interpreta una fecha RFC 3339. Las guardadas por versiones anteriores tienen
+// la zona horaria local, así que se comparan como fechas y no como texto.
+pub(crate) fn parse_timestamp(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc3339(value).ok().map(|date| date.with_timezone(&chrono::Utc))
+}
+
+pub(crate) fn player_mut<'a>(store: &'a mut NotesStore, sala: &str, nick: &str) -> &'a mut PlayerRecord {
+    store.jugadores.entry(player_key(sala, nick)).or_insert_with(|| PlayerRecord {
+        sala: sala.trim().to_string(),
+        nick: nick.trim().to_string(),
+        ..Default::default()
+    })
+}
+
+fn validate_player(sala: &str, nick: &str) -> Result<(), String> {
+    if sala.trim().is_empty() || nick.trim().is_empty() {
+        return Err("La sala y el nick no pueden estar vacíos".to_string());
+    }
+    Ok(())
+}
+
+// Notas y etiquetas de un jugador, si tiene alguna
+pub fn get_player(sala: &str, nick: &str) -> Result<Option<PlayerRecord>, String> {
+    with_store(|store| {
+        store.jugadores.get(&player_key(sala, nick))
+            .filter(|p| p.has_visible_content())
+            .map(|p| p.visible())
+    })
+}
+
+// Añade una nota a un jugador
+pub fn add_note(sala: &str, nick: &str, texto: &str) -> Result<PlayerRecord, String> {
+    validate_player(sala, nick)?;
+    if texto.trim().is_empty() {
+        return Err("La nota no puede estar vacía".to_string());
+    }
+
+    update_store(|store| Ok(add_note_in(store, sala, nick, texto)))
+}
+
+fn add_note_in(store: &mut NotesStore, sala: &str, nick: &str, texto: &str) -> PlayerRecord {
+    let timestamp = now();
+    let player = player_mut(store, sala, nick);
+    player.notas.push(PlayerNote {
+        id: new_note_id(),
+        texto: texto.trim().to_string(),
+        creado: timestamp.clone(),
+        actualizado: timestamp,
+        autor: crate::auth::current_user_name(),
+        version: 0,
+        pendiente: true,
+        eliminado: false,
+    });
+    player.visible()
+}
+
+// Modifica el texto de una nota
+pub fn update_note(sala: &str, nick: &str, id: &str, texto: &str) -> Result<PlayerRecord, String> {
+    if texto.trim().is_empty() {
+        return Err("La nota no puede estar vacía".to_string());
+    }
+
+    update_store(|store| {
+        let player = store.jugadores.get_mut(&player_key(sala, nick))
+            .ok_or_else(|| format!("No hay notas para {}", nick))?;
+        let note = player.notas.iter_mut().find(|n| n.id == id && !n.eliminado)
+            .ok_or_else(|| format!("Nota no encontrada: {}", id))?;
+        note.texto = texto.trim().to_string();
+        note.actualizado = now();
+        note.pendiente = true;
+        Ok(player.visible())
+    })
+}
+
+// Elimina una nota; el jugador se elimina si se queda sin notas ni etiquetas
+pub fn delete_note(sala: &str, nick: &str, id: &str) -> Result<Option<PlayerRecord>, String> {
+    update_store(|store| {
+        let key = player_key(sala, nick);
+        let player = store.jugadores.get_mut(&key)
+            .ok_or_else(|| format!("No hay notas para {}", nick))?;
+        let index = player.notas.iter().position(|n| n.id == id && !n.eliminado)
+            .ok_or_else(|| format!("Nota no encontrada: {}", id))?;
+
+        // Las notas que ya están en el servidor se marcan para borrarlas al sincronizar
+        if player.notas[index].version > 0 {
+            let note = &mut player.notas[index];
+            note.eliminado = true;
+            note.pendiente = true;
+            note.actualizado = now();
+        } else {
+            player.notas.remove(index);
+        }
+        store.conflictos.retain(|c| c.id != id);
+
+        let result = player.has_visible_content().then(|| player.visible());
+        if player.is_removable() {
+            store.jugadores.remove(&key);
+        }
+        Ok(result)
+    })
+}
+
+// Sustituye las etiquetas de un jugador
+pub fn set_tags(sala: &str, nick: &str, etiquetas: Vec<String>) -> Result<Option<PlayerRecord>, String> {
+    validate_player(sala, nick)?;
+    update_store(|store| set_tags_in(store, sala, nick, etiquetas))
+}
+
+fn set_tags_in(store: &mut NotesStore, sala: &str, nick: &str, etiquetas: Vec<String>) -> Result<Option<PlayerRecord>, String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in etiquetas {
+        let known = store.etiquetas.iter()
+            .find(|t| t.nombre.eq_ignore_ascii_case(tag.trim()))
+            .ok_or_else(|| format!("Etiqueta desconocida: '{}'", tag))?;
+        if !tags.contains(&known.nombre) {
+            tags.push(known.nombre.clone());
+        }
+    }
+
+    let key = player_key(sala, nick);
+    let player = player_mut(store, sala, nick);
+    if player.etiquetas != tags {
+        player.etiquetas = tags;
+        player.etiquetas_actualizado = now();
+        // Si nunca se sincronizaron y quedan vacías no hay nada que enviar
+        player.etiquetas_pendiente = player.etiquetas_version > 0 || !player.etiquetas.is_empty();
+    }
+
+    let result = player.has_visible_content().then(|| player.visible());
+    if player.is_removable() {
+        store.jugadores.remove(&key);
+    }
+    Ok(result)
+}
+
+// Etiquetas disponibles
+pub fn list_tags() -> Result<Vec<TagDef>, String> {
+    with_store(|store| store.etiquetas.clone())
+}
+
+// Crea una etiqueta o cambia el color de una existente
+pub fn save_tag(tag: TagDef) -> Result<Vec<TagDef>, String> {
+    let nombre = tag.nombre.trim().to_string();
+    if nombre.is_empty() {
+        return Err("La etiqueta necesita un nombre".to_string());
+    }
+    if !is_hex_color(&tag.color) {
+        return Err(format!("Color no válido: '{}' (usa el formato #rrggbb)", tag.color));
+    }
+
+    update_store(|store| {
+        match store.etiquetas.iter_mut().find(|t| t.nombre.eq_ignore_ascii_case(&nombre)) {
+            Some(existing) => existing.color = tag.color.clone(),
+            None => store.etiquetas.push(TagDef { nombre, color: tag.color.clone() }),
+        }
+        Ok(store.etiquetas.clone())
+    })
+}
+
+// Elimina una etiqueta y la quita de todos los jugadores
+pub fn delete_tag(nombre: &str) -> Result<Vec<TagDef>, String> {
+    update_store(|store| {
+        delete_tag_in(store, nombre);
+        Ok(store.etiquetas.clone())
+    })
+}
+
+fn delete_tag_in(store: &mut NotesStore, nombre: &str) {
+    store.etiquetas.retain(|t| !t.nombre.eq_ignore_ascii_case(nombre));
+    for player in store.jugadores.values_mut() {
+        let before = player.etiquetas.len();
+        player.etiquetas.retain(|t| !t.eq_ignore_ascii_case(nombre));
+        if player.etiquetas.len() != before {
+            player.etiquetas_actualizado = now();
+            player.etiquetas_pendiente = player.etiquetas_version > 0 || !player.etiquetas.is_empty();
+        }
+    }
+    store.jugadores.retain(|_, p| !p.is_removable());
+}
+
+fn is_hex_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+// Busca jugadores por nick o texto de sus notas, opcionalmente filtrando por sala y etiqueta.
+// Los resultados se ordenan por la nota más reciente.
+pub fn search(query: &str, sala: Option<&str>, etiqueta: Option<&str>) -> Result<Vec<PlayerRecord>, String> {
+    with_store(|store| search_in(store, query, sala, etiqueta))
+}
+
+fn search_in(store: &NotesStore, query: &str, sala: Option<&str>, etiqueta: Option<&str>) -> Vec<PlayerRecord> {
+    let query = query.trim().to_lowercase();
+    let mut results: Vec<PlayerRecord> = store.jugadores.values()
+        .filter(|p| p.has_visible_content())
+        .map(|p| p.visible())
+        .filter(|p| sala.is_none_or(|s| p.sala.eq_ignore_ascii_case(s)))
+        .filter(|p| etiqueta.is_none_or(|t| p.etiquetas.iter().any(|e| e.eq_ignore_ascii_case(t))))
+        .filter(|p| {
+            query.is_empty()
+                || p.nick.to_lowercase().contains(&query)
+                || p.notas.iter().any(|n| n.texto.to_lowercase().contains(&query))
+        })
+        .collect();
+
+    results.sort_by_key(|p| std::cmp::Reverse(last_update(p)));
+    results
+}
+
+fn last_update(player: &PlayerRecord) -> Option<chrono::DateTime<chrono::Utc>> {
+    player.notas.iter().filter_map(|n| parse_timestamp(&n.actualizado)).max()
+}
+
+// Texto con las etiquetas y notas de un jugador para añadir al resultado del análisis
+pub fn format_for_output(player: &PlayerRecord) -> String {
+    let mut output = String::from("Notas");
+    if !player.etiquetas.is_empty() {
+        output.push_str(&format!(" [{}]", player.etiquetas.join(", ")));
+    }
+
+    for note in &player.notas {
+        // Solo la fecha (AAAA-MM-DD) para no alargar el texto
+        let fecha = note.actualizado.get(..10).unwrap_or(&note.actualizado);
+        output.push_str(&format!("\n- {} {}", fecha, note.texto));
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_with_players() -> NotesStore {
+        let mut store = NotesStore::default();
+        add_note_in(&mut store, "PokerStars", "Villano1", "Sobrefolda al 3-bet");
+        set_tags_in(&mut store, "PokerStars", "Villano1", vec!["fish".to_string()]).unwrap();
+        add_note_in(&mut store, "GGPoker", "Regular", "Paga demasiado en el river");
+        set_tags_in(&mut store, "GGPoker", "Regular", vec!["REG".to_string(), "nit".to_string(), "reg".to_string()]).unwrap();
+        set_tags_in(&mut store, "GGPoker", "SoloEtiqueta", vec!["maniac".to_string()]).unwrap();
+        store
+    }
+
+    fn nicks(results: &[PlayerRecord]) -> Vec<&str> {
+        let mut nicks: Vec<&str> = results.iter().map(|p| p.nick.as_str()).collect();
+        nicks.sort();
+        nicks
+    }
+
+    #[test]
+    fn search_matches_nick_or_note_text_ignoring_case() {
+        let store = store_with_players();
+        assert_eq!(nicks(&search_in(&store, "villano", None, None)), ["Villano1"]);
+        assert_eq!(nicks(&search_in(&store, "RIVER", None, None)), ["Regular"]);
+        assert_eq!(nicks(&search_in(&store, "", None, None)), ["Regular", "SoloEtiqueta", "Villano1"]);
+        assert!(search_in(&store, "nadie", None, None).is_empty());
+    }
+
+    #[test]
+    fn search_filters_by_site_and_tag() {
+        let store = store_with_players();
+        assert_eq!(nicks(&search_in(&store, "", Some("ggpoker"), None)), ["Regular", "SoloEtiqueta"]);
+        assert_eq!(nicks(&search_in(&store, "", None, Some("Reg"))), ["Regular"]);
+        assert_eq!(nicks(&search_in(&store, "", Some("PokerStars"), Some("reg"))), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn tags_are_normalized_and_unknown_tags_rejected() {
+        let mut store = store_with_players();
+        let player = &store.jugadores[&player_key("ggpoker", "regular")];
+        assert_eq!(player.etiquetas, ["reg", "nit"]);
+        assert!(player.etiquetas_pendiente);
+
+        assert!(set_tags_in(&mut store, "GGPoker", "Regular", vec!["inventada".to_string()]).is_err());
+
+        // Sin notas ni etiquetas (y nunca sincronizado) el jugador desaparece
+        assert!(set_tags_in(&mut store, "GGPoker", "SoloEtiqueta", Vec::new()).unwrap().is_none());
+        assert!(!store.jugadores.contains_key(&player_key("GGPoker", "SoloEtiqueta")));
+    }
+
+    #[test]
+    fn deleting_a_tag_removes_it_from_players() {
+        let mut store = store_with_players();
+        delete_tag_in(&mut store, "MANIAC");
+        assert!(!store.etiquetas.iter().any(|t| t.nombre == "maniac"));
+        assert!(!store.jugadores.contains_key(&player_key("GGPoker", "SoloEtiqueta")));
+
+        delete_tag_in(&mut store, "reg");
+        let player = &store.jugadores[&player_key("GGPoker", "Regular")];
+        assert_eq!(player.etiquetas, ["nit"]);
+        assert_eq!(search_in(&store, "", None, Some("reg")).len(), 0);
+    }
+}
@@ -0,0 +1,530 @@
+// src-tauri/src/notes_import.rs
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::notes::{self, NotesStore, PlayerNote, TagDef};
+use crate::profiles;
+
+// Nombres de campo (en minúsculas) que usan las exportaciones de HM3 y PT4
+const PLAYER_FIELDS: &[&str] = &["playername", "player_name", "player name", "player", "screenname", "nick", "name"];
+const SITE_FIELDS: &[&str] = &["site", "sitename", "site_name", "site name", "siteid", "site_id", "room"];
+const TEXT_FIELDS: &[&str] = &["text", "notetext", "note_text", "note", "notes"];
+const LABEL_FIELDS: &[&str] = &["label", "tag", "notelabel", "label name", "label_name"];
+const COLOR_FIELDS: &[&str] = &["color", "colour", "labelcolor", "label_color", "label color"];
+
+// Formato del archivo exportado
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+    // HoldemManager 3: XML
+    Hm3Xml,
+    // PokerTracker 4: CSV
+    Pt4Csv,
+}
+
+// Qué hacer cuando el jugador ya tiene notas
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateMode {
+    // No añadir notas con el mismo texto que una existente
+    #[default]
+    Omitir,
+    // Añadir siempre
+    Anadir,
+    // Sustituir las notas del jugador por las importadas
+    Reemplazar,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ImportOptions {
+    // Si no se indica se deduce de la extensión del archivo
+    pub formato: Option<ImportFormat>,
+    // Sala para las entradas sin sala o con una sala no reconocida
+    pub sala_por_defecto: Option<String>,
+    // Equivalencias de sala del archivo (nombre o ID numérico) a código de sala
+    pub mapa_salas: HashMap<String, String>,
+    pub duplicados: DuplicateMode,
+    // Solo genera el informe, sin modificar las notas
+    pub simulacion: bool,
+}
+
+// Entrada leída del archivo
+#[derive(Debug, Clone)]
+struct RawEntry {
+    // Número de registro en el archivo (1 = primero)
+    registro: usize,
+    nick: String,
+    sitio: String,
+    texto: String,
+    etiqueta: Option<String>,
+    // Color de la etiqueta en formato #rrggbb
+    color: Option<String>,
+}
+
+// Entrada que no se pudo asociar a una sala conocida
+#[derive(Debug, Serialize, Clone)]
+pub struct UnmatchedEntry {
+    pub registro: usize,
+    pub nick: String,
+    pub sitio: String,
+}
+
+// Informe de la importación (o de la simulación)
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ImportReport {
+    pub simulacion: bool,
+    pub total: usize,
+    pub asociadas: usize,
+    pub importadas: usize,
+    // Notas repetidas en el archivo o ya existentes
+    pub duplicadas: usize,
+    // Entradas sin nick o sin texto
+    pub vacias: usize,
+    pub jugadores_nuevos: usize,
+    pub sin_sala: Vec<UnmatchedEntry>,
+    // Valores de sala del archivo que no se reconocieron (para completar mapa_salas)
+    pub salas_desconocidas: Vec<String>,
+    pub etiquetas_nuevas: Vec<String>,
+}
+
+fn detect_format(path: &str) -> Result<ImportFormat, String> {
+    let extension = Path::new(path).extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "xml" => Ok(ImportFormat::Hm3Xml),
+        "csv" | "txt" => Ok(ImportFormat::Pt4Csv),
+        _ => Err(format!("No se reconoce el formato del archivo '{}': indica HM3 (XML) o PT4 (CSV)", path)),
+    }
+}
+
+fn matches_field(name: &str, fields: &[&str]) -> bool {
+    let name = name.trim().to_lowercase();
+    fields.contains(&name.as_str())
+}
+
+// Colores con nombre que usan las etiquetas de PT4
+const NAMED_COLORS: &[(&str, &str)] = &[
+    ("red", "#e53e3e"),
+    ("orange", "#dd6b20"),
+    ("yellow", "#d69e2e"),
+    ("green", "#38a169"),
+    ("blue", "#3182ce"),
+    ("purple", "#805ad5"),
+    ("pink", "#d53f8c"),
+    ("black", "#000000"),
+    ("white", "#ffffff"),
+    ("gray", "#a0aec0"),
+    ("grey", "#a0aec0"),
+];
+
+// Color de la exportación en formato #rrggbb: hexadecimal (con o sin alfa),
+// entero ARGB como los guarda HM3 o nombre en inglés
+fn parse_color(value: &str) -> Option<String> {
+    let value = value.trim();
+    let hex = value.trim_start_matches('#');
+    if (hex.len() == 6 || hex.len() == 8) && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Some(format!("#{}", hex[hex.len() - 6..].to_lowercase()));
+    }
+    if let Ok(argb) = value.parse::<i64>() {
+        return Some(format!("#{:06x}", argb & 0xff_ffff));
+    }
+    NAMED_COLORS.iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(value))
+        .map(|(_, color)| color.to_string())
+}
+
+// Lee las notas de una exportación XML de HM3.
+// Cada nota es un elemento con el jugador, la sala y el texto como atributos o hijos.
+fn parse_hm3_xml(content: &str) -> Result<Vec<RawEntry>, String> {
+    let document = roxmltree::Document::parse(content)
+        .map_err(|e| format!("Error al leer XML de HM3: {}", e))?;
+
+    let mut entries = Vec::new();
+    for node in document.descendants().filter(|n| n.is_element()) {
+        let mut fields: Vec<(&str, String)> = node.attributes()
+            .map(|attribute| (attribute.name(), attribute.value().to_string()))
+            .collect();
+        for child in node.children().filter(|c| c.is_element()) {
+            // Solo hijos de texto simple (no otras notas anidadas)
+            if child.children().all(|c| c.is_text()) {
+                fields.push((child.tag_name().name(), child.text().unwrap_or("").to_string()));
+            }
+        }
+
+        let find = |names: &[&str]| fields.iter()
+            .find(|(key, _)| matches_field(key, names))
+            .map(|(_, value)| value.trim().to_string());
+
+        let Some(nick) = find(PLAYER_FIELDS) else { continue };
+        // Un jugador puede tener varias notas como hijos; sin texto no es una nota
+        let texts: Vec<String> = fields.iter()
+            .filter(|(key, _)| matches_field(key, TEXT_FIELDS))
+            .map(|(_, value)| value.trim().to_string())
+            .collect();
+        let sitio = find(SITE_FIELDS).unwrap_or_default();
+        let etiqueta = find(LABEL_FIELDS).filter(|l| !l.is_empty());
+        let color = find(COLOR_FIELDS).and_then(|c| parse_color(&c));
+
+        for texto in texts {
+            entries.push(RawEntry {
+                registro: entries.len() + 1,
+                nick: nick.clone(),
+                sitio: sitio.clone(),
+                texto,
+                etiqueta: etiqueta.clone(),
+                color: color.clone(),
+            });
+        }
+    }
+
+    Ok(entries)
+}
+
+// Lee las notas de una exportación CSV de PT4 (con cabecera)
+fn parse_pt4_csv(content: &str) -> Result<Vec<RawEntry>, String> {
+    // PT4 exporta con ';' en algunas configuraciones regionales
+    let first_line = content.lines().next().unwrap_or("");
+    let delimiter = if first_line.matches(';').count() > first_line.matches(',').count() { b';' } else { b',' };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(content.trim_start_matches('\u{feff}').as_bytes());
+
+    let headers = reader.headers()
+        .map_err(|e| format!("Error al leer cabecera CSV de PT4: {}", e))?
+        .clone();
+    let column = |names: &[&str]| headers.iter().position(|h| matches_field(h, names));
+
+    let player_column = column(PLAYER_FIELDS)
+        .ok_or("El CSV no tiene columna de jugador (Player)")?;
+    let text_column = column(TEXT_FIELDS)
+        .ok_or("El CSV no tiene columna de nota (Note)")?;
+    let site_column = column(SITE_FIELDS);
+    let label_column = column(LABEL_FIELDS);
+    let color_column = column(COLOR_FIELDS);
+
+    let mut entries = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| format!("Error en la fila {} del CSV: {}", i + 2, e))?;
+        let get = |index: Option<usize>| index.and_then(|i| record.get(i)).unwrap_or("").trim().to_string();
+
+        entries.push(RawEntry {
+            registro: i + 1,
+            nick: get(Some(player_column)),
+            sitio: get(site_column),
+            texto: get(Some(text_column)),
+            etiqueta: Some(get(label_column)).filter(|l| !l.is_empty()),
+            color: parse_color(&get(color_column)),
+        });
+    }
+
+    Ok(entries)
+}
+
+// Código de sala para el valor del archivo: primero el mapa del usuario, después el nombre
+fn map_site(sitio: &str, options: &ImportOptions) -> Option<String> {
+    let sitio = sitio.trim();
+    if let Some(mapped) = options.mapa_salas.iter().find(|(k, _)| k.trim().eq_ignore_ascii_case(sitio)) {
+        return Some(mapped.1.clone());
+    }
+    if let Some(code) = profiles::known_sites().into_iter().find(|code| code.eq_ignore_ascii_case(sitio)) {
+        return Some(code.to_string());
+    }
+    if let Some(code) = profiles::detect_site(sitio) {
+        return Some(code.to_string());
+    }
+    options.sala_por_defecto.clone().filter(|s| !s.trim().is_empty())
+}
+
+// Importa (o simula importar) un archivo de notas de HM3 o PT4
+pub fn import_notes(path: &str, options: ImportOptions) -> Result<ImportReport, String> {
+    let formato = match options.formato {
+        Some(formato) => formato,
+        None => detect_format(path)?,
+    };
+
+    let bytes = fs::read(path).map_err(|e| format!("Error al leer archivo de notas: {}", e))?;
+    let content = String::from_utf8_lossy(&bytes);
+    let entries = match formato {
+        ImportFormat::Hm3Xml => parse_hm3_xml(&content)?,
+        ImportFormat::Pt4Csv => parse_pt4_csv(&content)?,
+    };
+
+    // La simulación trabaja sobre una copia del almacén
+    if options.simulacion {
+        notes::with_store(|store| import_into(&mut store.clone(), entries, &options))
+    } else {
+        notes::update_store(|store| Ok(import_into(store, entries, &options)))
+    }
+}
+
+// Añade las entradas al almacén y genera el informe
+fn import_into(store: &mut NotesStore, entries: Vec<RawEntry>, options: &ImportOptions) -> ImportReport {
+    let mut report = ImportReport { simulacion: options.simulacion, total: entries.len(), ..Default::default() };
+    let mut unknown_sites = BTreeSet::new();
+
+    // Entradas asociadas a una sala, agrupadas por jugador
+    let mut by_player: Vec<(String, String, Vec<RawEntry>)> = Vec::new();
+    let mut player_index: HashMap<String, usize> = HashMap::new();
+    let mut seen = HashSet::new();
+
+    for entry in entries {
+        if entry.nick.is_empty() || entry.texto.is_empty() {
+            report.vacias += 1;
+            continue;
+        }
+        let Some(sala) = map_site(&entry.sitio, options) else {
+            if !entry.sitio.is_empty() {
+                unknown_sites.insert(entry.sitio.clone());
+            }
+            report.sin_sala.push(UnmatchedEntry { registro: entry.registro, nick: entry.nick, sitio: entry.sitio });
+            continue;
+        };
+        report.asociadas += 1;
+
+        let key = notes::player_key(&sala, &entry.nick);
+        // Notas repetidas dentro del propio archivo
+        if !seen.insert((key.clone(), entry.texto.to_lowercase())) {
+            report.duplicadas += 1;
+            continue;
+        }
+
+        let index = *player_index.entry(key).or_insert_with(|| {
+            by_player.push((sala.clone(), entry.nick.clone(), Vec::new()));
+            by_player.len() - 1
+        });
+        by_player[index].2.push(entry);
+    }
+    report.salas_desconocidas = unknown_sites.into_iter().collect();
+
+    let autor = crate::auth::current_user_name();
+    for (sala, nick, entries) in &by_player {
+        let key = notes::player_key(sala, nick);
+        // Un jugador que solo tenía etiquetas ya existe
+        if !store.jugadores.get(&key).is_some_and(|p| p.has_visible_content()) {
+            report.jugadores_nuevos += 1;
+        }
+        let existing: Vec<String> = store.jugadores.get(&key)
+            .map(|p| p.notas.iter().filter(|n| !n.eliminado).map(|n| n.texto.to_lowercase()).collect())
+            .unwrap_or_default();
+
+        let mut labels: Vec<(&str, Option<&str>)> = Vec::new();
+        let mut to_add = Vec::new();
+        for entry in entries {
+            if let Some(label) = &entry.etiqueta {
+                labels.push((label, entry.color.as_deref()));
+            }
+            if options.duplicados == DuplicateMode::Omitir && existing.contains(&entry.texto.to_lowercase()) {
+                report.duplicadas += 1;
+                continue;
+            }
+            to_add.push(entry);
+        }
+        report.importadas += to_add.len();
+
+        // Las etiquetas nuevas se crean con el color del archivo; las existentes lo conservan
+        let mut tag_names: Vec<String> = Vec::new();
+        for (label, color) in labels {
+            let nombre = match store.etiquetas.iter().find(|t| t.nombre.eq_ignore_ascii_case(label)) {
+                Some(known) => known.nombre.clone(),
+                None => {
+                    report.etiquetas_nuevas.push(label.to_string());
+                    let color = color.unwrap_or(notes::NEW_TAG_COLOR);
+                    store.etiquetas.push(TagDef { nombre: label.to_string(), color: color.to_string() });
+                    label.to_string()
+                },
+            };
+            if !tag_names.contains(&nombre) {
+                tag_names.push(nombre);
+            }
+        }
+
+        let player = notes::player_mut(store, sala, nick);
+        if options.duplicados == DuplicateMode::Reemplazar {
+            // Las notas ya sincronizadas se marcan como eliminadas para borrarlas en el servidor
+            player.notas.retain(|n| n.version > 0);
+            for note in player.notas.iter_mut() {
+                note.eliminado = true;
+                note.pendiente = true;
+            }
+        }
+
+        let timestamp = notes::now();
+        for entry in to_add {
+            player.notas.push(PlayerNote {
+                id: notes::new_note_id(),
+                texto: entry.texto.clone(),
+                creado: timestamp.clone(),
+                actualizado: timestamp.clone(),
+                autor: autor.clone(),
+                version: 0,
+                pendiente: true,
+                eliminado: false,
+            });
+        }
+
+        let mut changed_tags = false;
+        for nombre in tag_names {
+            if !player.etiquetas.iter().any(|t| t.eq_ignore_ascii_case(&nombre)) {
+                player.etiquetas.push(nombre);
+                changed_tags = true;
+            }
+        }
+        if changed_tags {
+            player.etiquetas_actualizado = timestamp;
+            player.etiquetas_pendiente = true;
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HM3_XML: &str = include_str!("../tests/fixtures/notes/hm3_notes.xml");
+    const PT4_CSV: &str = include_str!("../tests/fixtures/notes/pt4_notes.csv");
+
+    fn hm3_options() -> ImportOptions {
+        ImportOptions {
+            mapa_salas: HashMap::from([("2".to_string(), "PokerStars".to_string())]),
+            ..Default::default()
+        }
+    }
+
+    fn texts(store: &NotesStore, sala: &str, nick: &str) -> Vec<String> {
+        store.jugadores[&notes::player_key(sala, nick)].notas.iter()
+            .filter(|n| !n.eliminado)
+            .map(|n| n.texto.clone())
+            .collect()
+    }
+
+    // Jugador con una nota ya sincronizada y otro que solo tiene etiquetas
+    fn existing_store() -> NotesStore {
+        let mut store = NotesStore::default();
+        notes::player_mut(&mut store, "PokerStars", "villano1").notas.push(PlayerNote {
+            id: "n1".to_string(),
+            texto: "Sobrefolda al 3-bet".to_string(),
+            creado: "2024-05-01T08:00:00+00:00".to_string(),
+            actualizado: "2024-05-01T08:00:00+00:00".to_string(),
+            autor: None,
+            version: 3,
+            pendiente: false,
+            eliminado: false,
+        });
+        notes::player_mut(&mut store, "GGPoker", "Regular").etiquetas = vec!["reg".to_string()];
+        store
+    }
+
+    #[test]
+    fn hm3_export_is_matched_by_site_map() {
+        let entries = parse_hm3_xml(HM3_XML).unwrap();
+        let mut store = NotesStore::default();
+        let report = import_into(&mut store, entries, &hm3_options());
+
+        assert_eq!(report.total, 6);
+        assert_eq!(report.vacias, 1);
+        assert_eq!(report.asociadas, 4);
+        assert_eq!(report.duplicadas, 1);
+        assert_eq!(report.importadas, 3);
+        assert_eq!(report.jugadores_nuevos, 2);
+        assert_eq!(report.salas_desconocidas, ["99"]);
+        assert_eq!(report.sin_sala.len(), 1);
+        assert_eq!(report.sin_sala[0].nick, "Desconocido");
+
+        assert_eq!(texts(&store, "PokerStars", "Villano1"), ["Sobrefolda al 3-bet"]);
+        assert_eq!(texts(&store, "GGPoker", "Regular"), ["Paga demasiado en el river", "Limpea con ases"]);
+
+        // El color va a la etiqueta nueva, no se importa como etiqueta
+        assert_eq!(report.etiquetas_nuevas, ["Calling station"]);
+        let tag = store.etiquetas.iter().find(|t| t.nombre == "Calling station").unwrap();
+        assert_eq!(tag.color, "#ff0000");
+        let player = &store.jugadores[&notes::player_key("PokerStars", "Villano1")];
+        assert_eq!(player.etiquetas, ["Calling station"]);
+        assert!(player.etiquetas_pendiente);
+    }
+
+    #[test]
+    fn pt4_export_uses_default_site_for_unknown_rooms() {
+        let report = import_into(&mut NotesStore::default(), parse_pt4_csv(PT4_CSV).unwrap(), &ImportOptions::default());
+        assert_eq!(report.total, 5);
+        assert_eq!(report.vacias, 1);
+        assert_eq!(report.asociadas, 2);
+        assert_eq!(report.salas_desconocidas, ["Bodog"]);
+        let unmatched: Vec<&str> = report.sin_sala.iter().map(|e| e.nick.as_str()).collect();
+        assert_eq!(unmatched, ["Regular", "Otro"]);
+
+        let options = ImportOptions { sala_por_defecto: Some("GGPoker".to_string()), ..Default::default() };
+        let mut store = NotesStore::default();
+        let report = import_into(&mut store, parse_pt4_csv(PT4_CSV).unwrap(), &options);
+        assert_eq!(report.asociadas, 4);
+        assert!(report.sin_sala.is_empty());
+        assert_eq!(texts(&store, "GGPoker", "Otro"), ["Sala desconocida"]);
+
+        // Las etiquetas existentes conservan su color
+        assert_eq!(report.etiquetas_nuevas, ["Shark"]);
+        let color = |nombre: &str| store.etiquetas.iter().find(|t| t.nombre == nombre).unwrap().color.clone();
+        assert_eq!(color("Shark"), "#38a169");
+        assert_eq!(color("fish"), "#38a169");
+        assert_eq!(store.jugadores[&notes::player_key("PokerStars", "Villano1")].etiquetas, ["fish"]);
+    }
+
+    #[test]
+    fn colors_are_normalized() {
+        assert_eq!(parse_color("-16776961").as_deref(), Some("#0000ff"));
+        assert_eq!(parse_color("#FF00FF00").as_deref(), Some("#00ff00"));
+        assert_eq!(parse_color("38A169").as_deref(), Some("#38a169"));
+        assert_eq!(parse_color("Purple").as_deref(), Some("#805ad5"));
+        assert_eq!(parse_color("fucsia"), None);
+    }
+
+    #[test]
+    fn dry_run_reports_without_changing_store() {
+        let options = ImportOptions { sala_por_defecto: Some("GGPoker".to_string()), ..Default::default() };
+        let store = existing_store();
+        let before = serde_json::to_string(&store).unwrap();
+
+        let simulated = import_into(&mut store.clone(), parse_pt4_csv(PT4_CSV).unwrap(), &ImportOptions { simulacion: true, ..options.clone() });
+        assert!(simulated.simulacion);
+        assert_eq!(serde_json::to_string(&store).unwrap(), before);
+
+        let mut imported_store = store.clone();
+        let imported = import_into(&mut imported_store, parse_pt4_csv(PT4_CSV).unwrap(), &options);
+        assert_eq!(
+            serde_json::to_value(ImportReport { simulacion: false, ..simulated }).unwrap(),
+            serde_json::to_value(&imported).unwrap(),
+        );
+        // El jugador que solo tenía etiquetas no cuenta como nuevo
+        assert_eq!(imported.jugadores_nuevos, 1);
+    }
+
+    #[test]
+    fn duplicate_modes() {
+        let options = |duplicados| ImportOptions { duplicados, ..Default::default() };
+        let entries = || parse_pt4_csv(PT4_CSV).unwrap();
+
+        let mut store = existing_store();
+        let report = import_into(&mut store, entries(), &options(DuplicateMode::Omitir));
+        assert_eq!(report.duplicadas, 1);
+        assert_eq!(report.importadas, 1);
+        assert_eq!(texts(&store, "PokerStars", "Villano1"), ["Sobrefolda al 3-bet", "Nunca farolea en el turn"]);
+
+        let mut store = existing_store();
+        let report = import_into(&mut store, entries(), &options(DuplicateMode::Anadir));
+        assert_eq!(report.duplicadas, 0);
+        assert_eq!(texts(&store, "PokerStars", "Villano1").len(), 3);
+
+        // La nota sincronizada queda marcada para borrarla en el servidor
+        let mut store = existing_store();
+        import_into(&mut store, entries(), &options(DuplicateMode::Reemplazar));
+        let player = &store.jugadores[&notes::player_key("PokerStars", "Villano1")];
+        let old = player.notas.iter().find(|n| n.id == "n1").unwrap();
+        assert!(old.eliminado && old.pendiente);
+        assert_eq!(texts(&store, "PokerStars", "Villano1"), ["Sobrefolda al 3-bet", "Nunca farolea en el turn"]);
+    }
+}
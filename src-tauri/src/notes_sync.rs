@@ -0,0 +1,575 @@
+// src-tauri/src/notes_sync.rs
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use once_cell::sync::Lazy;
+use reqwest::{Client, StatusCode};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE, AUTHORIZATION};
+use tauri::Manager;
+use crate::notes::{self, NoteConflict, NotesStore, PlayerNote, TagDef};
+use crate::config_state::ConfigState;
+use crate::secrets::{self, SecretKind};
+use crate::auth;
+
+// Nombre del evento emitido al frontend cuando llegan cambios del equipo
+pub const NOTES_SYNCED_EVENT: &str = "notes_synced";
+
+// Intervalo de sincronización automática
+const SYNC_INTERVAL_SECS: u64 = 120;
+
+// Evita dos sincronizaciones a la vez (automática y manual)
+static SYNC_RUNNING: AtomicBool = AtomicBool::new(false);
+
+// Estado de la última sincronización, para mostrarlo en la interfaz
+static SYNC_STATUS: Lazy<Mutex<SyncStatus>> = Lazy::new(|| Mutex::new(SyncStatus::default()));
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct SyncStatus {
+    pub ultimo_sync: Option<String>,
+    pub ultimo_error: Option<String>,
+    // Cambios locales en cola (se envían cuando el servidor vuelve a responder)
+    pub pendientes: usize,
+    pub conflictos: usize,
+}
+
+// Resultado de una sincronización
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct SyncSummary {
+    pub enviados: usize,
+    pub recibidos: usize,
+    pub conflictos: usize,
+}
+
+// Nota tal como la intercambia el servidor
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RemoteNote {
+    pub id: String,
+    pub sala: String,
+    pub nick: String,
+    pub texto: String,
+    pub creado: String,
+    pub actualizado: String,
+    pub autor: Option<String>,
+    // En los envíos es la versión en la que se basa el cambio; en las respuestas, la nueva versión
+    pub version: u64,
+    #[serde(default)]
+    pub eliminado: bool,
+}
+
+// Etiquetas de un jugador tal como las intercambia el servidor
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RemoteTags {
+    pub sala: String,
+    pub nick: String,
+    pub etiquetas: Vec<String>,
+    pub actualizado: String,
+    pub version: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct SyncRequest {
+    cursor: Option<String>,
+    notas: Vec<RemoteNote>,
+    etiquetas: Vec<RemoteTags>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AcceptedNote {
+    id: String,
+    version: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct AcceptedTags {
+    sala: String,
+    nick: String,
+    version: u64,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct SyncResponse {
+    cursor: Option<String>,
+    // Cambios locales guardados en el servidor con su nueva versión
+    aceptadas: Vec<AcceptedNote>,
+    etiquetas_aceptadas: Vec<AcceptedTags>,
+    // Notas que otro miembro modificó antes: versión actual del servidor
+    rechazadas: Vec<RemoteNote>,
+    // Cambios del equipo desde el cursor enviado
+    notas: Vec<RemoteNote>,
+    etiquetas: Vec<RemoteTags>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    error: String,
+}
+
+fn to_remote(sala: &str, nick: &str, note: &PlayerNote) -> RemoteNote {
+    RemoteNote {
+        id: note.id.clone(),
+        sala: sala.to_string(),
+        nick: nick.to_string(),
+        texto: note.texto.clone(),
+        creado: note.creado.clone(),
+        actualizado: note.actualizado.clone(),
+        autor: note.autor.clone(),
+        version: note.version,
+        eliminado: note.eliminado,
+    }
+}
+
+fn from_remote(remote: &RemoteNote) -> PlayerNote {
+    PlayerNote {
+        id: remote.id.clone(),
+        texto: remote.texto.clone(),
+        creado: remote.creado.clone(),
+        actualizado: remote.actualizado.clone(),
+        autor: remote.autor.clone(),
+        version: remote.version,
+        pendiente: false,
+        eliminado: remote.eliminado,
+    }
+}
+
+// Cambios locales a enviar. Las notas en conflicto esperan a que el usuario decida.
+fn collect_changes(store: &NotesStore) -> SyncRequest {
+    let mut notas = Vec::new();
+    let mut etiquetas = Vec::new();
+
+    for player in store.jugadores.values() {
+        for note in player.notas.iter().filter(|n| n.pendiente) {
+            if !store.conflictos.iter().any(|c| c.id == note.id) {
+                notas.push(to_remote(&player.sala, &player.nick, note));
+            }
+        }
+        if player.etiquetas_pendiente {
+            etiquetas.push(RemoteTags {
+                sala: player.sala.clone(),
+                nick: player.nick.clone(),
+                etiquetas: player.etiquetas.clone(),
+                actualizado: player.etiquetas_actualizado.clone(),
+                version: player.etiquetas_version,
+            });
+        }
+    }
+
+    SyncRequest { cursor: store.cursor_sync.clone(), notas, etiquetas }
+}
+
+fn count_pending(store: &NotesStore) -> usize {
+    store.jugadores.values()
+        .map(|p| p.notas.iter().filter(|n| n.pendiente).count() + usize::from(p.etiquetas_pendiente))
+        .sum()
+}
+
+fn add_conflict(store: &mut NotesStore, local: &PlayerNote, remote: &RemoteNote) {
+    store.conflictos.retain(|c| c.id != remote.id);
+    store.conflictos.push(NoteConflict {
+        id: remote.id.clone(),
+        sala: remote.sala.clone(),
+        nick: remote.nick.clone(),
+        local: local.clone(),
+        remoto: from_remote(remote),
+    });
+}
+
+// true si la fecha `a` es posterior a `b`. Si alguna no se puede interpretar
+// se comparan como texto.
+fn is_later(a: &str, b: &str) -> bool {
+    match (notes::parse_timestamp(a), notes::parse_timestamp(b)) {
+        (Some(a), Some(b)) => a > b,
+        _ => a > b,
+    }
+}
+
+// Ordena las notas por fecha de creación
+fn sort_by_created(notas: &mut [PlayerNote]) {
+    notas.sort_by_key(|n| notes::parse_timestamp(&n.creado));
+}
+
+// Aplica la respuesta del servidor sobre el almacén.
+// `sent` son los cambios enviados: si una nota se editó durante la sincronización
+// sigue pendiente con la versión nueva como base.
+fn apply_response(store: &mut NotesStore, sent: &SyncRequest, response: SyncResponse) -> SyncSummary {
+    let mut summary = SyncSummary { enviados: response.aceptadas.len() + response.etiquetas_aceptadas.len(), ..Default::default() };
+
+    for accepted in &response.aceptadas {
+        let Some(sent_note) = sent.notas.iter().find(|n| n.id == accepted.id) else { continue };
+        let key = notes::player_key(&sent_note.sala, &sent_note.nick);
+        let Some(player) = store.jugadores.get_mut(&key) else { continue };
+        let Some(index) = player.notas.iter().position(|n| n.id == accepted.id) else { continue };
+
+        let note = &mut player.notas[index];
+        note.version = accepted.version;
+        if note.actualizado == sent_note.actualizado {
+            note.pendiente = false;
+            if note.eliminado {
+                player.notas.remove(index);
+            }
+        }
+    }
+
+    for accepted in &response.etiquetas_aceptadas {
+        let sent_tags = sent.etiquetas.iter()
+            .find(|t| notes::player_key(&t.sala, &t.nick) == notes::player_key(&accepted.sala, &accepted.nick));
+        let Some(player) = store.jugadores.get_mut(&notes::player_key(&accepted.sala, &accepted.nick)) else { continue };
+        player.etiquetas_version = accepted.version;
+        if sent_tags.is_some_and(|t| t.actualizado == player.etiquetas_actualizado) {
+            player.etiquetas_pendiente = false;
+        }
+    }
+
+    for remote in &response.rechazadas {
+        let key = notes::player_key(&remote.sala, &remote.nick);
+        let local = store.jugadores.get(&key)
+            .and_then(|p| p.notas.iter().find(|n| n.id == remote.id))
+            .cloned();
+        if let Some(local) = local {
+            add_conflict(store, &local, remote);
+        }
+    }
+
+    for remote in &response.notas {
+        let key = notes::player_key(&remote.sala, &remote.nick);
+        let local = store.jugadores.get(&key)
+            .and_then(|p| p.notas.iter().find(|n| n.id == remote.id))
+            .cloned();
+
+        match local {
+            // Cambio local sin enviar basado en otra versión: el usuario decide
+            Some(local) if local.pendiente && local.version != remote.version => {
+                add_conflict(store, &local, remote);
+            },
+            // Ya teníamos esta versión (p. ej. nuestro propio cambio aceptado)
+            Some(local) if !local.pendiente && local.version >= remote.version => {},
+            _ => {
+                let player = notes::player_mut(store, &remote.sala, &remote.nick);
+                player.notas.retain(|n| n.id != remote.id);
+                if !remote.eliminado {
+                    player.notas.push(from_remote(remote));
+                    sort_by_created(&mut player.notas);
+                }
+                summary.recibidos += 1;
+            },
+        }
+    }
+
+    for remote in &response.etiquetas {
+        for tag in &remote.etiquetas {
+            if !store.etiquetas.iter().any(|t| t.nombre.eq_ignore_ascii_case(tag)) {
+                store.etiquetas.push(TagDef { nombre: tag.clone(), color: notes::NEW_TAG_COLOR.to_string() });
+            }
+        }
+
+        let player = notes::player_mut(store, &remote.sala, &remote.nick);
+        if remote.version <= player.etiquetas_version {
+            continue;
+        }
+        // Las etiquetas se resuelven por último cambio: gana el más reciente
+        if player.etiquetas_pendiente && is_later(&player.etiquetas_actualizado, &remote.actualizado) {
+            player.etiquetas_version = remote.version;
+            continue;
+        }
+        player.etiquetas = remote.etiquetas.clone();
+        player.etiquetas_actualizado = remote.actualizado.clone();
+        player.etiquetas_version = remote.version;
+        player.etiquetas_pendiente = false;
+        summary.recibidos += 1;
+    }
+
+    if response.cursor.is_some() {
+        store.cursor_sync = response.cursor;
+    }
+    store.jugadores.retain(|_, p| !p.is_removable());
+
+    summary.conflictos = store.conflictos.len();
+    summary
+}
+
+async fn post_sync(request: &SyncRequest, token: &str, server_url: &str) -> Result<SyncResponse, String> {
+    let client = Client::new();
+
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    let auth_value = format!("Bearer {}", token);
+    headers.insert(AUTHORIZATION, HeaderValue::from_str(&auth_value).map_err(|e| e.to_string())?);
+
+    let url = format!("{}/api/notas/sync", server_url);
+    let response = client.post(&url)
+        .headers(headers)
+        .json(request)
+        .send()
+        .await
+        .map_err(|e| format!("Error de conexión: {}", e))?;
+
+    let status = response.status();
+    let text = response.text().await.map_err(|e| format!("Error leyendo cuerpo: {}", e))?;
+
+    if status.is_success() {
+        serde_json::from_str::<SyncResponse>(&text)
+            .map_err(|e| format!("Error al decodificar respuesta: {}", e))
+    } else if status == StatusCode::UNAUTHORIZED {
+        Err(auth::SESSION_EXPIRED_ERROR.to_string())
+    } else {
+        match serde_json::from_str::<ApiError>(&text) {
+            Ok(api_error) => Err(api_error.error),
+            Err(_) => Err(format!("Error en la API: {}", status)),
+        }
+    }
+}
+
+fn set_status(result: &Result<SyncSummary, String>) {
+    let (pendientes, conflictos) = notes::with_store(|store| (count_pending(store), store.conflictos.len()))
+        .unwrap_or_default();
+
+    if let Ok(mut status) = SYNC_STATUS.lock() {
+        match result {
+            Ok(_) => {
+                status.ultimo_sync = Some(notes::now());
+                status.ultimo_error = None;
+            },
+            Err(e) => status.ultimo_error = Some(e.clone()),
+        }
+        status.pendientes = pendientes;
+        status.conflictos = conflictos;
+    }
+}
+
+// Envía los cambios locales y recibe los del equipo.
+// Si el servidor no responde los cambios quedan en cola para el siguiente intento.
+pub async fn sync(token: &str, server_url: &str) -> Result<SyncSummary, String> {
+    if token.is_empty() {
+        return Err("Inicia sesión para sincronizar las notas".to_string());
+    }
+    if SYNC_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("Ya hay una sincronización en curso".to_string());
+    }
+
+    let result = async {
+        let request = notes::with_store(collect_changes)?;
+        let response = post_sync(&request, token, server_url).await?;
+        notes::update_store(|store| Ok(apply_response(store, &request, response)))
+    }.await;
+
+    SYNC_RUNNING.store(false, Ordering::SeqCst);
+    set_status(&result);
+    result
+}
+
+// Estado de la sincronización
+pub fn status() -> SyncStatus {
+    let mut status = SYNC_STATUS.lock().map(|s| s.clone()).unwrap_or_default();
+    if let Ok((pendientes, conflictos)) = notes::with_store(|store| (count_pending(store), store.conflictos.len())) {
+        status.pendientes = pendientes;
+        status.conflictos = conflictos;
+    }
+    status
+}
+
+// Conflictos pendientes de resolver
+pub fn list_conflicts() -> Result<Vec<NoteConflict>, String> {
+    notes::with_store(|store| store.conflictos.clone())
+}
+
+// Resuelve un conflicto conservando la nota local (se envía de nuevo) o la del servidor
+pub fn resolve_conflict(id: &str, conservar_local: bool) -> Result<(), String> {
+    notes::update_store(|store| {
+        let index = store.conflictos.iter().position(|c| c.id == id)
+            .ok_or_else(|| format!("Conflicto no encontrado: {}", id))?;
+        let conflict = store.conflictos.remove(index);
+
+        let player = notes::player_mut(store, &conflict.sala, &conflict.nick);
+        player.notas.retain(|n| n.id != conflict.id);
+
+        if conservar_local {
+            // La nota local pasa a basarse en la versión actual del servidor
+            let mut note = conflict.local;
+            note.version = conflict.remoto.version;
+            note.pendiente = true;
+            note.actualizado = notes::now();
+            player.notas.push(note);
+        } else if !conflict.remoto.eliminado {
+            player.notas.push(conflict.remoto);
+        }
+        sort_by_created(&mut player.notas);
+
+        store.jugadores.retain(|_, p| !p.is_removable());
+        Ok(())
+    })
+}
+
+// Inicia la sincronización periódica de notas con el servidor
+pub fn start_notes_sync<R: tauri::Runtime>(app_handle: tauri::AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(SYNC_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+
+            let token = match secrets::get_secret(SecretKind::Token) {
+                Ok(token) if !token.is_empty() => token,
+                _ => continue,
+            };
+
+            let server_url = app_handle.state::<ConfigState>().get().server_url;
+            match sync(&token, &server_url).await {
+                Ok(summary) => {
+                    if summary.recibidos > 0 || summary.conflictos > 0 {
+                        let _ = app_handle.emit_all(NOTES_SYNCED_EVENT, &summary);
+                    }
+                },
+                Err(e) if auth::is_session_expired_error(&e) => auth::handle_session_expired(&app_handle),
+                Err(e) => eprintln!("Error al sincronizar notas: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: &str, texto: &str, version: u64, pendiente: bool) -> PlayerNote {
+        PlayerNote {
+            id: id.to_string(),
+            texto: texto.to_string(),
+            creado: "2024-05-01T08:00:00+00:00".to_string(),
+            actualizado: "2024-05-01T08:00:00+00:00".to_string(),
+            autor: None,
+            version,
+            pendiente,
+            eliminado: false,
+        }
+    }
+
+    fn remote(id: &str, texto: &str, version: u64) -> RemoteNote {
+        to_remote("PokerStars", "Villano", &note(id, texto, version, false))
+    }
+
+    fn store_with(notas: Vec<PlayerNote>) -> NotesStore {
+        let mut store = NotesStore::default();
+        notes::player_mut(&mut store, "PokerStars", "Villano").notas = notas;
+        store
+    }
+
+    fn player(store: &NotesStore) -> &notes::PlayerRecord {
+        &store.jugadores[&notes::player_key("PokerStars", "Villano")]
+    }
+
+    #[test]
+    fn accepted_notes_leave_the_queue_unless_edited_during_sync() {
+        let mut store = store_with(vec![note("a", "Foldea mucho", 0, true), note("b", "Paga", 2, true)]);
+        let sent = collect_changes(&store);
+        assert_eq!(sent.notas.len(), 2);
+
+        // La nota "b" se edita mientras la petición está en curso
+        let edited = "2024-05-01T09:00:00+00:00".to_string();
+        notes::player_mut(&mut store, "PokerStars", "Villano").notas[1].actualizado = edited.clone();
+
+        let response = SyncResponse {
+            aceptadas: vec![AcceptedNote { id: "a".to_string(), version: 1 }, AcceptedNote { id: "b".to_string(), version: 3 }],
+            ..Default::default()
+        };
+        let summary = apply_response(&mut store, &sent, response);
+        assert_eq!(summary.enviados, 2);
+
+        let notas = &player(&store).notas;
+        assert!(!notas[0].pendiente);
+        assert_eq!(notas[0].version, 1);
+        assert!(notas[1].pendiente);
+        assert_eq!(notas[1].version, 3);
+        assert_eq!(notas[1].actualizado, edited);
+        assert_eq!(count_pending(&store), 1);
+    }
+
+    #[test]
+    fn remote_change_on_pending_note_becomes_conflict() {
+        let mut store = store_with(vec![note("a", "Versión local", 1, true), note("b", "Sin cambios", 1, false)]);
+        let response = SyncResponse {
+            notas: vec![remote("a", "Versión del equipo", 2), remote("b", "Actualizada", 2)],
+            ..Default::default()
+        };
+        let summary = apply_response(&mut store, &collect_changes(&NotesStore::default()), response);
+        assert_eq!(summary.recibidos, 1);
+        assert_eq!(summary.conflictos, 1);
+        assert_eq!(store.conflictos[0].id, "a");
+        assert_eq!(store.conflictos[0].remoto.texto, "Versión del equipo");
+
+        let notas = &player(&store).notas;
+        assert_eq!(notas.iter().find(|n| n.id == "a").unwrap().texto, "Versión local");
+        assert_eq!(notas.iter().find(|n| n.id == "b").unwrap().texto, "Actualizada");
+
+        // La nota en conflicto no se vuelve a enviar hasta que el usuario decida
+        assert!(collect_changes(&store).notas.is_empty());
+        assert_eq!(count_pending(&store), 1);
+    }
+
+    #[test]
+    fn tags_last_writer_wins_compares_dates_not_text() {
+        let mut store = store_with(vec![note("a", "Nota", 1, false)]);
+        let local = notes::player_mut(&mut store, "PokerStars", "Villano");
+        local.etiquetas = vec!["fish".to_string()];
+        // 08:30 UTC: como texto parece posterior a las 09:00 UTC del servidor
+        local.etiquetas_actualizado = "2024-05-01T10:30:00+02:00".to_string();
+        local.etiquetas_version = 1;
+        local.etiquetas_pendiente = true;
+
+        let response = SyncResponse {
+            etiquetas: vec![RemoteTags {
+                sala: "PokerStars".to_string(),
+                nick: "Villano".to_string(),
+                etiquetas: vec!["reg".to_string()],
+                actualizado: "2024-05-01T09:00:00Z".to_string(),
+                version: 2,
+            }],
+            ..Default::default()
+        };
+        apply_response(&mut store, &collect_changes(&NotesStore::default()), response);
+
+        let player = player(&store);
+        assert_eq!(player.etiquetas, ["reg"]);
+        assert_eq!(player.etiquetas_version, 2);
+        assert!(!player.etiquetas_pendiente);
+        assert!(store.etiquetas.iter().any(|t| t.nombre == "reg"));
+    }
+
+    #[test]
+    fn offline_queue_keeps_changes_until_server_answers() {
+        let mut deleted = note("c", "Borrada", 4, true);
+        deleted.eliminado = true;
+        let mut store = store_with(vec![note("a", "Nueva", 0, true), note("b", "Sincronizada", 1, false), deleted]);
+        let local = notes::player_mut(&mut store, "PokerStars", "Villano");
+        local.etiquetas = vec!["fish".to_string()];
+        local.etiquetas_actualizado = notes::now();
+        local.etiquetas_pendiente = true;
+        store.cursor_sync = Some("cursor-1".to_string());
+
+        // Sin conexión la cola no cambia y se envía completa en el siguiente intento
+        let sent = collect_changes(&store);
+        assert_eq!(sent.cursor.as_deref(), Some("cursor-1"));
+        let ids: Vec<&str> = sent.notas.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, ["a", "c"]);
+        assert!(sent.notas[1].eliminado);
+        assert_eq!(sent.etiquetas.len(), 1);
+        assert_eq!(count_pending(&store), 3);
+
+        let response = SyncResponse {
+            cursor: Some("cursor-2".to_string()),
+            aceptadas: vec![AcceptedNote { id: "c".to_string(), version: 5 }],
+            etiquetas_aceptadas: vec![AcceptedTags { sala: "pokerstars".to_string(), nick: "villano".to_string(), version: 1 }],
+            rechazadas: vec![remote("a", "Otra versión", 1)],
+            ..Default::default()
+        };
+        let summary = apply_response(&mut store, &sent, response);
+        assert_eq!(summary.enviados, 2);
+        assert_eq!(summary.conflictos, 1);
+        assert_eq!(store.cursor_sync.as_deref(), Some("cursor-2"));
+
+        // La nota borrada desaparece al confirmarse y la rechazada queda en conflicto
+        let ids: Vec<&str> = player(&store).notas.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b"]);
+        assert!(!player(&store).etiquetas_pendiente);
+        assert_eq!(store.conflictos[0].id, "a");
+        assert_eq!(count_pending(&store), 1);
+    }
+}
@@ -0,0 +1,185 @@
+// src-tauri/src/profiles.rs
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::settings::{AppConfig, OcrCoords};
+
+// Nombre del perfil global (los campos de nivel superior de AppConfig)
+pub const GLOBAL_PROFILE: &str = "Global";
+
+// Salas reconocidas en el título de la ventana: (código, patrones en minúsculas)
+const KNOWN_SITES: &[(&str, &[&str])] = &[
+    ("XPK", &["x-poker", "xpoker", "xpk"]),
+    ("GGPoker", &["ggpoker", "natural8", "rush & cash", "rush and cash"]),
+    ("PokerStars", &["pokerstars", "zoom"]),
+    ("888poker", &["888poker", "888 poker"]),
+    ("PartyPoker", &["partypoker", "party poker"]),
+    ("Winamax", &["winamax"]),
+    ("iPoker", &["ipoker"]),
+];
+
+// Perfil de configuración para una o varias salas.
+// Los campos vacíos (None) usan el valor del perfil global.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SiteProfile {
+    pub nombre: String,
+    // Códigos de sala (ver KNOWN_SITES) a los que se aplica el perfil
+    pub sitios: Vec<String>,
+    // Código de sala que se envía al servidor de estadísticas
+    pub sala: Option<String>,
+    pub ocr_coords: Option<OcrCoords>,
+    pub stats_seleccionadas: Option<HashMap<String, bool>>,
+    pub stats_order: Option<Vec<String>>,
+    pub stats_format: Option<HashMap<String, String>>,
+    pub plantilla_analisis: Option<String>,
+}
+
+// Códigos de las salas reconocidas
+pub fn known_sites() -> Vec<&'static str> {
+    KNOWN_SITES.iter().map(|(code, _)| *code).collect()
+}
+
+// Detecta la sala a partir del título de la ventana de la mesa
+pub fn detect_site(title: &str) -> Option<&'static str> {
+    let title_lower = title.to_lowercase();
+    KNOWN_SITES.iter()
+        .find(|(_, patterns)| patterns.iter().any(|pattern| title_lower.contains(pattern)))
+        .map(|(code, _)| *code)
+}
+
+// Busca el perfil que corresponde a una sala
+pub fn find_profile<'a>(config: &'a AppConfig, site: &str) -> Option<&'a SiteProfile> {
+    config.perfiles.iter()
+        .find(|profile| profile.sitios.iter().any(|s| s.eq_ignore_ascii_case(site)))
+}
+
+// Código de sala (ver KNOWN_SITES) con el que se guardan las manos y las notas.
+// `sala` es el código que se envía al servidor ("XPK", "PS"...): se usa la sala
+// detectada en el título de la mesa y, si no hay, la del perfil que envía ese código.
+pub fn local_site(config: &AppConfig, sala: &str, title: Option<&str>) -> String {
+    let sala = sala.trim();
+    title.and_then(detect_site)
+        .or_else(|| detect_site(sala))
+        .or_else(|| {
+            config.perfiles.iter()
+                .filter(|profile| profile.sala.as_deref().is_some_and(|s| s.trim().eq_ignore_ascii_case(sala)))
+                .flat_map(|profile| profile.sitios.iter())
+                .find_map(|site| KNOWN_SITES.iter().find(|(code, _)| code.eq_ignore_ascii_case(site)))
+                .map(|(code, _)| *code)
+        })
+        .map_or_else(|| sala.to_string(), str::to_string)
+}
+
+// Devuelve la configuración efectiva para una mesa: el perfil de su sala
+// aplicado sobre el perfil global, y el nombre del perfil usado.
+pub fn resolve_for_title(config: &AppConfig, title: &str) -> (AppConfig, String) {
+    let profile = detect_site(title).and_then(|site| find_profile(config, site));
+
+    match profile {
+        Some(profile) => (apply_profile(config, profile), profile.nombre.clone()),
+        None => (config.clone(), GLOBAL_PROFILE.to_string()),
+    }
+}
+
+// Aplica los campos definidos en un perfil sobre la configuración global
+pub fn apply_profile(config: &AppConfig, profile: &SiteProfile) -> AppConfig {
+    let mut effective = config.clone();
+
+    if let Some(sala) = &profile.sala {
+        effective.sala_default = sala.clone();
+    }
+    if let Some(ocr_coords) = &profile.ocr_coords {
+        effective.ocr_coords = ocr_coords.clone();
+    }
+    if let Some(stats_seleccionadas) = &profile.stats_seleccionadas {
+        effective.stats_seleccionadas = stats_seleccionadas.clone();
+    }
+    if let Some(stats_order) = &profile.stats_order {
+        effective.stats_order = stats_order.clone();
+    }
+    if let Some(stats_format) = &profile.stats_format {
+        effective.stats_format = stats_format.clone();
+    }
+    if let Some(plantilla) = &profile.plantilla_analisis {
+        effective.plantilla_analisis = Some(plantilla.clone());
+    }
+
+    effective
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_profiles() -> AppConfig {
+        AppConfig {
+            sala_default: "XPK".to_string(),
+            perfiles: vec![
+                SiteProfile {
+                    nombre: "Stars".to_string(),
+                    sitios: vec!["pokerstars".to_string()],
+                    sala: Some("PS".to_string()),
+                    ocr_coords: Some(OcrCoords { x: 10, y: 20, w: 120, h: 30 }),
+                    ..Default::default()
+                },
+                SiteProfile {
+                    nombre: "GG".to_string(),
+                    sitios: vec!["GGPoker".to_string()],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn detects_the_site_from_the_window_title() {
+        assert_eq!(detect_site("Halley II - No Limit Hold'em $0.05/$0.10 - PokerStars"), Some("PokerStars"));
+        assert_eq!(detect_site("Zoom Halley - 6-max"), Some("PokerStars"));
+        assert_eq!(detect_site("NLH 0.02/0.05 | Rush & Cash"), Some("GGPoker"));
+        assert_eq!(detect_site("WINAMAX Paris 05"), Some("Winamax"));
+        assert_eq!(detect_site("X-Poker Mesa 3"), Some("XPK"));
+        assert_eq!(detect_site("Bloc de notas"), None);
+    }
+
+    #[test]
+    fn applies_the_profile_of_the_detected_site() {
+        let config = config_with_profiles();
+        let (effective, nombre) = resolve_for_title(&config, "Halley - PokerStars");
+        assert_eq!(nombre, "Stars");
+        assert_eq!(effective.sala_default, "PS");
+        assert_eq!(effective.ocr_coords.w, 120);
+        // Lo que el perfil no define se hereda del global
+        assert_eq!(effective.stats_order, config.stats_order);
+    }
+
+    #[test]
+    fn falls_back_to_the_global_profile() {
+        let config = config_with_profiles();
+
+        // Sala reconocida pero sin perfil
+        let (effective, nombre) = resolve_for_title(&config, "Winamax Paris 05");
+        assert_eq!(nombre, GLOBAL_PROFILE);
+        assert_eq!(effective.sala_default, "XPK");
+
+        // Título sin sala reconocida
+        let (_, nombre) = resolve_for_title(&config, "Mesa 1");
+        assert_eq!(nombre, GLOBAL_PROFILE);
+
+        // Perfil sin campos propios: se usa pero todo sale del global
+        let (effective, nombre) = resolve_for_title(&config, "NLH 0.02/0.05 | GGPoker");
+        assert_eq!(nombre, "GG");
+        assert_eq!(effective.sala_default, "XPK");
+        assert_eq!(effective.ocr_coords.x, config.ocr_coords.x);
+    }
+
+    #[test]
+    fn maps_server_site_codes_to_local_sites() {
+        let config = config_with_profiles();
+        assert_eq!(local_site(&config, "XPK", Some("NLH 0.02/0.05 | Rush & Cash")), "GGPoker");
+        // Sin título: el perfil que envía "PS" es el de PokerStars
+        assert_eq!(local_site(&config, "ps", None), "PokerStars");
+        assert_eq!(local_site(&config, "winamax", Some("Mesa 1")), "Winamax");
+        assert_eq!(local_site(&config, " Otra ", None), "Otra");
+    }
+}
@@ -0,0 +1,336 @@
+// src-tauri/src/replayer.rs
+// Representación de una mano guardada para el reproductor: estados calle a calle
+// con stacks, bote, apuestas, board y cartas visibles, y listado de las manos de
+// un jugador para revisar las que respaldan una nota
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use crate::hand_db;
+use crate::hand_history::{ActionKind, Card, Chips, Collection, Hand, Position, Street, Variant};
+
+// Manos que se devuelven como mucho en un listado
+const DEFAULT_HAND_LIMIT: usize = 200;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ReplayerSeat {
+    pub asiento: u8,
+    pub jugador: String,
+    // Stack al empezar la mano
+    pub stack: Chips,
+    pub posicion: Option<Position>,
+    pub hero: bool,
+    // Resultado neto de la mano
+    pub neto: Chips,
+}
+
+// Situación de la mesa tras una acción, al empezar una calle o en el showdown.
+// Los vectores siguen el orden de `HandReplay::asientos`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ReplayerState {
+    pub calle: Street,
+    // Acción que lleva a este estado (None al empezar una calle y en el showdown)
+    pub jugador: Option<String>,
+    pub accion: Option<ActionKind>,
+    pub importe: Chips,
+    pub all_in: bool,
+    pub board: Vec<Card>,
+    // Fichas ya recogidas en el centro (calles anteriores y antes)
+    pub bote: Chips,
+    // Apuestas de la calle actual, todavía delante de cada jugador
+    pub apuestas: Vec<Chips>,
+    pub stacks: Vec<Chips>,
+    pub retirados: Vec<bool>,
+    // Cartas conocidas en este momento: las del hero y las mostradas en el showdown
+    pub cartas: Vec<Vec<Card>>,
+    pub showdown: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct HandReplay {
+    pub sala: String,
+    pub hand_id: String,
+    pub mesa: String,
+    pub fecha: NaiveDateTime,
+    pub variante: Variant,
+    pub ciega_pequena: Chips,
+    pub ciega_grande: Chips,
+    pub ante: Chips,
+    pub boton: u8,
+    pub asientos: Vec<ReplayerSeat>,
+    pub estados: Vec<ReplayerState>,
+    // Quién se lleva cada bote al final
+    pub botes: Vec<Collection>,
+    pub rake: Chips,
+}
+
+// Filtros del listado de manos de un jugador
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct HandListFilter {
+    // Solo manos en las que el jugador enseñó sus cartas
+    pub solo_showdown: bool,
+    // Bote mínimo en ciegas grandes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bote_min_bb: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limite: Option<usize>,
+}
+
+// Resumen de una mano del listado
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct HandSummary {
+    pub sala: String,
+    pub hand_id: String,
+    pub fecha: NaiveDateTime,
+    pub mesa: String,
+    pub ciega_grande: Chips,
+    pub posicion: Option<Position>,
+    pub bote_bb: f64,
+    // Resultado del jugador consultado
+    pub neto_bb: f64,
+    // Cartas que enseñó el jugador (vacío si no llegó a enseñarlas)
+    pub cartas: Vec<Card>,
+    pub board: Vec<Card>,
+}
+
+// Mano guardada lista para el reproductor
+pub fn hand_replay(sala: &str, hand_id: &str) -> Result<HandReplay, String> {
+    let hand = hand_db::get_hand(sala, hand_id)?
+        .ok_or_else(|| format!("No se encontró la mano {} de {}", hand_id, sala))?;
+    Ok(build_replay(&hand))
+}
+
+pub fn build_replay(hand: &Hand) -> HandReplay {
+    // Los jugadores ausentes solo aparecen si llegan a actuar (p. ej. pagando ciegas)
+    let seats: Vec<_> = hand.seats.iter()
+        .filter(|s| !s.sitting_out || hand.actions.iter().any(|a| a.player == s.player))
+        .collect();
+    let index_of = |player: &str| seats.iter().position(|s| s.player == player);
+    let is_hero = |player: &str| hand.hero.as_deref() == Some(player);
+
+    let mut state = ReplayerState {
+        calle: Street::Preflop,
+        jugador: None,
+        accion: None,
+        importe: 0,
+        all_in: false,
+        board: Vec::new(),
+        bote: 0,
+        apuestas: vec![0; seats.len()],
+        stacks: seats.iter().map(|s| s.stack).collect(),
+        retirados: vec![false; seats.len()],
+        cartas: seats.iter()
+            .map(|s| if is_hero(&s.player) { hand.hero_cards.clone() } else { Vec::new() })
+            .collect(),
+        showdown: false,
+    };
+    let mut estados = vec![state.clone()];
+
+    for street in [Street::Preflop, Street::Flop, Street::Turn, Street::River] {
+        let visible = visible_board(street).min(hand.board.len());
+        let has_actions = hand.actions.iter().any(|a| a.street == street);
+        if street != Street::Preflop {
+            if visible == state.board.len() && !has_actions {
+                break;
+            }
+            // Las apuestas de la calle anterior pasan al bote
+            state.bote += state.apuestas.iter().sum::<Chips>();
+            state.apuestas.iter_mut().for_each(|bet| *bet = 0);
+            state.calle = street;
+            state.board = hand.board[..visible].to_vec();
+            state.jugador = None;
+            state.accion = None;
+            state.importe = 0;
+            state.all_in = false;
+            estados.push(state.clone());
+        }
+
+        for action in hand.actions.iter().filter(|a| a.street == street) {
+            let Some(i) = index_of(&action.player) else {
+                continue;
+            };
+            state.stacks[i] -= action.amount;
+            // Las antes van directamente al bote
+            if action.kind == ActionKind::Ante {
+                state.bote += action.amount;
+            } else {
+                state.apuestas[i] += action.amount;
+            }
+            if action.kind == ActionKind::Fold {
+                state.retirados[i] = true;
+            }
+            state.jugador = Some(action.player.clone());
+            state.accion = Some(action.kind);
+            state.importe = action.to.unwrap_or(action.amount);
+            state.all_in = action.all_in;
+            estados.push(state.clone());
+        }
+    }
+
+    // Final: se devuelven las apuestas no igualadas y se enseñan las cartas
+    for returned in hand.returned.iter() {
+        if let Some(i) = index_of(&returned.player) {
+            let from_bet = returned.amount.min(state.apuestas[i]);
+            state.apuestas[i] -= from_bet;
+            state.bote -= returned.amount - from_bet;
+            state.stacks[i] += returned.amount;
+        }
+    }
+    state.bote += state.apuestas.iter().sum::<Chips>();
+    state.apuestas.iter_mut().for_each(|bet| *bet = 0);
+    for shown in hand.showdown.iter().filter(|s| !s.cards.is_empty()) {
+        if let Some(i) = index_of(&shown.player) {
+            state.cartas[i] = shown.cards.clone();
+        }
+    }
+    state.board = hand.board.clone();
+    state.jugador = None;
+    state.accion = None;
+    state.importe = 0;
+    state.all_in = false;
+    state.showdown = true;
+    estados.push(state);
+
+    HandReplay {
+        sala: hand.site.code().to_string(),
+        hand_id: hand.id.clone(),
+        mesa: hand.table.clone(),
+        fecha: hand.date,
+        variante: hand.variant,
+        ciega_pequena: hand.small_blind,
+        ciega_grande: hand.big_blind,
+        ante: hand.ante,
+        boton: hand.button_seat,
+        asientos: seats.iter()
+            .map(|s| ReplayerSeat {
+                asiento: s.number,
+                jugador: s.player.clone(),
+                stack: s.stack,
+                posicion: hand.position(&s.player),
+                hero: is_hero(&s.player),
+                neto: hand.net(&s.player),
+            })
+            .collect(),
+        estados,
+        botes: hand.collected.clone(),
+        rake: hand.rake,
+    }
+}
+
+// Cartas comunitarias repartidas al llegar a cada calle
+fn visible_board(street: Street) -> usize {
+    match street {
+        Street::Preflop => 0,
+        Street::Flop => 3,
+        Street::Turn => 4,
+        Street::River => 5,
+    }
+}
+
+// Manos guardadas de un jugador, de la más reciente a la más antigua
+pub fn list_player_hands(sala: &str, nick: &str, filter: &HandListFilter) -> Result<Vec<HandSummary>, String> {
+    let limit = filter.limite.unwrap_or(DEFAULT_HAND_LIMIT);
+    let hands = hand_db::player_hands(sala, nick, filter.solo_showdown, filter.bote_min_bb, limit)?;
+    Ok(summarize_hands(&hands))
+}
+
+fn summarize_hands(hands: &[(String, Hand)]) -> Vec<HandSummary> {
+    hands.iter()
+        .filter_map(|(nick, hand)| summarize(hand, hand.seat_player(nick)?))
+        .collect()
+}
+
+fn summarize(hand: &Hand, player: &str) -> Option<HandSummary> {
+    if hand.big_blind <= 0 {
+        return None;
+    }
+    let big_blind = hand.big_blind as f64;
+
+    Some(HandSummary {
+        sala: hand.site.code().to_string(),
+        hand_id: hand.id.clone(),
+        fecha: hand.date,
+        mesa: hand.table.clone(),
+        ciega_grande: hand.big_blind,
+        posicion: hand.position(player),
+        bote_bb: hand.total_pot as f64 / big_blind,
+        neto_bb: hand.net(player) as f64 / big_blind,
+        cartas: hand.shown_cards(player).to_vec(),
+        board: hand.board.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand_history::fixtures::pokerstars_hands;
+
+    #[test]
+    fn replays_streets_with_stacks_pot_and_cards() {
+        let hand = &pokerstars_hands()[0];
+        let replay = build_replay(hand);
+
+        // El jugador ausente que no actúa no aparece
+        assert_eq!(replay.asientos.len(), 5);
+        let hero = replay.asientos.iter().position(|s| s.hero).unwrap();
+        let villain = replay.asientos.iter().position(|s| s.jugador == "bb:player").unwrap();
+        assert_eq!(replay.asientos[villain].posicion, Some(Position::Bb));
+
+        let first = &replay.estados[0];
+        assert_eq!(first.cartas[hero].len(), 2);
+        assert!(first.cartas[villain].is_empty());
+
+        // Inicio del flop: ciegas y llamadas recogidas en el bote
+        let flop = replay.estados.iter().find(|s| s.calle == Street::Flop && s.accion.is_none()).unwrap();
+        assert_eq!(flop.board.len(), 3);
+        assert_eq!(flop.bote, 65);
+        assert!(flop.apuestas.iter().all(|bet| *bet == 0));
+
+        // All-in en el flop: turn y river se reparten sin acciones
+        let streets: Vec<Street> = replay.estados.iter().filter(|s| s.accion.is_none()).map(|s| s.calle).collect();
+        assert_eq!(streets, [Street::Preflop, Street::Flop, Street::Turn, Street::River, Street::River]);
+
+        let last = replay.estados.last().unwrap();
+        assert!(last.showdown);
+        assert_eq!(last.bote, hand.total_pot);
+        assert_eq!(last.stacks[villain], 0);
+        assert_eq!(last.cartas[villain].len(), 2);
+        assert_eq!(last.board.len(), 5);
+        let total: Chips = replay.asientos.iter().map(|s| s.stack).sum();
+        assert_eq!(total - last.stacks.iter().sum::<Chips>(), last.bote);
+    }
+
+    #[test]
+    fn uncalled_bets_go_back_to_the_stack() {
+        let hand = &pokerstars_hands()[1];
+        let replay = build_replay(hand);
+        let hero = replay.asientos.iter().position(|s| s.hero).unwrap();
+        let last = replay.estados.last().unwrap();
+        assert_eq!(last.bote, hand.total_pot);
+        assert_eq!(last.stacks[hero], replay.asientos[hero].stack - 30);
+        assert_eq!(replay.asientos[hero].neto, 35);
+    }
+
+    #[test]
+    fn lists_hands_with_showdown_and_pot_filters() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        hand_db::init_schema(&conn).unwrap();
+        hand_db::insert_hands_in(&conn, &pokerstars_hands()).unwrap();
+        let list = |filter: &HandListFilter| {
+            let limit = filter.limite.unwrap_or(DEFAULT_HAND_LIMIT);
+            let hands = hand_db::player_hands_in(&conn, "pokerstars", "BB:PLAYER", filter.solo_showdown, filter.bote_min_bb, limit, "").unwrap();
+            summarize_hands(&hands)
+        };
+
+        let all = list(&HandListFilter::default());
+        assert_eq!(all.len(), 3);
+        assert!(all.windows(2).all(|pair| pair[0].fecha >= pair[1].fecha));
+        let shown = list(&HandListFilter { solo_showdown: true, ..Default::default() });
+        assert_eq!(shown.len(), 2);
+        assert!(shown.iter().all(|s| s.cartas.len() == 2));
+
+        let big = list(&HandListFilter { bote_min_bb: Some(100.0), ..Default::default() });
+        assert_eq!(big.len(), 1);
+        assert_eq!(big[0].hand_id, "245000000003");
+        assert_eq!(list(&HandListFilter { limite: Some(1), ..Default::default() }).len(), 1);
+    }
+}
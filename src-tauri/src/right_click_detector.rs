@@ -2,6 +2,7 @@ use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Manager, Runtime};
 use std::thread;
 use std::time::Duration;
+use tokio::sync::watch;
 
 #[cfg(target_os = "windows")]
 use windows_sys::Win32::UI::WindowsAndMessaging::*;
@@ -13,6 +14,10 @@ use windows_sys::Win32::UI::Input::KeyboardAndMouse::*;
 use windows_sys::Win32::Graphics::Gdi::ScreenToClient;
 
 use crate::window_manager::is_poker_table;
+use crate::config_state::ConfigState;
+use crate::settings::AppConfig;
+#[cfg(target_os = "windows")]
+use crate::profiles;
 
 // Estructura para mantener el estado del detector
 pub struct RightClickDetector {
@@ -45,9 +50,12 @@ impl RightClickDetector {
         let active = Arc::clone(&self.active);
         let running = Arc::clone(&self.running);
 
+        // Recibir los cambios de configuración sin leer el archivo en cada clic
+        let config_rx = app_handle.state::<ConfigState>().subscribe();
+
         // Iniciar hilo para monitoreo
         self.thread_handle = Some(thread::spawn(move || {
-            Self::run_detection_loop(app_handle, active, running);
+            Self::run_detection_loop(app_handle, config_rx, active, running);
         }));
 
         Ok(())
@@ -88,6 +96,7 @@ impl RightClickDetector {
     #[cfg(target_os = "windows")]
     fn run_detection_loop<R: Runtime>(
         app_handle: AppHandle<R>,
+        config_rx: watch::Receiver<AppConfig>,
         active: Arc<Mutex<bool>>,
         running: Arc<Mutex<bool>>
     ) {
@@ -139,6 +148,9 @@ impl RightClickDetector {
                                             let mut client_point = point;
                                             ScreenToClient(hwnd, &mut client_point);
                                             
+                                            // Perfil de la sala con la configuración más reciente
+                                            let (effective, profile_name) = profiles::resolve_for_title(&config_rx.borrow(), &title_str);
+                                            
                                             // Emitir evento a la interfaz
                                             let _ = app_handle.emit_all("profile_right_click", serde_json::json!({
                                                 "hwnd": hwnd,
@@ -147,7 +159,10 @@ impl RightClickDetector {
                                                 "x": client_point.x,
                                                 "y": client_point.y,
                                                 "screen_x": point.x,
-                                                "screen_y": point.y
+                                                "screen_y": point.y,
+                                                "perfil": profile_name,
+                                                "sala": effective.sala_default,
+                                                "ocr_coords": effective.ocr_coords
                                             }));
                                             
                                             // Actualizar ventana actual
@@ -178,6 +193,7 @@ impl RightClickDetector {
     #[cfg(not(target_os = "windows"))]
     fn run_detection_loop<R: Runtime>(
         _app_handle: AppHandle<R>,
+        _config_rx: watch::Receiver<AppConfig>,
         _active: Arc<Mutex<bool>>,
         running: Arc<Mutex<bool>>
     ) {
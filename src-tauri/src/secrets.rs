@@ -0,0 +1,491 @@
+// src-tauri/src/secrets.rs
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use once_cell::sync::Lazy;
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use aes_gcm::aead::{Aead, KeyInit};
+use rand::RngCore;
+use sha2::Sha256;
+use reqwest::Client;
+use reqwest::header::AUTHORIZATION;
+
+// Identificadores de la entrada del llavero del sistema
+const KEYRING_SERVICE: &str = "PokerProTrack";
+const KEYRING_USER: &str = "secrets-key";
+
+// Iteraciones de PBKDF2 para derivar la clave desde una contraseña
+const PBKDF2_ROUNDS: u32 = 200_000;
+
+const STORE_VERSION: u32 = 1;
+
+// Secretos gestionados por el almacén
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretKind {
+    Token,
+    OpenaiApiKey,
+}
+
+impl SecretKind {
+    fn key(&self) -> &'static str {
+        match self {
+            SecretKind::Token => "token",
+            SecretKind::OpenaiApiKey => "openai_api_key",
+        }
+    }
+}
+
+// Origen de la clave de cifrado
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    Keyring,
+    Passphrase,
+}
+
+// Formato del archivo cifrado en disco
+#[derive(Debug, Serialize, Deserialize)]
+struct SecretsFile {
+    version: u32,
+    key_source: KeySource,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+// Estado del almacén que se puede compartir con el frontend (nunca incluye valores)
+#[derive(Debug, Serialize, Clone)]
+pub struct SecretsStatus {
+    pub key_source: Option<KeySource>,
+    pub unlocked: bool,
+    pub needs_passphrase: bool,
+    // Error al abrir con el llavero un almacén que depende de él (clave perdida o
+    // llavero no disponible); se puede descartar el almacén con reset_store
+    pub keyring_error: Option<String>,
+    pub token_set: bool,
+    pub openai_api_key_set: bool,
+}
+
+// Estado en memoria del almacén
+#[derive(Default)]
+struct SecretsState {
+    key: Option<[u8; 32]>,
+    key_source: Option<KeySource>,
+    salt: Vec<u8>,
+    values: HashMap<String, String>,
+    keyring_error: Option<String>,
+    // Secretos recibidos mientras el almacén estaba bloqueado
+    pending: HashMap<String, String>,
+}
+
+static SECRETS: Lazy<Mutex<SecretsState>> = Lazy::new(|| Mutex::new(SecretsState::default()));
+
+// Ruta del archivo de secretos, separado de config.json
+fn secrets_path() -> Result<PathBuf, String> {
+    let app_config_dir = tauri::api::path::app_config_dir(&tauri::Config::default())
+        .ok_or("No se pudo determinar el directorio de configuración")?;
+    Ok(app_config_dir.join("secrets.dat"))
+}
+
+fn read_secrets_file() -> Result<Option<SecretsFile>, String> {
+    let path = secrets_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Error al leer almacén de secretos: {}", e))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Almacén de secretos dañado: {}", e))
+}
+
+fn keyring_entry() -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+        .map_err(|e| format!("Llavero del sistema no disponible: {}", e))
+}
+
+// Obtiene la clave guardada en el llavero del sistema
+fn load_keyring_key() -> Result<[u8; 32], String> {
+    let encoded = keyring_entry()?
+        .get_password()
+        .map_err(|e| format!("No se pudo leer la clave del llavero: {}", e))?;
+    let bytes = base64::decode(encoded).map_err(|_| "Clave del llavero no válida".to_string())?;
+    bytes.try_into().map_err(|_| "Clave del llavero no válida".to_string())
+}
+
+// Genera una clave nueva y la guarda en el llavero del sistema
+fn create_keyring_key() -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut key);
+    keyring_entry()?
+        .set_password(&base64::encode(key))
+        .map_err(|e| format!("No se pudo guardar la clave en el llavero: {}", e))?;
+    Ok(key)
+}
+
+fn derive_passphrase_key(passphrase: &str, salt: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+    key
+}
+
+fn decrypt_file(file: &SecretsFile, key: &[u8; 32]) -> Result<HashMap<String, String>, String> {
+    let nonce = base64::decode(&file.nonce).map_err(|_| "Almacén de secretos dañado".to_string())?;
+    let ciphertext = base64::decode(&file.ciphertext).map_err(|_| "Almacén de secretos dañado".to_string())?;
+    if nonce.len() != 12 {
+        return Err("Almacén de secretos dañado".to_string());
+    }
+
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let plaintext = cipher.decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| "No se pudo descifrar el almacén (clave o contraseña incorrecta)".to_string())?;
+
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Almacén de secretos dañado: {}", e))
+}
+
+// Cifra los secretos con la clave del almacén
+fn encrypt_values(values: &HashMap<String, String>, key: &[u8; 32], key_source: KeySource, salt: &[u8]) -> Result<SecretsFile, String> {
+    let plaintext = serde_json::to_vec(values)
+        .map_err(|e| format!("Error al serializar secretos: {}", e))?;
+
+    let mut nonce = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce), plaintext.as_ref())
+        .map_err(|_| "Error al cifrar secretos".to_string())?;
+
+    Ok(SecretsFile {
+        version: STORE_VERSION,
+        key_source,
+        salt: base64::encode(salt),
+        nonce: base64::encode(nonce),
+        ciphertext: base64::encode(ciphertext),
+    })
+}
+
+// Cifra y guarda los secretos en disco
+fn persist(state: &SecretsState) -> Result<(), String> {
+    let key = state.key.ok_or("El almacén de secretos está bloqueado")?;
+    let key_source = state.key_source.ok_or("El almacén de secretos está bloqueado")?;
+    let file = encrypt_values(&state.values, &key, key_source, &state.salt)?;
+
+    let path = secrets_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Error al crear directorio de configuración: {}", e))?;
+    }
+    let serialized = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Error al serializar secretos: {}", e))?;
+    fs::write(&path, serialized)
+        .map_err(|e| format!("Error al escribir almacén de secretos: {}", e))
+}
+
+// Pasa a los valores del almacén los secretos recibidos mientras estaba bloqueado.
+// Devuelve true si había alguno.
+fn merge_pending(state: &mut SecretsState) -> bool {
+    if state.pending.is_empty() {
+        return false;
+    }
+    let pending: Vec<(String, String)> = state.pending.drain().collect();
+    for (name, value) in pending {
+        state.values.entry(name).or_insert(value);
+    }
+    true
+}
+
+// Vuelca en el almacén los secretos recibidos mientras estaba bloqueado
+fn flush_pending(state: &mut SecretsState) -> Result<(), String> {
+    if merge_pending(state) {
+        persist(state)?;
+    }
+    Ok(())
+}
+
+// Abre el almacén con la clave del llavero, o crea uno nuevo si no hay archivo.
+// Si el llavero no está disponible el almacén queda bloqueado a la espera de una contraseña.
+fn open_store(
+    state: &mut SecretsState,
+    file: Option<&SecretsFile>,
+    load_key: impl FnOnce() -> Result<[u8; 32], String>,
+    create_key: impl FnOnce() -> Result<[u8; 32], String>,
+) -> Result<(), String> {
+    match file {
+        Some(file) => match file.key_source {
+            KeySource::Keyring => {
+                state.key_source = Some(KeySource::Keyring);
+                let opened = load_key().and_then(|key| Ok((key, decrypt_file(file, &key)?)));
+                let (key, values) = opened.inspect_err(|e| state.keyring_error = Some(e.clone()))?;
+                state.values = values;
+                state.key = Some(key);
+                state.keyring_error = None;
+                state.salt = base64::decode(&file.salt).unwrap_or_default();
+                Ok(())
+            },
+            KeySource::Passphrase => {
+                state.key_source = Some(KeySource::Passphrase);
+                Err("Se requiere la contraseña del almacén de secretos".to_string())
+            },
+        },
+        None => {
+            // Almacén nuevo: preferir el llavero del sistema
+            match create_key() {
+                Ok(key) => {
+                    state.key = Some(key);
+                    state.key_source = Some(KeySource::Keyring);
+                    state.values.clear();
+                    Ok(())
+                },
+                Err(e) => {
+                    println!("{} - se usará una contraseña", e);
+                    state.key_source = Some(KeySource::Passphrase);
+                    Err("Se requiere una contraseña para crear el almacén de secretos".to_string())
+                },
+            }
+        },
+    }
+}
+
+// Intenta desbloquear el almacén sin intervención del usuario (llavero del sistema)
+fn ensure_unlocked(state: &mut SecretsState) -> Result<(), String> {
+    if state.key.is_some() {
+        return Ok(());
+    }
+    let file = read_secrets_file()?;
+    open_store(state, file.as_ref(), load_keyring_key, create_keyring_key)?;
+    flush_pending(state)
+}
+
+// Obtiene la clave a partir de la contraseña, descifrando el archivo si existe
+fn unlock_state(state: &mut SecretsState, file: Option<&SecretsFile>, passphrase: &str) -> Result<(), String> {
+    match file {
+        Some(file) => {
+            if file.key_source != KeySource::Passphrase {
+                return Err("El almacén usa el llavero del sistema, no una contraseña".to_string());
+            }
+            let salt = base64::decode(&file.salt).map_err(|_| "Almacén de secretos dañado".to_string())?;
+            let key = derive_passphrase_key(passphrase, &salt);
+            state.values = decrypt_file(file, &key)?;
+            state.key = Some(key);
+            state.salt = salt;
+        },
+        None => {
+            let mut salt = vec![0u8; 16];
+            rand::thread_rng().fill_bytes(&mut salt);
+            state.key = Some(derive_passphrase_key(passphrase, &salt));
+            state.salt = salt;
+            state.values.clear();
+        },
+    }
+    state.key_source = Some(KeySource::Passphrase);
+    Ok(())
+}
+
+// Desbloquea (o crea) el almacén con una clave derivada de una contraseña
+pub fn unlock_with_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.is_empty() {
+        return Err("La contraseña no puede estar vacía".to_string());
+    }
+
+    let mut state = SECRETS.lock().map_err(|_| "No se pudo acceder al almacén de secretos")?;
+    let file = read_secrets_file()?;
+    unlock_state(&mut state, file.as_ref(), passphrase)?;
+    merge_pending(&mut state);
+    persist(&state)
+}
+
+// Descarta un almacén que no se puede abrir (p. ej. se perdió la clave del llavero)
+// y crea uno nuevo con los secretos pendientes. El archivo anterior se conserva como
+// secrets.dat.bak por si la clave vuelve a estar disponible.
+pub fn reset_store() -> Result<(), String> {
+    let mut state = SECRETS.lock().map_err(|_| "No se pudo acceder al almacén de secretos")?;
+    if ensure_unlocked(&mut state).is_ok() {
+        return Err("El almacén de secretos funciona, no hace falta descartarlo".to_string());
+    }
+
+    let path = secrets_path()?;
+    if path.exists() {
+        fs::rename(&path, path.with_extension("dat.bak"))
+            .map_err(|e| format!("Error al descartar el almacén de secretos: {}", e))?;
+    }
+    let pending = std::mem::take(&mut state.pending);
+    *state = SecretsState { pending, ..Default::default() };
+
+    // Sin llavero el almacén nuevo queda a la espera de una contraseña (unlock_with_passphrase)
+    if let Err(e) = ensure_unlocked(&mut state) {
+        println!("{}", e);
+    }
+    Ok(())
+}
+
+// Obtiene el valor de un secreto (cadena vacía si no existe). Si el almacén está
+// bloqueado devuelve el error, salvo que el secreto esté pendiente de guardarse.
+pub fn get_secret(kind: SecretKind) -> Result<String, String> {
+    let mut state = SECRETS.lock().map_err(|_| "No se pudo acceder al almacén de secretos")?;
+    if let Err(e) = ensure_unlocked(&mut state) {
+        return state.pending.get(kind.key()).cloned().ok_or(e);
+    }
+    Ok(state.values.get(kind.key()).cloned().unwrap_or_default())
+}
+
+// Guarda un secreto en el almacén cifrado
+pub fn set_secret(kind: SecretKind, value: &str) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return clear_secret(kind);
+    }
+
+    let mut state = SECRETS.lock().map_err(|_| "No se pudo acceder al almacén de secretos")?;
+    ensure_unlocked(&mut state)?;
+    state.values.insert(kind.key().to_string(), value.trim().to_string());
+    persist(&state)
+}
+
+// Elimina un secreto del almacén
+pub fn clear_secret(kind: SecretKind) -> Result<(), String> {
+    let mut state = SECRETS.lock().map_err(|_| "No se pudo acceder al almacén de secretos")?;
+    state.pending.remove(kind.key());
+    ensure_unlocked(&mut state)?;
+    if state.values.remove(kind.key()).is_some() {
+        persist(&state)?;
+    }
+    Ok(())
+}
+
+// Importa secretos que estaban guardados en texto plano en config.json.
+// Devuelve true si quedaron guardados en el almacén cifrado.
+pub fn import_legacy(values: Vec<(SecretKind, String)>) -> bool {
+    let mut state = match SECRETS.lock() {
+        Ok(state) => state,
+        Err(_) => return false,
+    };
+
+    for (kind, value) in values {
+        if !value.trim().is_empty() {
+            state.pending.insert(kind.key().to_string(), value.trim().to_string());
+        }
+    }
+
+    // Si el almacén está bloqueado, quedan pendientes hasta que se desbloquee
+    ensure_unlocked(&mut state).is_ok()
+}
+
+// Estado del almacén para la interfaz de configuración
+pub fn status() -> SecretsStatus {
+    let mut state = match SECRETS.lock() {
+        Ok(state) => state,
+        Err(_) => return SecretsStatus {
+            key_source: None,
+            unlocked: false,
+            needs_passphrase: false,
+            keyring_error: None,
+            token_set: false,
+            openai_api_key_set: false,
+        },
+    };
+
+    let unlocked = ensure_unlocked(&mut state).is_ok();
+    let is_set = |kind: SecretKind| {
+        state.values.contains_key(kind.key()) || state.pending.contains_key(kind.key())
+    };
+
+    SecretsStatus {
+        key_source: state.key_source,
+        unlocked,
+        needs_passphrase: !unlocked && state.key_source == Some(KeySource::Passphrase),
+        keyring_error: if unlocked { None } else { state.keyring_error.clone() },
+        token_set: is_set(SecretKind::Token),
+        openai_api_key_set: is_set(SecretKind::OpenaiApiKey),
+    }
+}
+
+// Comprueba que un secreto funciona contra su servicio
+pub async fn test_secret(kind: SecretKind, server_url: &str) -> Result<bool, String> {
+    let value = get_secret(kind)?;
+    if value.is_empty() {
+        return Ok(false);
+    }
+
+    match kind {
+        SecretKind::Token => crate::auth::verify_token(&value, server_url).await,
+        SecretKind::OpenaiApiKey => {
+            let client = Client::new();
+            match client.get("https://api.openai.com/v1/models")
+                .header(AUTHORIZATION, format!("Bearer {}", value))
+                .send()
+                .await {
+                    Ok(response) => Ok(response.status().is_success()),
+                    Err(e) => Err(format!("Error de conexión con OpenAI: {}", e)),
+                }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> HashMap<String, String> {
+        HashMap::from([
+            (SecretKind::Token.key().to_string(), "token-123".to_string()),
+            (SecretKind::OpenaiApiKey.key().to_string(), "sk-abc".to_string()),
+        ])
+    }
+
+    #[test]
+    fn encrypted_values_round_trip_only_with_the_same_key() {
+        let key = [7u8; 32];
+        let file = encrypt_values(&values(), &key, KeySource::Keyring, &[]).unwrap();
+        assert!(!file.ciphertext.contains("token-123"));
+        assert_eq!(decrypt_file(&file, &key).unwrap(), values());
+        assert!(decrypt_file(&file, &[8u8; 32]).is_err());
+
+        let mut state = SecretsState::default();
+        open_store(&mut state, Some(&file), || Ok(key), || panic!("no debe crear otra clave")).unwrap();
+        assert_eq!(state.key_source, Some(KeySource::Keyring));
+        assert_eq!(state.values, values());
+    }
+
+    #[test]
+    fn falls_back_to_a_passphrase_without_keyring() {
+        let mut state = SecretsState::default();
+        state.pending.insert(SecretKind::Token.key().to_string(), "token-123".to_string());
+
+        // Sin llavero el almacén nuevo queda bloqueado hasta tener contraseña
+        let locked = open_store(&mut state, None, || panic!("no hay archivo"), || Err("sin llavero".to_string()));
+        assert!(locked.is_err());
+        assert!(state.key.is_none());
+        assert_eq!(state.key_source, Some(KeySource::Passphrase));
+
+        unlock_state(&mut state, None, "contraseña").unwrap();
+        assert!(merge_pending(&mut state));
+        assert_eq!(state.values.get("token").map(String::as_str), Some("token-123"));
+
+        let file = encrypt_values(&state.values, &state.key.unwrap(), KeySource::Passphrase, &state.salt).unwrap();
+        let mut reopened = SecretsState::default();
+        assert!(open_store(&mut reopened, Some(&file), || panic!("no usa el llavero"), || panic!("no crea clave")).is_err());
+        assert!(unlock_state(&mut reopened, Some(&file), "otra").is_err());
+        unlock_state(&mut reopened, Some(&file), "contraseña").unwrap();
+        assert_eq!(reopened.values, state.values);
+    }
+
+    #[test]
+    fn reports_a_keyring_store_without_its_key() {
+        let file = encrypt_values(&values(), &[7u8; 32], KeySource::Keyring, &[]).unwrap();
+
+        let mut state = SecretsState::default();
+        let opened = open_store(&mut state, Some(&file), || Err("sin llavero".to_string()), || panic!("no debe crear otra clave"));
+        assert!(opened.is_err());
+        assert!(state.key.is_none());
+        assert_eq!(state.keyring_error.as_deref(), Some("sin llavero"));
+
+        // Una clave distinta en el llavero tampoco abre el almacén
+        assert!(open_store(&mut state, Some(&file), || Ok([8u8; 32]), || panic!("no debe crear otra clave")).is_err());
+        assert!(state.keyring_error.is_some());
+
+        open_store(&mut state, Some(&file), || Ok([7u8; 32]), || panic!("no debe crear otra clave")).unwrap();
+        assert_eq!(state.keyring_error, None);
+    }
+}
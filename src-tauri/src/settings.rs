@@ -1,12 +1,34 @@
 use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
 use std::path::Path;
 use serde::{Deserialize, Serialize};
 use tauri::api::path::app_config_dir;
 use tauri::api::path::app_data_dir;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use crate::secrets::{self, SecretKind};
+use crate::config_migrations::{self, CONFIG_VERSION};
+use crate::config_validation;
+use crate::config_backups;
+use crate::profiles::SiteProfile;
+use crate::stats_source::StatsSourceKind;
+use crate::stats_filter::StatsFilter;
+use crate::hero_report::{self, RangoReferencia};
+
+// Claves de estadísticas que la app sabe mostrar
+pub const STAT_KEYS: &[&str] = &[
+    "vpip", "pfr", "three_bet", "fold_to_3bet_pct", "wtsd", "wsd", 
+    "cbet_flop", "cbet_turn", "fold_to_flop_cbet_pct", "fold_to_turn_cbet_pct",
+    "limp_pct", "limp_raise_pct", "four_bet_preflop_pct", "fold_to_4bet_pct",
+    "probe_bet_turn_pct", "bet_river_pct", "fold_to_river_bet_pct", 
+    "overbet_turn_pct", "overbet_river_pct", "wsdwbr_pct", "wwsf", 
+    "total_manos", "bb_100", "win_usd", "por_posicion"
+];
 
 // Estructura de configuración que se puede compartir con el frontend
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct OcrCoords {
     pub x: i32,
     pub y: i32,
@@ -14,11 +36,18 @@ pub struct OcrCoords {
     pub h: i32,
 }
 
-// Estructura para manejar las estadísticas seleccionadas
+impl Default for OcrCoords {
+    fn default() -> Self {
+        OcrCoords { x: 95, y: 110, w: 95, h: 22 }
+    }
+}
+
+// Estructura para manejar las estadísticas seleccionadas.
+// Los campos que falten en el archivo toman su valor de `AppConfig::default()`.
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct AppConfig {
-    pub token: String,
-    pub openai_api_key: String,
+    pub version: u32,
     pub ocr_coords: OcrCoords,
     pub server_url: String,
     pub sala_default: String,
@@ -33,19 +62,24 @@ pub struct AppConfig {
     pub stats_seleccionadas: HashMap<String, bool>,
     pub stats_order: Vec<String>,
     pub stats_format: HashMap<String, String>,
+    // Plantilla del prompt de análisis (None = plantilla incorporada)
+    pub plantilla_analisis: Option<String>,
+    // Perfiles por sala; los campos de arriba forman el perfil global
+    pub perfiles: Vec<SiteProfile>,
+    // Carpetas de historiales de manos que se importan en segundo plano
+    pub carpetas_historial: Vec<String>,
+    // De dónde salen las stats: servidor, base de datos local o ambas
+    pub fuente_stats: StatsSourceKind,
+    // Filtro de las stats (límites, fechas, tamaño de mesa, juego, posición)
+    pub filtro_stats: StatsFilter,
+    // Rangos de referencia (en %) para detectar leaks en las manos propias
+    pub rangos_referencia: HashMap<String, RangoReferencia>,
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         let mut stats_seleccionadas = HashMap::new();
-        let default_stats = vec![
-            "vpip", "pfr", "three_bet", "fold_to_3bet_pct", "wtsd", "wsd", 
-            "cbet_flop", "cbet_turn", "fold_to_flop_cbet_pct", "fold_to_turn_cbet_pct",
-            "limp_pct", "limp_raise_pct", "four_bet_preflop_pct", "fold_to_4bet_pct",
-            "probe_bet_turn_pct", "bet_river_pct", "fold_to_river_bet_pct", 
-            "overbet_turn_pct", "overbet_river_pct", "wsdwbr_pct", "wwsf", 
-            "total_manos", "bb_100", "win_usd"
-        ];
+        let default_stats = STAT_KEYS.to_vec();
         
         // Establecer valores predeterminados para estadísticas
         for stat in default_stats.iter() {
@@ -65,11 +99,11 @@ impl Default for AppConfig {
         stats_format.insert("wsd".to_string(), "WSD:{value}".to_string());
         stats_format.insert("cbet_flop".to_string(), "CF:{value}".to_string());
         stats_format.insert("cbet_turn".to_string(), "CT:{value}".to_string());
+        stats_format.insert("por_posicion".to_string(), "{value}".to_string());
         
         AppConfig {
-            token: String::new(),
-            openai_api_key: String::new(),
-            ocr_coords: OcrCoords { x: 95, y: 110, w: 95, h: 22 },
+            version: CONFIG_VERSION,
+            ocr_coords: OcrCoords::default(),
             server_url: "http://localhost:3000".to_string(),
             sala_default: "XPK".to_string(),
             hotkey: "alt+q".to_string(),
@@ -83,8 +117,84 @@ impl Default for AppConfig {
             stats_seleccionadas,
             stats_order: default_stats.into_iter().map(|s| s.to_string()).collect(),
             stats_format,
+            plantilla_analisis: None,
+            perfiles: Vec::new(),
+            carpetas_historial: Vec::new(),
+            fuente_stats: StatsSourceKind::default(),
+            filtro_stats: StatsFilter::default(),
+            rangos_referencia: hero_report::default_reference_ranges(),
+        }
+    }
+}
+
+// Resultado de interpretar el contenido de config.json
+#[derive(Debug)]
+pub struct ParsedConfig {
+    pub config: AppConfig,
+    pub from_version: u32,
+    // Secretos encontrados en texto plano que deben ir al almacén cifrado
+    pub legacy_secrets: Vec<(SecretKind, String)>,
+    // Campos con valores no válidos que se sustituyeron por su valor por defecto
+    pub dropped_fields: Vec<String>,
+}
+
+// Interpreta el contenido de config.json aplicando las migraciones pendientes.
+// Solo falla si el archivo no es un objeto JSON.
+pub fn parse_config(content: &str) -> Result<ParsedConfig, String> {
+    let raw: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| format!("JSON no válido: {}", e))?;
+    
+    let outcome = config_migrations::migrate(raw)?;
+    
+    let (config, dropped_fields) = match serde_json::from_value::<AppConfig>(outcome.value.clone()) {
+        Ok(config) => (config, Vec::new()),
+        Err(e) => {
+            println!("Error al deserializar la configuración: {}", e);
+            recover_fields(&outcome.value)
+        }
+    };
+    
+    Ok(ParsedConfig {
+        config,
+        from_version: outcome.from_version,
+        legacy_secrets: outcome.context.legacy_secrets,
+        dropped_fields,
+    })
+}
+
+// Recupera campo a campo una configuración con algún valor de tipo incorrecto,
+// descartando solo los campos que no se pueden interpretar.
+fn recover_fields(value: &serde_json::Value) -> (AppConfig, Vec<String>) {
+    let mut merged = serde_json::to_value(AppConfig::default()).unwrap_or_default();
+    let mut dropped = Vec::new();
+    
+    if let (Some(fields), Some(target)) = (value.as_object(), merged.as_object_mut()) {
+        for (key, field_value) in fields {
+            let previous = target.insert(key.clone(), field_value.clone());
+            let candidate = serde_json::Value::Object(target.clone());
+            if serde_json::from_value::<AppConfig>(candidate).is_err() {
+                // Restaurar el valor por defecto del campo
+                match previous {
+                    Some(previous) => target.insert(key.clone(), previous),
+                    None => target.remove(key),
+                };
+                dropped.push(key.clone());
+            }
         }
     }
+    
+    let config = serde_json::from_value(merged).unwrap_or_default();
+    (config, dropped)
+}
+
+// Lee config.json sin modificarlo (sin migraciones en disco ni valores por defecto).
+// Falla si el archivo no existe o no se puede interpretar.
+pub fn read_config() -> Result<AppConfig, String> {
+    let content = fs::read_to_string(config_path()?)
+        .map_err(|e| format!("Error al leer archivo de configuración: {}", e))?;
+    let mut parsed = parse_config(&content)?;
+    config_validation::sanitize(&mut parsed.config);
+    Ok(parsed.config)
 }
 
 // Función para cargar la configuración
@@ -102,20 +212,62 @@ pub fn load_config() -> AppConfig {
         return default_config;
     }
     
+    // Copia de seguridad del archivo antes de modificarlo o descartarlo
+    let backup = |motivo: &str| {
+        if let Err(e) = config_backups::create_backup(motivo) {
+            eprintln!("{}", e);
+        }
+    };
+    
     match fs::read_to_string(&config_path) {
         Ok(content) => {
-            match serde_json::from_str::<AppConfig>(&content) {
-                Ok(config) => config,
+            match parse_config(&content) {
+                Ok(mut parsed) => {
+                    // Corregir valores fuera de rango y guardar el informe para la interfaz
+                    let report = config_validation::sanitize(&mut parsed.config);
+                    let has_invalid_values = !report.is_valid();
+                    if has_invalid_values {
+                        println!("Valores de configuración no válidos restablecidos: {}", report.error_summary());
+                    }
+                    config_validation::set_load_report(report);
+                    
+                    if parsed.from_version > CONFIG_VERSION {
+                        // Archivo escrito por una versión más nueva: no sobrescribirlo
+                        println!("Configuración de una versión más reciente ({}), se usará sin guardar", parsed.from_version);
+                        return parsed.config;
+                    }
+                    
+                    let mut needs_save = parsed.from_version < CONFIG_VERSION
+                        || !parsed.dropped_fields.is_empty()
+                        || has_invalid_values;
+                    
+                    // Si el almacén de secretos está bloqueado se mantiene el archivo
+                    // original hasta poder mover los secretos
+                    if !parsed.legacy_secrets.is_empty() && !secrets::import_legacy(parsed.legacy_secrets) {
+                        needs_save = false;
+                    }
+                    
+                    if needs_save {
+                        if parsed.from_version < CONFIG_VERSION {
+                            println!("Migrando configuración de la versión {} a la {}", parsed.from_version, CONFIG_VERSION);
+                            backup(&format!("v{}", parsed.from_version));
+                        } else {
+                            println!("Campos de configuración no válidos restablecidos: {:?}", parsed.dropped_fields);
+                            backup("corregido");
+                        }
+                        write_config(parsed.config.clone()).ok();
+                    }
+                    
+                    parsed.config
+                },
                 Err(e) => {
-                    println!("Error al deserializar la configuración: {}", e);
+                    println!("Error al interpretar la configuración: {}", e);
                     // Crear copia de seguridad del archivo corrupto
-                    let backup_path = app_config_dir.join(format!("config_backup_{}.json", 
-                                                                chrono::Local::now().format("%Y%m%d_%H%M%S")));
-                    fs::copy(&config_path, &backup_path).ok();
+                    backup("corrupto");
                     
                     // Devolver configuración por defecto
                     let default_config = AppConfig::default();
-                    save_config(default_config.clone()).ok();
+                    write_config(default_config.clone()).ok();
                     default_config
                 }
             }
@@ -130,8 +282,44 @@ pub fn load_config() -> AppConfig {
     }
 }
 
-// Función para guardar la configuración
+// Falla si config.json es de una versión más nueva: guardarlo con el esquema
+// actual descartaría los campos que esta versión no conoce
+fn ensure_not_newer(path: &Path) -> Result<(), String> {
+    let version = fs::read_to_string(path).ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .map(|raw| config_migrations::detect_version(&raw));
+    match version {
+        Some(version) if version > CONFIG_VERSION => Err(format!(
+            "La configuración es de una versión más reciente de la aplicación ({}); actualiza la aplicación para modificarla",
+            version
+        )),
+        _ => Ok(()),
+    }
+}
+
+// Función para guardar la configuración, con copia de seguridad de la anterior
 pub fn save_config(config: AppConfig) -> Result<(), String> {
+    let path = config_path()?;
+    ensure_not_newer(&path)?;
+    let mut new_config = config.clone();
+    new_config.version = CONFIG_VERSION;
+    
+    // Solo se copia el archivo anterior si el guardado cambia algo
+    let unchanged = fs::read_to_string(&path).ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .map(|previous| serde_json::to_value(&new_config).ok() == Some(previous))
+        .unwrap_or(false);
+    if !unchanged {
+        if let Err(e) = config_backups::create_backup("") {
+            eprintln!("{}", e);
+        }
+    }
+    
+    write_config(config)
+}
+
+// Escribe config.json sin crear copia de seguridad
+fn write_config(config: AppConfig) -> Result<(), String> {
     // Obtenemos la ruta del directorio de configuración de la app
     let app_config_dir = app_config_dir(&tauri::Config::default())
         .expect("No se pudo determinar el directorio de configuración");
@@ -143,18 +331,48 @@ pub fn save_config(config: AppConfig) -> Result<(), String> {
     }
     
     let config_path = app_config_dir.join("config.json");
+    ensure_not_newer(&config_path)?;
+    
+    // Guardar siempre con la versión actual del esquema
+    let mut config = config;
+    config.version = CONFIG_VERSION;
     
     // Serializar la configuración
     let serialized = serde_json::to_string_pretty(&config)
         .map_err(|e| format!("Error al serializar configuración: {}", e))?;
     
-    // Guardar en el archivo
-    fs::write(&config_path, serialized)
-        .map_err(|e| format!("Error al escribir archivo de configuración: {}", e))?;
+    // Escribir en un archivo temporal y renombrarlo para que un cierre
+    // inesperado nunca deje config.json a medio escribir. El nombre es único
+    // para que dos guardados simultáneos no compartan el temporal.
+    static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+    let tmp_path = app_config_dir.join(format!(
+        "config.json.{}.{}.tmp",
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    {
+        let mut file = fs::File::create(&tmp_path)
+            .map_err(|e| format!("Error al escribir archivo de configuración: {}", e))?;
+        file.write_all(serialized.as_bytes())
+            .and_then(|_| file.sync_all())
+            .map_err(|e| format!("Error al escribir archivo de configuración: {}", e))?;
+    }
+    fs::rename(&tmp_path, &config_path)
+        .map_err(|e| {
+            let _ = fs::remove_file(&tmp_path);
+            format!("Error al reemplazar archivo de configuración: {}", e)
+        })?;
     
     Ok(())
 }
 
+// Ruta de config.json
+pub fn config_path() -> Result<PathBuf, String> {
+    app_config_dir(&tauri::Config::default())
+        .map(|dir| dir.join("config.json"))
+        .ok_or_else(|| "No se pudo determinar el directorio de configuración".to_string())
+}
+
 // Función para restablecer la configuración a valores por defecto
 pub fn reset_config() -> Result<(), String> {
     let default_config = AppConfig::default();
@@ -188,6 +406,7 @@ pub fn get_stat_display_name(stat_key: &str) -> String {
         "total_manos" => "Total Manos".to_string(),
         "bb_100" => "BB/100".to_string(),
         "win_usd" => "Ganancias USD".to_string(),
+        "por_posicion" => "Por posición".to_string(),
         _ => stat_key.to_uppercase(),
     }
 }
\ No newline at end of file
@@ -0,0 +1,96 @@
+// src-tauri/src/stats_filter.rs
+// Filtros de las stats de un jugador. Se envían al servidor como parámetros de
+// la consulta y se aplican igual a las manos de la base de datos local.
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use crate::hand_history::{BettingLimit, Hand, Position, Variant};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct StatsFilter {
+    // Ciega grande mínima y máxima, en unidades de la moneda de la mesa
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ciega_min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ciega_max: Option<f64>,
+    // Fechas de las manos, ambas incluidas
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desde: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hasta: Option<NaiveDate>,
+    // Tamaño de la mesa (2 = heads-up, 6 = 6-max, 9 = mesa completa...)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_jugadores: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variante: Option<Variant>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limite: Option<BettingLimit>,
+    // Posición del jugador en la mano
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub posicion: Option<Position>,
+}
+
+impl StatsFilter {
+    pub fn is_empty(&self) -> bool {
+        *self == StatsFilter::default()
+    }
+
+    // Indica si la mano cumple el filtro para el jugador
+    pub fn matches(&self, hand: &Hand, player: &str) -> bool {
+        let big_blind = hand.big_blind as f64 / 100.0;
+        let date = hand.date.date();
+
+        self.ciega_min.is_none_or(|min| big_blind >= min)
+            && self.ciega_max.is_none_or(|max| big_blind <= max)
+            && self.desde.is_none_or(|desde| date >= desde)
+            && self.hasta.is_none_or(|hasta| date <= hasta)
+            && self.max_jugadores.is_none_or(|max| hand.max_players == max)
+            && self.variante.is_none_or(|variante| hand.variant == variante)
+            && self.limite.is_none_or(|limite| hand.limit == limite)
+            && self.posicion.is_none_or(|posicion| hand.position(player) == Some(posicion))
+    }
+
+    // Descripción corta para mostrar junto a las stats ("NL Hold'em, BB 0.50-1.00, 6-max, BTN")
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+
+        let limit = self.limite.map(|limite| match limite {
+            BettingLimit::NoLimit => "NL",
+            BettingLimit::PotLimit => "PL",
+            BettingLimit::Fixed => "FL",
+        });
+        let variant = self.variante.map(|variante| match variante {
+            Variant::Holdem => "Hold'em",
+            Variant::Omaha => "Omaha",
+        });
+        match (limit, variant) {
+            (Some(limit), Some(variant)) => parts.push(format!("{} {}", limit, variant)),
+            (Some(limit), None) => parts.push(limit.to_string()),
+            (None, Some(variant)) => parts.push(variant.to_string()),
+            (None, None) => {},
+        }
+
+        match (self.ciega_min, self.ciega_max) {
+            (Some(min), Some(max)) => parts.push(format!("BB {:.2}-{:.2}", min, max)),
+            (Some(min), None) => parts.push(format!("BB ≥ {:.2}", min)),
+            (None, Some(max)) => parts.push(format!("BB ≤ {:.2}", max)),
+            (None, None) => {},
+        }
+
+        if let Some(max) = self.max_jugadores {
+            parts.push(format!("{}-max", max));
+        }
+        if let Some(posicion) = self.posicion {
+            parts.push(posicion.label().to_string());
+        }
+
+        match (self.desde, self.hasta) {
+            (Some(desde), Some(hasta)) => parts.push(format!("{} a {}", desde, hasta)),
+            (Some(desde), None) => parts.push(format!("desde {}", desde)),
+            (None, Some(hasta)) => parts.push(format!("hasta {}", hasta)),
+            (None, None) => {},
+        }
+
+        parts.join(", ")
+    }
+}
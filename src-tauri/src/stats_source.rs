@@ -0,0 +1,204 @@
+// src-tauri/src/stats_source.rs
+// Origen de las stats de un jugador: el servidor, la base de datos local de
+// manos importadas o la combinación de ambos
+use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use crate::api::{self, PlayerStats};
+use crate::hand_db;
+use crate::local_stats::{LocalStats, StatCounter};
+use crate::secrets::{self, SecretKind};
+use crate::settings::AppConfig;
+use crate::stats_filter::StatsFilter;
+
+// Fuente de stats elegida en la configuración
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsSourceKind {
+    #[default]
+    Servidor,
+    Local,
+    Combinada,
+}
+
+pub enum StatsSource {
+    Remote { token: String, server_url: String },
+    Local,
+    // Stats del servidor más las de las manos locales que el servidor no tenga
+    Merged { token: String, server_url: String },
+}
+
+impl StatsSource {
+    // Falla si la fuente necesita el token y el almacén de secretos está bloqueado
+    pub fn from_config(config: &AppConfig) -> Result<StatsSource, String> {
+        let server_url = config.server_url.clone();
+        Ok(match config.fuente_stats {
+            StatsSourceKind::Servidor => StatsSource::Remote { token: secrets::get_secret(SecretKind::Token)?, server_url },
+            StatsSourceKind::Local => StatsSource::Local,
+            StatsSourceKind::Combinada => StatsSource::Merged { token: secrets::get_secret(SecretKind::Token)?, server_url },
+        })
+    }
+
+    // `sala` es el código que se envía al servidor y `site` el de la base de datos
+    // local (ver profiles::local_site)
+    pub async fn get_player_stats(&self, nick: &str, sala: &str, site: &str, filter: &StatsFilter) -> Result<PlayerStats, String> {
+        match self {
+            StatsSource::Remote { token, server_url } => {
+                let mut stats = api::get_player_stats(nick.to_string(), sala.to_string(), token.clone(), server_url.clone(), filter).await?;
+                // Si el servidor no envía el desglose por posición se calcula con las manos locales
+                if stats.by_position.is_none() {
+                    stats.by_position = hand_db::get_player_stats(site, nick, filter).ok().and_then(|local| local.by_position);
+                }
+                Ok(stats)
+            },
+            StatsSource::Local => hand_db::get_player_stats(site, nick, filter),
+            StatsSource::Merged { token, server_url } => {
+                let remote = api::get_player_stats(nick.to_string(), sala.to_string(), token.clone(), server_url.clone(), filter).await;
+                let remote = match remote {
+                    Ok(stats) => stats,
+                    // Sin servidor se muestran solo las stats locales (salvo si la sesión caducó)
+                    Err(e) if !crate::auth::is_session_expired_error(&e) => {
+                        println!("Error al obtener stats del servidor, se usan las locales: {}", e);
+                        return hand_db::get_player_stats(site, nick, filter).map_err(|_| e);
+                    },
+                    Err(e) => return Err(e),
+                };
+
+                let local = match &remote.hand_ids {
+                    Some(ids) => {
+                        let excluded: HashSet<String> = ids.iter().cloned().collect();
+                        hand_db::player_stats_from_hands(site, nick, &excluded, filter)?
+                    },
+                    None if !filter.is_empty() => hand_db::player_stats_from_hands(site, nick, &HashSet::new(), filter)?,
+                    None => hand_db::player_stats(site, nick)?,
+                };
+
+                Ok(match local {
+                    Some(local) => merge_stats(remote, &local),
+                    None => remote,
+                })
+            },
+        }
+    }
+}
+
+// Combina las stats del servidor con las locales ponderando cada stat por el
+// número de manos de cada muestra. El servidor no envía oportunidades, así que
+// el tamaño de la muestra es el total de manos en los dos lados.
+fn merge_stats(mut remote: PlayerStats, local: &LocalStats) -> PlayerStats {
+    let remote_hands = remote.total_manos.trim().parse::<u32>().unwrap_or(0);
+    let local_hands = local.total_manos;
+
+    let weighted = |remote_value: Option<f64>, local_value: Option<f64>| -> Option<f64> {
+        match (remote_value.filter(|_| remote_hands > 0), local_value.filter(|_| local_hands > 0)) {
+            (Some(r), Some(l)) => Some((r * remote_hands as f64 + l * local_hands as f64) / (remote_hands + local_hands) as f64),
+            (Some(r), None) => Some(r),
+            (None, Some(l)) => Some(l),
+            (None, None) => None,
+        }
+    };
+    let pct = |remote_value: &str, counter: StatCounter| -> String {
+        weighted(parse_stat(remote_value), counter.pct())
+            .map(|value| format!("{:.1}", value))
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    let bb_100 = weighted(parse_stat(&remote.bb_100), local.bb_100())
+        .map(|value| format!("{:.2}", value))
+        .unwrap_or_else(|| "-".to_string());
+    let win_usd = parse_stat(&remote.win_usd).unwrap_or(0.0) + local.ganancia as f64 / 100.0;
+    // El desglose por posición del servidor tiene prioridad sobre el local
+    let by_position = remote.by_position.take().or_else(|| local.to_player_stats().by_position);
+
+    PlayerStats {
+        vpip: pct(&remote.vpip, local.vpip),
+        pfr: pct(&remote.pfr, local.pfr),
+        three_bet: pct(&remote.three_bet, local.three_bet),
+        fold_to_3bet_pct: pct(&remote.fold_to_3bet_pct, local.fold_to_3bet),
+        wtsd: pct(&remote.wtsd, local.wtsd),
+        wsd: pct(&remote.wsd, local.wsd),
+        cbet_flop: pct(&remote.cbet_flop, local.cbet_flop),
+        cbet_turn: pct(&remote.cbet_turn, local.cbet_turn),
+        fold_to_flop_cbet_pct: pct(&remote.fold_to_flop_cbet_pct, local.fold_to_flop_cbet),
+        fold_to_turn_cbet_pct: pct(&remote.fold_to_turn_cbet_pct, local.fold_to_turn_cbet),
+        wwsf: pct(&remote.wwsf, local.wwsf),
+        total_manos: (remote_hands + local_hands).to_string(),
+        bb_100,
+        win_usd: format!("{:.2}", win_usd),
+        hand_ids: None,
+        by_position,
+        // Las stats que solo calcula el servidor se mantienen tal cual
+        ..remote
+    }
+}
+
+// Valor numérico de una stat del servidor ("-" o vacío = sin dato)
+fn parse_stat(value: &str) -> Option<f64> {
+    value.trim().trim_end_matches('%').parse::<f64>().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote_stats(total_manos: &str, vpip: &str, win_usd: &str) -> PlayerStats {
+        let stat = |value: &str| serde_json::Value::String(value.to_string());
+        serde_json::from_value(serde_json::json!({
+            "player_name": "Villano",
+            "vpip": stat(vpip),
+            "pfr": "-",
+            "three_bet": "10.0",
+            "fold_to_3bet_pct": "-",
+            "wtsd": "-",
+            "wsd": "-",
+            "cbet_flop": "-",
+            "cbet_turn": "-",
+            "fold_to_flop_cbet_pct": "-",
+            "fold_to_turn_cbet_pct": "-",
+            "limp_pct": "12.5",
+            "wwsf": "-",
+            "total_manos": stat(total_manos),
+            "bb_100": "-",
+            "win_usd": stat(win_usd),
+        })).unwrap()
+    }
+
+    fn local_stats(total_manos: u32, vpip: u32, ganancia: i64) -> LocalStats {
+        LocalStats {
+            player_name: "villano".to_string(),
+            total_manos,
+            vpip: StatCounter { veces: vpip, oportunidades: total_manos },
+            ganancia,
+            ganancia_bb: 10.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn merged_stats_are_weighted_by_hands() {
+        let merged = merge_stats(remote_stats("300", "20.0%", "12.50"), &local_stats(100, 40, 250));
+        assert_eq!(merged.total_manos, "400");
+        // (20 * 300 + 40 * 100) / 400
+        assert_eq!(merged.vpip, "25.0");
+        assert_eq!(merged.win_usd, "15.00");
+        // Sin datos locales se conserva el valor del servidor
+        assert_eq!(merged.three_bet, "10.0");
+        assert_eq!(merged.pfr, "-");
+        assert_eq!(merged.limp_pct.as_deref(), Some("12.5"));
+        assert_eq!(merged.player_name, "Villano");
+        // El servidor no envía bb/100: se usa el local
+        assert_eq!(merged.bb_100, "10.00");
+    }
+
+    #[test]
+    fn merge_ignores_empty_samples() {
+        let merged = merge_stats(remote_stats("0", "55.0", "-"), &local_stats(10, 3, 0));
+        assert_eq!(merged.total_manos, "10");
+        assert_eq!(merged.vpip, "30.0");
+        assert_eq!(merged.win_usd, "0.00");
+
+        let merged = merge_stats(remote_stats("50", "20.0", "1"), &LocalStats::default());
+        assert_eq!(merged.total_manos, "50");
+        assert_eq!(merged.vpip, "20.0");
+        assert_eq!(merged.bb_100, "-");
+    }
+}
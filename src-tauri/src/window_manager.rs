@@ -5,6 +5,10 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use crate::settings::AppConfig;
 use crate::ocr_bridge::{initialize_ocr, capture_and_read_nick};
 use crate::error::AppError;
+use crate::secrets::{self, SecretKind};
+use crate::profiles;
+use crate::stats_source::StatsSource;
+use crate::hand_history::Position;
 use regex::Regex;
 use once_cell::sync::Lazy;
 
@@ -62,22 +66,13 @@ pub fn find_poker_tables() -> Vec<(u32, String)> {
     unsafe {
         use windows_sys::Win32::UI::WindowsAndMessaging::*;
         use windows_sys::Win32::Foundation::*;
-        use std::ptr::null_mut;
-        use std::ffi::OsString;
-        use std::os::windows::ffi::OsStringExt;
         
         let mut tables = Vec::new();
         
         extern "system" fn enum_windows_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
             unsafe {
                 if IsWindowVisible(hwnd) != 0 {
-                    let mut title: [u16; 512] = [0; 512];
-                    let len = GetWindowTextW(hwnd, title.as_mut_ptr(), title.len() as i32);
-                    
-                    if len > 0 {
-                        let title_os_string = OsString::from_wide(&title[0..len as usize]);
-                        let title_str = title_os_string.to_string_lossy().to_string();
-                        
+                    if let Some(title_str) = window_text(hwnd) {
                         if is_poker_table(&title_str) {
                             let tables = &mut *(lparam as *mut Vec<(u32, String)>);
                             tables.push((hwnd as u32, title_str));
@@ -112,8 +107,6 @@ pub fn get_window_under_cursor() -> Option<(u32, String)> {
     unsafe {
         use windows_sys::Win32::UI::WindowsAndMessaging::*;
         use windows_sys::Win32::Foundation::*;
-        use std::ffi::OsString;
-        use std::os::windows::ffi::OsStringExt;
         
         let mut point = std::mem::zeroed::<POINT>();
         if GetCursorPos(&mut point) == 0 {
@@ -126,13 +119,7 @@ pub fn get_window_under_cursor() -> Option<(u32, String)> {
         }
         
         // Obtener título de la ventana
-        let mut title: [u16; 512] = [0; 512];
-        let len = GetWindowTextW(hwnd, title.as_mut_ptr(), title.len() as i32);
-        
-        if len > 0 {
-            let title_os_string = OsString::from_wide(&title[0..len as usize]);
-            let title_str = title_os_string.to_string_lossy().to_string();
-            
+        if let Some(title_str) = window_text(hwnd) {
             if is_poker_table(&title_str) {
                 return Some((hwnd as u32, title_str));
             }
@@ -140,13 +127,7 @@ pub fn get_window_under_cursor() -> Option<(u32, String)> {
             // Verificar ventana padre
             let parent_hwnd = GetParent(hwnd);
             if parent_hwnd != 0 {
-                let mut parent_title: [u16; 512] = [0; 512];
-                let parent_len = GetWindowTextW(parent_hwnd, parent_title.as_mut_ptr(), parent_title.len() as i32);
-                
-                if parent_len > 0 {
-                    let parent_title_os_string = OsString::from_wide(&parent_title[0..parent_len as usize]);
-                    let parent_title_str = parent_title_os_string.to_string_lossy().to_string();
-                    
+                if let Some(parent_title_str) = window_text(parent_hwnd) {
                     if is_poker_table(&parent_title_str) {
                         return Some((parent_hwnd as u32, parent_title_str));
                     }
@@ -165,8 +146,49 @@ pub fn get_window_under_cursor() -> Option<(u32, String)> {
     }
 }
 
+// Lee el título de una ventana (None si no tiene)
+#[cfg(target_os = "windows")]
+unsafe fn window_text(hwnd: windows_sys::Win32::Foundation::HWND) -> Option<String> {
+    use windows_sys::Win32::UI::WindowsAndMessaging::GetWindowTextW;
+    
+    let mut title: [u16; 512] = [0; 512];
+    let len = GetWindowTextW(hwnd, title.as_mut_ptr(), title.len() as i32);
+    if len > 0 {
+        Some(String::from_utf16_lossy(&title[0..len as usize]))
+    } else {
+        None
+    }
+}
+
+// Obtiene el título de una ventana
+pub fn get_window_title(hwnd: u32) -> Option<String> {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        window_text(hwnd as windows_sys::Win32::Foundation::HWND)
+    }
+    
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = hwnd;
+        None
+    }
+}
+
 // Analiza una mesa específica
 pub async fn analyze_table(hwnd: u32, config: AppConfig, manual_nick: Option<String>, force_new_capture: bool) -> Result<String, String> {
+    // Aplicar el perfil de la sala detectada en el título de la mesa
+    let title = get_window_title(hwnd);
+    let config = match &title {
+        Some(title) => {
+            let (effective, profile_name) = profiles::resolve_for_title(&config, title);
+            println!("Perfil de configuración para '{}': {}", title, profile_name);
+            effective
+        },
+        None => config,
+    };
+    // Sala de las manos importadas y de las notas (sala_default es el código del servidor)
+    let site = profiles::local_site(&config, &config.sala_default, title.as_deref());
+    
     // Obtener nick del jugador
     let nick = if let Some(nick_str) = manual_nick {
         nick_str
@@ -195,13 +217,12 @@ pub async fn analyze_table(hwnd: u32, config: AppConfig, manual_nick: Option<Str
         }
     };
     
-    // Obtener estadísticas del jugador
-    let stats_result = crate::api::get_player_stats(
-        nick.clone(), 
-        config.sala_default.clone(), 
-        config.token.clone(), 
-        config.server_url.clone()
-    ).await;
+    // Obtener estadísticas del jugador de la fuente configurada
+    let source = StatsSource::from_config(&config)
+        .map_err(|e| format!("Error al obtener estadísticas: {}", e))?;
+    let stats_result = source
+        .get_player_stats(&nick, &config.sala_default, &site, &config.filtro_stats)
+        .await;
     
     let stats = match stats_result {
         Ok(stats_data) => stats_data,
@@ -213,23 +234,52 @@ pub async fn analyze_table(hwnd: u32, config: AppConfig, manual_nick: Option<Str
     
     // Incluir stats si están habilitadas
     if config.mostrar_stats {
+        if !config.filtro_stats.is_empty() {
+            response.push_str(&format!("Filtro: {}\n", config.filtro_stats.describe()));
+        }
         let stats_text = format_stats(&stats, &config);
         response.push_str(&stats_text);
         response.push_str("\n\n");
     }
     
     // Incluir análisis si está habilitado
-    if config.mostrar_analisis && !config.openai_api_key.is_empty() {
-        match crate::api::analyze_stats(stats.clone(), config.openai_api_key.clone()).await {
-            Ok(analysis) => {
-                response.push_str(&analysis);
-            },
-            Err(e) => {
+    let openai_api_key = match secrets::get_secret(SecretKind::OpenaiApiKey) {
+        Ok(key) => key,
+        Err(e) => {
+            if config.mostrar_analisis {
                 response.push_str(&format!("Error en análisis: {}", e));
             }
+            String::new()
+        },
+    };
+    // La API key se comprueba antes de descontar el análisis del límite diario
+    if config.mostrar_analisis && crate::api::check_api_key(&openai_api_key).is_ok() {
+        if let Err(e) = crate::entitlements::consume_analysis() {
+            response.push_str(&e.to_string());
+        } else {
+            match crate::api::analyze_stats(stats.clone(), openai_api_key, config.plantilla_analisis.clone()).await {
+                Ok(analysis) => {
+                    response.push_str(&analysis);
+                },
+                Err(e) => {
+                    response.push_str(&format!("Error en análisis: {}", e));
+                }
+            }
         }
     }
     
+    // Añadir las notas y etiquetas guardadas del jugador
+    match crate::notes::get_player(&site, &nick) {
+        Ok(Some(player)) => {
+            if !response.is_empty() && !response.ends_with("\n\n") {
+                response.push_str("\n\n");
+            }
+            response.push_str(&crate::notes::format_for_output(&player));
+        },
+        Ok(None) => {},
+        Err(e) => eprintln!("Error al leer notas del jugador: {}", e),
+    }
+    
     // Guardar en historial
     // TODO: Implementar guardar en historial
     
@@ -315,9 +365,17 @@ fn format_stats(stats: &crate::api::PlayerStats, config: &AppConfig) -> String {
     
     // Construir partes del resumen
     let mut stats_parts = Vec::new();
+    // El desglose por posición va en líneas aparte, una por posición
+    let mut position_lines = Vec::new();
     
     for stat_key in filtered_stats {
         if let Some(format_str) = stats_format.get(stat_key) {
+            if stat_key == "por_posicion" {
+                for (position, row) in stats.by_position.iter().flatten() {
+                    position_lines.push(format_str.replace("{value}", &format_position_stats(*position, row)));
+                }
+                continue;
+            }
             let value: &str = match stat_key.as_str() {
                 "vpip" => &stats.vpip,
                 "pfr" => &stats.pfr,
@@ -351,7 +409,25 @@ fn format_stats(stats: &crate::api::PlayerStats, config: &AppConfig) -> String {
         }
     }
     
-    stats_parts.join(" ")
+    let mut lines = vec![stats_parts.join(" ")];
+    lines.extend(position_lines);
+    lines.retain(|line| !line.is_empty());
+    lines.join("\n")
+}
+
+// Línea del desglose de una posición: "BTN VPIP:35.0 PFR:28.0 RFI:40.0 3B:9.0 F3B:55.0 ST:42.0"
+fn format_position_stats(position: Position, stats: &crate::api::PositionalStats) -> String {
+    let mut line = format!(
+        "{} VPIP:{} PFR:{} RFI:{} 3B:{} F3B:{}",
+        position.label(), stats.vpip, stats.pfr, stats.rfi, stats.three_bet, stats.fold_to_3bet_pct
+    );
+    if position.is_steal_position() {
+        line.push_str(&format!(" ST:{}", stats.steal_pct));
+    }
+    if position.is_blind() {
+        line.push_str(&format!(" FST:{}", stats.fold_to_steal_pct));
+    }
+    line
 }
 
 // Da foco a una ventana
@@ -10,8 +10,8 @@ import Historial from "./pages/Historial"; // Crea Historial.jsx (placeholder)
 import Configuracion from "./pages/Configuracion"; // Crea Configuracion.jsx (placeholder)
 
 function PrivateRoute({ children }) {
-  const { token } = useAuth();
-  return token ? children : <Navigate to="/login" />;
+  const { isAuthenticated } = useAuth();
+  return isAuthenticated ? children : <Navigate to="/login" />;
 }
 
 function Layout({ children }) {
@@ -26,7 +26,7 @@ import {
 import { FaSync, FaSearch, FaDesktop, FaMouse, FaChess, FaCopy, FaPlay, FaStop } from 'react-icons/fa';
 import SectionHeader from '../ui/SectionHeader';
 import { useMesas } from '../../hooks/useMesas';
-import { loadConfig, getWindowUnderCursor, analyzeTable, copyToClipboard, clearNickCache, setupPythonEnvironment, checkOcrAvailable } from '../../services/tauri';
+import { loadConfig, onConfigChanged, getWindowUnderCursor, analyzeTable, copyToClipboard, clearNickCache, setupPythonEnvironment, checkOcrAvailable } from '../../services/tauri';
 
 const MesasDetectadas = () => {
   const [selectedMesa, setSelectedMesa] = useState(null);
@@ -114,6 +114,14 @@ const MesasDetectadas = () => {
     fetchConfig();
   }, [toast]);
 
+  // Mantener la configuración al día cuando cambia en el backend
+  useEffect(() => {
+    const unlistenPromise = onConfigChanged(({ config: newConfig }) => setConfig(newConfig));
+    return () => {
+      unlistenPromise.then(unlisten => unlisten());
+    };
+  }, []);
+
   // Efecto para modo automático
   useEffect(() => {
     let intervalId = null;
@@ -129,7 +137,7 @@ const MesasDetectadas = () => {
         if (tables && tables.length > 0) {
           const firstTable = tables[0];
           // Analizar sin forzar nueva captura para aprovechar caché
-          await handleAnalyzeMesaById(firstTable.id, false);
+          await handleAnalyzeMesaById(firstTable.id, false, true);
         }
       } catch (err) {
         console.error("Error en modo automático:", err);
@@ -165,13 +173,13 @@ const MesasDetectadas = () => {
   };
   
   // Función para analizar mesa por ID
-  const handleAnalyzeMesaById = async (mesaId, forceNew = true) => {
+  const handleAnalyzeMesaById = async (mesaId, forceNew = true, automatic = false) => {
     if (!config) return;
     
     try {
       setAnalyzing(true);
       
-      const result = await analyzeTable(mesaId, null, forceNew, config);
+      const result = await analyzeTable(mesaId, null, forceNew, config, automatic);
       setAnalyzeResult(result);
       
       toast({
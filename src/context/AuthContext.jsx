@@ -1,11 +1,10 @@
 import React, { createContext, useContext, useState, useEffect } from "react";
-import { login as apiLogin, logout as apiLogout, getUser, getToken } from "../services/auth";
+import { login as apiLogin, logout as apiLogout, getUser } from "../services/auth";
 
 const AuthContext = createContext();
 
 export function AuthProvider({ children }) {
   const [user, setUser] = useState(() => getUser());
-  const [token, setToken] = useState(() => getToken());
   const [loading, setLoading] = useState(false);
 
   const login = async (email, password) => {
@@ -13,7 +12,6 @@ export function AuthProvider({ children }) {
     try {
       const data = await apiLogin(email, password);
       setUser(data.usuario);
-      setToken(data.token);
       return data.usuario;
     } catch (err) {
       throw err;
@@ -25,28 +23,20 @@ export function AuthProvider({ children }) {
   const logout = () => {
     apiLogout();
     setUser(null);
-    setToken("");
   };
 
-  // Restaurar sesión al iniciar la aplicación
+  // Limpiar el token que guardaban versiones anteriores; ahora solo lo conoce el backend
   useEffect(() => {
-    const savedToken = getToken();
-    const savedUser = getUser();
-    
-    if (savedToken && savedUser) {
-      setToken(savedToken);
-      setUser(savedUser);
-    }
+    localStorage.removeItem("token");
   }, []);
 
   return (
     <AuthContext.Provider value={{ 
       user, 
-      token, 
       login, 
       logout, 
       loading, 
-      isAuthenticated: !!token 
+      isAuthenticated: !!user 
     }}>
       {children}
     </AuthContext.Provider>
@@ -1,13 +1,11 @@
 // src/hooks/useMesas.js
 import { useState, useEffect, useCallback } from 'react';
 import { findPokerTables, analyzeTable } from '../services/tauri';
-import { useAuth } from '../context/AuthContext';
 
 export function useMesas() {
   const [mesas, setMesas] = useState([]);
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState(null);
-  const { token } = useAuth();
   
   const refreshMesas = useCallback(async () => {
     try {
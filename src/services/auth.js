@@ -1,18 +1,17 @@
 // src/services/auth.js
-import { loginUser } from './tauri';
+import { loginUser, logoutUser } from './tauri';
 
 export async function login(email, password) {
   try {
     // Llamada al comando Tauri para iniciar sesión
     const response = await loginUser(email, password);
     
-    // Guardar datos del usuario en localStorage
-    if (response && response.token && response.usuario) {
+    // Guardar datos del usuario en localStorage (el token se queda en el backend)
+    if (response && response.usuario) {
       localStorage.setItem("user", JSON.stringify(response.usuario));
-      localStorage.setItem("token", response.token);
     }
     
-    return response; // Devuelve { mensaje, token, usuario }
+    return response; // Devuelve { mensaje, usuario }
   } catch (error) {
     console.error("Error en inicio de sesión:", error);
     throw new Error(error || "Error de autenticación");
@@ -20,19 +19,18 @@ export async function login(email, password) {
 }
 
 export function logout() {
+  // Cerrar también la sesión del backend (token guardado en configuración)
+  logoutUser().catch(console.error);
   localStorage.removeItem("user");
+  // Token guardado por versiones anteriores
   localStorage.removeItem("token");
 }
 
-export function getToken() {
-  return localStorage.getItem("token") || "";
-}
-
 export function getUser() {
   const user = localStorage.getItem("user");
   return user ? JSON.parse(user) : null;
 }
 
 export function isAuthenticated() {
-  return !!getToken();
+  return !!getUser();
 }
\ No newline at end of file
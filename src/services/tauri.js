@@ -2,6 +2,19 @@
 import { invoke } from '@tauri-apps/api';
 import { readText, writeText } from '@tauri-apps/api/clipboard';
 import { open, save } from '@tauri-apps/api/dialog';
+import { listen } from '@tauri-apps/api/event';
+
+// Los límites de la suscripción llegan como { code, tier, limit, message };
+// se conservan en el Error para que la interfaz pueda distinguirlos
+const commandError = (prefix, error) => {
+  if (error && typeof error === 'object' && error.code) {
+    const err = new Error(`${prefix}: ${error.message}`);
+    err.code = error.code;
+    err.details = error;
+    return err;
+  }
+  return new Error(`${prefix}: ${error}`);
+};
 
 // Funciones para gestión de ventanas de póker
 export const findPokerTables = async () => {
@@ -13,17 +26,18 @@ export const findPokerTables = async () => {
   }
 };
 
-export const analyzeTable = async (hwnd, manualNick = null, forceNewCapture = false, config) => {
+export const analyzeTable = async (hwnd, manualNick = null, forceNewCapture = false, config, automatic = false) => {
   try {
     return await invoke('analyze_table', { 
       hwnd, 
       manualNick, 
       forceNewCapture, 
-      config 
+      config,
+      automatic
     });
   } catch (error) {
     console.error('Error al analizar mesa:', error);
-    throw new Error(`Error al analizar mesa: ${error}`);
+    throw commandError('Error al analizar mesa', error);
   }
 };
 
@@ -55,6 +69,161 @@ export const loadConfig = async () => {
   }
 };
 
+// Escucha los cambios de configuración (guardados desde la app o editados en disco).
+// Devuelve la función para dejar de escuchar.
+export const onConfigChanged = async (callback) => {
+  return await listen('config_changed', (event) => callback(event.payload));
+};
+
+export const validateConfig = async (config) => {
+  try {
+    return await invoke('validate_config', { config });
+  } catch (error) {
+    console.error('Error al validar configuración:', error);
+    throw new Error(`Error al validar configuración: ${error}`);
+  }
+};
+
+export const listConfigBackups = async () => {
+  try {
+    return await invoke('list_config_backups');
+  } catch (error) {
+    console.error('Error al listar copias de seguridad:', error);
+    throw new Error(`Error al listar copias de seguridad: ${error}`);
+  }
+};
+
+export const restoreConfigBackup = async (nombre) => {
+  try {
+    return await invoke('restore_config_backup', { nombre });
+  } catch (error) {
+    console.error('Error al restaurar copia de seguridad:', error);
+    throw new Error(`Error al restaurar copia de seguridad: ${error}`);
+  }
+};
+
+export const getConfigValidation = async () => {
+  try {
+    return await invoke('get_config_validation');
+  } catch (error) {
+    console.error('Error al obtener validación de configuración:', error);
+    return { errors: [], warnings: [] };
+  }
+};
+
+// Funciones para perfiles por sala
+export const getProfileForTable = async (hwnd) => {
+  try {
+    return await invoke('get_profile_for_table', { hwnd });
+  } catch (error) {
+    console.error('Error al obtener perfil de la mesa:', error);
+    throw new Error(`Error al obtener perfil de la mesa: ${error}`);
+  }
+};
+
+export const getKnownSites = async () => {
+  try {
+    return await invoke('get_known_sites');
+  } catch (error) {
+    console.error('Error al obtener salas:', error);
+    return [];
+  }
+};
+
+// Funciones para compartir ajustes entre equipos
+export const exportSettingsBundle = async (path) => {
+  try {
+    return await invoke('export_settings_bundle', { path });
+  } catch (error) {
+    console.error('Error al exportar ajustes:', error);
+    throw new Error(`Error al exportar ajustes: ${error}`);
+  }
+};
+
+export const previewSettingsBundle = async (path) => {
+  try {
+    return await invoke('preview_settings_bundle', { path });
+  } catch (error) {
+    console.error('Error al leer paquete de ajustes:', error);
+    throw new Error(`Error al leer paquete de ajustes: ${error}`);
+  }
+};
+
+export const importSettingsBundle = async (path, campos = null) => {
+  try {
+    return await invoke('import_settings_bundle', { path, campos });
+  } catch (error) {
+    console.error('Error al importar ajustes:', error);
+    throw new Error(`Error al importar ajustes: ${error}`);
+  }
+};
+
+// Capacidades de la suscripción activa
+export const getEntitlements = async () => {
+  try {
+    return await invoke('get_entitlements');
+  } catch (error) {
+    console.error('Error al obtener suscripción:', error);
+    throw new Error(`Error al obtener suscripción: ${error}`);
+  }
+};
+
+// Funciones para el almacén de secretos (token y API key nunca viajan en la configuración)
+export const getSecretsStatus = async () => {
+  try {
+    return await invoke('get_secrets_status');
+  } catch (error) {
+    console.error('Error al obtener estado de secretos:', error);
+    throw new Error(`Error al obtener estado de secretos: ${error}`);
+  }
+};
+
+export const unlockSecrets = async (passphrase) => {
+  try {
+    return await invoke('unlock_secrets', { passphrase });
+  } catch (error) {
+    console.error('Error al desbloquear secretos:', error);
+    throw new Error(`Error al desbloquear secretos: ${error}`);
+  }
+};
+
+// Descarta el almacén de secretos cuando no se puede abrir (se pierden los secretos guardados)
+export const resetSecretsStore = async (confirmar) => {
+  try {
+    return await invoke('reset_secrets_store', { confirmar });
+  } catch (error) {
+    console.error('Error al descartar el almacén de secretos:', error);
+    throw new Error(`Error al descartar el almacén de secretos: ${error}`);
+  }
+};
+
+export const setSecret = async (kind, value) => {
+  try {
+    return await invoke('set_secret', { kind, value });
+  } catch (error) {
+    console.error('Error al guardar secreto:', error);
+    throw new Error(`Error al guardar secreto: ${error}`);
+  }
+};
+
+export const clearSecret = async (kind) => {
+  try {
+    return await invoke('clear_secret', { kind });
+  } catch (error) {
+    console.error('Error al eliminar secreto:', error);
+    throw new Error(`Error al eliminar secreto: ${error}`);
+  }
+};
+
+export const testSecret = async (kind) => {
+  try {
+    return await invoke('test_secret', { kind });
+  } catch (error) {
+    console.error('Error al comprobar secreto:', error);
+    throw new Error(`Error al comprobar secreto: ${error}`);
+  }
+};
+
 // Funciones para autenticación
 export const loginUser = async (email, password) => {
   try {
@@ -65,10 +234,37 @@ export const loginUser = async (email, password) => {
   }
 };
 
+export const logoutUser = async () => {
+  try {
+    return await invoke('logout');
+  } catch (error) {
+    console.error('Error al cerrar sesión:', error);
+    throw new Error(`Error al cerrar sesión: ${error}`);
+  }
+};
+
+export const getCurrentUser = async () => {
+  try {
+    return await invoke('get_current_user');
+  } catch (error) {
+    console.error('Error al obtener usuario actual:', error);
+    return null;
+  }
+};
+
+export const verifySession = async () => {
+  try {
+    return await invoke('verify_session');
+  } catch (error) {
+    console.error('Error al verificar sesión:', error);
+    return false;
+  }
+};
+
 // Funciones para análisis y API
-export const getPlayerStats = async (nick, sala) => {
+export const getPlayerStats = async (nick, sala, filter = null) => {
   try {
-    return await invoke('get_player_stats', { nick, sala });
+    return await invoke('get_player_stats', { nick, sala, filter });
   } catch (error) {
     console.error('Error al obtener estadísticas del jugador:', error);
     throw new Error(`Error al obtener estadísticas: ${error}`);
@@ -80,7 +276,258 @@ export const analyzeStats = async (data) => {
     return await invoke('analyze_stats', { data });
   } catch (error) {
     console.error('Error al analizar estadísticas:', error);
-    throw new Error(`Error al analizar estadísticas: ${error}`);
+    throw commandError('Error al analizar estadísticas', error);
+  }
+};
+
+// Funciones para notas y etiquetas de jugadores
+export const getPlayerNotes = async (sala, nick) => {
+  try {
+    return await invoke('get_player_notes', { sala, nick });
+  } catch (error) {
+    console.error('Error al obtener notas del jugador:', error);
+    throw new Error(`Error al obtener notas del jugador: ${error}`);
+  }
+};
+
+export const addPlayerNote = async (sala, nick, texto) => {
+  try {
+    return await invoke('add_player_note', { sala, nick, texto });
+  } catch (error) {
+    console.error('Error al añadir nota:', error);
+    throw new Error(`Error al añadir nota: ${error}`);
+  }
+};
+
+export const updatePlayerNote = async (sala, nick, id, texto) => {
+  try {
+    return await invoke('update_player_note', { sala, nick, id, texto });
+  } catch (error) {
+    console.error('Error al modificar nota:', error);
+    throw new Error(`Error al modificar nota: ${error}`);
+  }
+};
+
+export const deletePlayerNote = async (sala, nick, id) => {
+  try {
+    return await invoke('delete_player_note', { sala, nick, id });
+  } catch (error) {
+    console.error('Error al eliminar nota:', error);
+    throw new Error(`Error al eliminar nota: ${error}`);
+  }
+};
+
+export const setPlayerTags = async (sala, nick, etiquetas) => {
+  try {
+    return await invoke('set_player_tags', { sala, nick, etiquetas });
+  } catch (error) {
+    console.error('Error al asignar etiquetas:', error);
+    throw new Error(`Error al asignar etiquetas: ${error}`);
+  }
+};
+
+export const searchPlayerNotes = async (query = '', sala = null, etiqueta = null) => {
+  try {
+    return await invoke('search_player_notes', { query, sala, etiqueta });
+  } catch (error) {
+    console.error('Error al buscar notas:', error);
+    throw new Error(`Error al buscar notas: ${error}`);
+  }
+};
+
+export const getNoteTags = async () => {
+  try {
+    return await invoke('get_note_tags');
+  } catch (error) {
+    console.error('Error al obtener etiquetas:', error);
+    throw new Error(`Error al obtener etiquetas: ${error}`);
+  }
+};
+
+export const saveNoteTag = async (tag) => {
+  try {
+    return await invoke('save_note_tag', { tag });
+  } catch (error) {
+    console.error('Error al guardar etiqueta:', error);
+    throw new Error(`Error al guardar etiqueta: ${error}`);
+  }
+};
+
+export const deleteNoteTag = async (nombre) => {
+  try {
+    return await invoke('delete_note_tag', { nombre });
+  } catch (error) {
+    console.error('Error al eliminar etiqueta:', error);
+    throw new Error(`Error al eliminar etiqueta: ${error}`);
+  }
+};
+
+// Escucha los cambios de notas recibidos del equipo.
+// Devuelve la función para dejar de escuchar.
+export const onNotesSynced = async (callback) => {
+  return await listen('notes_synced', (event) => callback(event.payload));
+};
+
+// options: { formato: 'hm3_xml' | 'pt4_csv', sala_por_defecto, mapa_salas, duplicados: 'omitir' | 'anadir' | 'reemplazar', simulacion }
+export const importPlayerNotes = async (path, options = {}) => {
+  try {
+    return await invoke('import_player_notes', { path, options });
+  } catch (error) {
+    console.error('Error al importar notas:', error);
+    throw new Error(`Error al importar notas: ${error}`);
+  }
+};
+
+export const syncNotes = async () => {
+  try {
+    return await invoke('sync_notes');
+  } catch (error) {
+    console.error('Error al sincronizar notas:', error);
+    throw new Error(`Error al sincronizar notas: ${error}`);
+  }
+};
+
+export const getNotesSyncStatus = async () => {
+  try {
+    return await invoke('get_notes_sync_status');
+  } catch (error) {
+    console.error('Error al obtener estado de sincronización:', error);
+    throw new Error(`Error al obtener estado de sincronización: ${error}`);
+  }
+};
+
+export const getNoteConflicts = async () => {
+  try {
+    return await invoke('get_note_conflicts');
+  } catch (error) {
+    console.error('Error al obtener conflictos de notas:', error);
+    throw new Error(`Error al obtener conflictos de notas: ${error}`);
+  }
+};
+
+export const resolveNoteConflict = async (id, conservarLocal) => {
+  try {
+    return await invoke('resolve_note_conflict', { id, conservarLocal });
+  } catch (error) {
+    console.error('Error al resolver conflicto de nota:', error);
+    throw new Error(`Error al resolver conflicto de nota: ${error}`);
+  }
+};
+
+export const parseHandHistoryFile = async (path) => {
+  try {
+    return await invoke('parse_hand_history_file', { path });
+  } catch (error) {
+    console.error('Error al leer historial de manos:', error);
+    throw new Error(`Error al leer historial de manos: ${error}`);
+  }
+};
+
+export const importHandHistories = async () => {
+  try {
+    return await invoke('import_hand_histories');
+  } catch (error) {
+    console.error('Error al importar historiales:', error);
+    throw new Error(`Error al importar historiales: ${error}`);
+  }
+};
+
+export const getHandImportStatus = async () => {
+  try {
+    return await invoke('get_hand_import_status');
+  } catch (error) {
+    console.error('Error al obtener estado de importación:', error);
+    throw new Error(`Error al obtener estado de importación: ${error}`);
+  }
+};
+
+// Progreso por archivo: { archivo, indice, total, nuevas, duplicadas, errores }
+export const onHandImportProgress = async (callback) => {
+  return await listen('hand_import_progress', (event) => callback(event.payload));
+};
+
+export const onHandsImported = async (callback) => {
+  return await listen('hands_imported', (event) => callback(event.payload));
+};
+
+export const getLocalDbPlayerStats = async (nick, sala, filter = null) => {
+  try {
+    return await invoke('get_local_db_player_stats', { nick, sala, filter });
+  } catch (error) {
+    console.error('Error al obtener stats locales:', error);
+    throw new Error(`Error al obtener stats locales: ${error}`);
+  }
+};
+
+export const computeLocalStats = async (paths, nick, filter = null) => {
+  try {
+    return await invoke('compute_local_stats', { paths, nick, filter });
+  } catch (error) {
+    console.error('Error al calcular stats locales:', error);
+    throw new Error(`Error al calcular stats locales: ${error}`);
+  }
+};
+
+export const getLocalPlayerStats = async (paths, nick, filter = null) => {
+  try {
+    return await invoke('get_local_player_stats', { paths, nick, filter });
+  } catch (error) {
+    console.error('Error al calcular stats locales:', error);
+    throw new Error(`Error al calcular stats locales: ${error}`);
+  }
+};
+
+export const getHeroReport = async (filter = null, analizar = false) => {
+  try {
+    return await invoke('get_hero_report', { filter, analizar });
+  } catch (error) {
+    console.error('Error al generar el informe:', error);
+    throw commandError('Error al generar el informe', error);
+  }
+};
+
+export const calculateEquity = async (manos, board = null, muertas = null, iteraciones = null) => {
+  try {
+    return await invoke('calculate_equity', { manos, board, muertas, iteraciones });
+  } catch (error) {
+    console.error('Error al calcular la equidad:', error);
+    throw new Error(`Error al calcular la equidad: ${error}`);
+  }
+};
+
+export const getWinningsGraph = async (filter = null, eje = null, maxPuntos = null) => {
+  try {
+    return await invoke('get_winnings_graph', { filter, eje, maxPuntos });
+  } catch (error) {
+    console.error('Error al generar la gráfica:', error);
+    throw new Error(`Error al generar la gráfica: ${error}`);
+  }
+};
+
+export const getHandReplay = async (sala, handId) => {
+  try {
+    return await invoke('get_hand_replay', { sala, handId });
+  } catch (error) {
+    console.error('Error al obtener la mano:', error);
+    throw new Error(`Error al obtener la mano: ${error}`);
+  }
+};
+
+export const listPlayerHands = async (sala, nick, filter = null) => {
+  try {
+    return await invoke('list_player_hands', { sala, nick, filter });
+  } catch (error) {
+    console.error('Error al listar las manos del jugador:', error);
+    throw new Error(`Error al listar las manos del jugador: ${error}`);
+  }
+};
+
+export const exportHands = async (sala, handIds, options = null) => {
+  try {
+    return await invoke('export_hands', { sala, handIds, options });
+  } catch (error) {
+    console.error('Error al exportar manos:', error);
+    throw commandError('Error al exportar manos', error);
   }
 };
 